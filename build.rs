@@ -0,0 +1,56 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// Java sources for the classes that duchess bundles (see `src/bundled.rs`)
+const SOURCE_PATH: &str = "java";
+
+fn main() -> std::io::Result<()> {
+    println!("cargo:rerun-if-changed={}", SOURCE_PATH);
+
+    // Class files are written to `$OUT_DIR/java` and embedded into the library with `include_bytes!`.
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+    let target_dir = out_dir.join("java");
+
+    // The `java_package!` macro reflects on the classes it mirrors with `javap`, so they must be on the classpath.
+    println!("cargo:rustc-env=CLASSPATH={}", target_dir.display());
+
+    let mut sources = vec![];
+    collect_java_sources(Path::new(SOURCE_PATH), &mut sources)?;
+
+    let output = Command::new("javac")
+        .args([
+            "--release", // Keep the bundled classes loadable by any JVM that duchess supports
+            "8",
+            "-d", // Specify the target directory for class files. Javac will create all parents if needed
+            &target_dir.display().to_string(),
+            "-sourcepath", // Specify where to find other source files (e.g. dependencies)
+            SOURCE_PATH,
+        ])
+        .args(&sources)
+        .output()?;
+
+    if !output.status.success() {
+        panic!(
+            "failed to compile bundled Java sources: {}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+
+    Ok(())
+}
+
+/// Recursively collects all `.java` files beneath `dir`
+fn collect_java_sources(dir: &Path, sources: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_java_sources(&path, sources)?;
+        } else if path.extension().is_some_and(|e| e == "java") {
+            sources.push(path);
+        }
+    }
+    Ok(())
+}
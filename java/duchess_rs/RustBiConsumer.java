@@ -0,0 +1,14 @@
+package duchess_rs;
+
+import java.util.function.BiConsumer;
+
+/** A `java.util.function.BiConsumer` implemented by a Rust closure. */
+public final class RustBiConsumer extends RustClosure implements BiConsumer<Object, Object> {
+    public RustBiConsumer(long closure) {
+        super(closure);
+    }
+
+    public void accept(Object t, Object u) {
+        invoke(t, u);
+    }
+}
@@ -0,0 +1,14 @@
+package duchess_rs;
+
+import java.util.function.BiFunction;
+
+/** A `java.util.function.BiFunction` implemented by a Rust closure. */
+public final class RustBiFunction extends RustClosure implements BiFunction<Object, Object, Object> {
+    public RustBiFunction(long closure) {
+        super(closure);
+    }
+
+    public Object apply(Object t, Object u) {
+        return invoke(t, u);
+    }
+}
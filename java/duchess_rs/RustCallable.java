@@ -0,0 +1,14 @@
+package duchess_rs;
+
+import java.util.concurrent.Callable;
+
+/** A `java.util.concurrent.Callable` implemented by a Rust closure. */
+public final class RustCallable extends RustClosure implements Callable<Object> {
+    public RustCallable(long closure) {
+        super(closure);
+    }
+
+    public Object call() {
+        return invoke(null, null);
+    }
+}
@@ -0,0 +1,30 @@
+package duchess_rs;
+
+/**
+ * Base class of the Java objects that wrap Rust closures (see the subclasses, one per functional
+ * interface). Holds a pointer to the boxed Rust closure, which is freed once this object has been
+ * garbage collected (see {@link RustObjectCleaner}).
+ */
+public abstract class RustClosure {
+    private final long closure;
+
+    RustClosure(long closure) {
+        this.closure = closure;
+        RustObjectCleaner.register(this, () -> drop(closure));
+    }
+
+    /** Invokes the Rust closure with the given arguments (unused arguments are null). */
+    final Object invoke(Object a, Object b) {
+        try {
+            return call(closure, a, b);
+        } finally {
+            // Keep this object (and thus the closure) alive until the call has returned.
+            synchronized (this) {
+            }
+        }
+    }
+
+    private static native Object call(long closure, Object a, Object b);
+
+    private static native void drop(long closure);
+}
@@ -0,0 +1,17 @@
+package duchess_rs;
+
+import java.util.concurrent.CompletableFuture;
+
+/**
+ * The `CompletableFuture` returned to Java by an async native method implemented with
+ * duchess. It is completed by duchess once the Rust future finishes.
+ */
+public class RustCompletableFuture<T> extends CompletableFuture<T> {
+    public RustCompletableFuture() {
+    }
+
+    /** Completes this future with `null`, which duchess can't pass as an argument to `complete`. */
+    public boolean completeNull() {
+        return complete(null);
+    }
+}
@@ -0,0 +1,14 @@
+package duchess_rs;
+
+import java.util.function.Consumer;
+
+/** A `java.util.function.Consumer` implemented by a Rust closure. */
+public final class RustConsumer extends RustClosure implements Consumer<Object> {
+    public RustConsumer(long closure) {
+        super(closure);
+    }
+
+    public void accept(Object t) {
+        invoke(t, null);
+    }
+}
@@ -0,0 +1,14 @@
+package duchess_rs;
+
+import java.util.function.Function;
+
+/** A `java.util.function.Function` implemented by a Rust closure. */
+public final class RustFunction extends RustClosure implements Function<Object, Object> {
+    public RustFunction(long closure) {
+        super(closure);
+    }
+
+    public Object apply(Object t) {
+        return invoke(t, null);
+    }
+}
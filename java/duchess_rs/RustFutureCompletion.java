@@ -0,0 +1,60 @@
+package duchess_rs;
+
+import java.util.concurrent.CompletionException;
+import java.util.concurrent.atomic.AtomicLong;
+import java.util.function.BiConsumer;
+
+/**
+ * Completion callback that duchess registers on a `CompletableFuture` to wake up the
+ * Rust future awaiting it. Holds a pointer to the Rust end of a channel, which is
+ * consumed (and freed) by the first completion, or once this callback has been garbage
+ * collected without ever having been invoked (see {@link RustObjectCleaner}).
+ */
+public final class RustFutureCompletion implements BiConsumer<Object, Throwable> {
+    /** Shared with the cleanup, which must not refer to this callback. */
+    private final AtomicLong sender;
+
+    public RustFutureCompletion(long sender) {
+        AtomicLong cell = new AtomicLong(sender);
+        this.sender = cell;
+        RustObjectCleaner.register(this, () -> {
+            long unused = cell.getAndSet(0);
+            if (unused != 0) {
+                drop(unused);
+            }
+        });
+    }
+
+    /**
+     * Gives the sender back to Rust if this callback hasn't consumed it yet (e.g. because it
+     * couldn't be registered), returning 0 otherwise.
+     */
+    public long detach() {
+        return sender.getAndSet(0);
+    }
+
+    public void accept(Object value, Throwable exception) {
+        long sender = this.sender.getAndSet(0);
+        if (sender == 0) {
+            return;
+        }
+        if (exception instanceof CompletionException && exception.getCause() != null) {
+            exception = exception.getCause();
+        }
+        if (exception != null) {
+            completeExceptionally(sender, exception);
+        } else if (value != null) {
+            complete(sender, value);
+        } else {
+            completeNull(sender);
+        }
+    }
+
+    private static native void complete(long sender, Object value);
+
+    private static native void completeNull(long sender);
+
+    private static native void completeExceptionally(long sender, Throwable exception);
+
+    private static native void drop(long sender);
+}
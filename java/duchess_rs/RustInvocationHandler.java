@@ -0,0 +1,110 @@
+package duchess_rs;
+
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.MethodType;
+import java.lang.reflect.Constructor;
+import java.lang.reflect.InvocationHandler;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.lang.reflect.Proxy;
+
+/**
+ * Implements a Java interface with a Rust value: the methods of the proxies created by
+ * `newProxy` are dispatched (by name and descriptor) to the Rust value, except for the methods
+ * of `Object`, which use the identity of the proxy, and the default methods that the Rust value
+ * does not implement, which run their default body. Holds a pointer to the boxed Rust value, which is freed
+ * once this handler has been garbage collected (see {@link RustObjectCleaner}).
+ */
+public final class RustInvocationHandler implements InvocationHandler {
+    /** `InvocationHandler.invokeDefault`, or `null` before Java 16. */
+    private static final Method INVOKE_DEFAULT = findInvokeDefault();
+
+    private final long handler;
+
+    private RustInvocationHandler(long handler) {
+        this.handler = handler;
+        RustObjectCleaner.register(this, () -> drop(handler));
+    }
+
+    /** Creates a proxy implementing `iface`, taking ownership of `handler` (`iface` is a raw type for the sake of duchess, which erases `Class`). */
+    public static Object newProxy(Class iface, long handler) {
+        return Proxy.newProxyInstance(
+            iface.getClassLoader(),
+            new Class<?>[] { iface },
+            new RustInvocationHandler(handler));
+    }
+
+    public Object invoke(Object proxy, Method method, Object[] args) throws Throwable {
+        if (method.getDeclaringClass() == Object.class) {
+            switch (method.getName()) {
+                case "equals":
+                    return proxy == args[0];
+                case "hashCode":
+                    return System.identityHashCode(proxy);
+                default:
+                    return proxy.getClass().getInterfaces()[0].getName() + "@" + Integer.toHexString(System.identityHashCode(proxy));
+            }
+        }
+
+        String key = method.getName()
+            + MethodType.methodType(method.getReturnType(), method.getParameterTypes()).toMethodDescriptorString();
+        try {
+            if (method.isDefault() && !implemented(handler, key)) {
+                return invokeDefault(proxy, method, args == null ? new Object[0] : args);
+            }
+            return call(handler, key, args == null ? new Object[0] : args);
+        } finally {
+            // Keep this handler (and thus the Rust value) alive until the call has returned.
+            synchronized (this) {
+            }
+        }
+    }
+
+    /** Runs the body of the default `method` on `proxy`, like `InvocationHandler.invokeDefault` (added in Java 16). */
+    private static Object invokeDefault(Object proxy, Method method, Object[] args) throws Throwable {
+        if (INVOKE_DEFAULT != null) {
+            try {
+                return INVOKE_DEFAULT.invoke(null, proxy, method, args);
+            } catch (InvocationTargetException e) {
+                throw e.getCause();
+            }
+        }
+
+        // Before Java 16, calling the default method with `invokespecial` requires private access to the interface.
+        Class<?> iface = method.getDeclaringClass();
+        return privateLookupIn(iface).unreflectSpecial(method, iface).bindTo(proxy).invokeWithArguments(args);
+    }
+
+    private static Method findInvokeDefault() {
+        try {
+            return InvocationHandler.class.getMethod("invokeDefault", Object.class, Method.class, Object[].class);
+        } catch (NoSuchMethodException e) {
+            return null;
+        }
+    }
+
+    private static MethodHandles.Lookup privateLookupIn(Class<?> iface) throws Throwable {
+        try {
+            // Java 9 to 15
+            Method privateLookupIn = MethodHandles.class.getMethod("privateLookupIn", Class.class, MethodHandles.Lookup.class);
+            try {
+                return (MethodHandles.Lookup) privateLookupIn.invoke(null, iface, MethodHandles.lookup());
+            } catch (InvocationTargetException e) {
+                throw e.getCause();
+            }
+        } catch (NoSuchMethodException e) {
+            // Java 8, whose lookups can only be created in another class through this private constructor
+            Constructor<MethodHandles.Lookup> constructor =
+                MethodHandles.Lookup.class.getDeclaredConstructor(Class.class, int.class);
+            constructor.setAccessible(true);
+            return constructor.newInstance(iface, MethodHandles.Lookup.PRIVATE);
+        }
+    }
+
+    /** Returns whether the Rust value implements `method`, given by its name and descriptor. */
+    private static native boolean implemented(long handler, String method);
+
+    private static native Object call(long handler, String method, Object[] args);
+
+    private static native void drop(long handler);
+}
@@ -0,0 +1,35 @@
+package duchess_rs;
+
+import java.util.Iterator;
+import java.util.NoSuchElementException;
+
+/**
+ * A `java.util.Iterator` over the items of a Rust iterator. The Rust closure returns the next item,
+ * or this iterator itself once there are no items left (items may be null).
+ */
+public final class RustIterator extends RustClosure implements Iterator<Object> {
+    private Object next;
+    private boolean fetched;
+
+    public RustIterator(long closure) {
+        super(closure);
+    }
+
+    public boolean hasNext() {
+        if (!fetched) {
+            next = invoke(this, null);
+            fetched = true;
+        }
+        return next != this;
+    }
+
+    public Object next() {
+        if (!hasNext()) {
+            throw new NoSuchElementException();
+        }
+        Object result = next;
+        next = null;
+        fetched = false;
+        return result;
+    }
+}
@@ -0,0 +1,31 @@
+package duchess_rs;
+
+import java.util.AbstractList;
+import java.util.List;
+import java.util.RandomAccess;
+import java.util.function.Function;
+
+/**
+ * A read-only `java.util.List` backed by a Rust `Vec`. Each element is converted by the Rust
+ * function `get` (which is passed the boxed index) when it is accessed.
+ */
+public final class RustList extends AbstractList<Object> implements List<Object>, RandomAccess {
+    private final int size;
+    private final Function<Object, Object> get;
+
+    public RustList(int size, Function<Object, Object> get) {
+        this.size = size;
+        this.get = get;
+    }
+
+    public Object get(int index) {
+        if (index < 0 || index >= size) {
+            throw new IndexOutOfBoundsException("Index: " + index + ", Size: " + size);
+        }
+        return get.apply(Integer.valueOf(index));
+    }
+
+    public int size() {
+        return size;
+    }
+}
@@ -0,0 +1,49 @@
+package duchess_rs;
+
+import java.util.AbstractMap;
+import java.util.Iterator;
+import java.util.Map;
+import java.util.Set;
+import java.util.function.Function;
+import java.util.function.Supplier;
+
+/**
+ * A read-only `java.util.Map` backed by a Rust `HashMap`. Lookups are done by the Rust function
+ * `get`, which returns null for keys that are absent (or not of the key type); `keys` returns a new
+ * `java.util.Iterator` over the keys of the map each time it is called.
+ */
+public final class RustMap extends AbstractMap<Object, Object> implements Map<Object, Object> {
+    private final Function<Object, Object> get;
+    private final int size;
+    private final Supplier<Object> keys;
+
+    public RustMap(Function<Object, Object> get, int size, Supplier<Object> keys) {
+        this.get = get;
+        this.size = size;
+        this.keys = keys;
+    }
+
+    public Object get(Object key) {
+        if (key == null) {
+            return null;
+        }
+        return get.apply(key);
+    }
+
+    public boolean containsKey(Object key) {
+        return get(key) != null;
+    }
+
+    public int size() {
+        return size;
+    }
+
+    public Set<Map.Entry<Object, Object>> entrySet() {
+        return new RustMapEntrySet(this);
+    }
+
+    @SuppressWarnings("unchecked")
+    Iterator<Object> keyIterator() {
+        return (Iterator<Object>) keys.get();
+    }
+}
@@ -0,0 +1,25 @@
+package duchess_rs;
+
+import java.util.AbstractMap;
+import java.util.Iterator;
+import java.util.Map;
+
+/** Iterates over the entries of a `RustMap`, looking up the value of each key in turn. */
+final class RustMapEntryIterator implements Iterator<Map.Entry<Object, Object>> {
+    private final RustMap map;
+    private final Iterator<Object> keys;
+
+    RustMapEntryIterator(RustMap map, Iterator<Object> keys) {
+        this.map = map;
+        this.keys = keys;
+    }
+
+    public boolean hasNext() {
+        return keys.hasNext();
+    }
+
+    public Map.Entry<Object, Object> next() {
+        Object key = keys.next();
+        return new AbstractMap.SimpleImmutableEntry<Object, Object>(key, map.get(key));
+    }
+}
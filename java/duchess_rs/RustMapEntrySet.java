@@ -0,0 +1,22 @@
+package duchess_rs;
+
+import java.util.AbstractSet;
+import java.util.Iterator;
+import java.util.Map;
+
+/** The entry set of a `RustMap`, whose entries are looked up as they are iterated over. */
+final class RustMapEntrySet extends AbstractSet<Map.Entry<Object, Object>> {
+    private final RustMap map;
+
+    RustMapEntrySet(RustMap map) {
+        this.map = map;
+    }
+
+    public Iterator<Map.Entry<Object, Object>> iterator() {
+        return new RustMapEntryIterator(map, map.keyIterator());
+    }
+
+    public int size() {
+        return map.size();
+    }
+}
@@ -0,0 +1,35 @@
+package duchess_rs;
+
+/**
+ * Base class of the peer classes that duchess generates for Rust values (one per Rust type and
+ * interface). Holds a pointer to the Rust value; the native methods of the peer class find the
+ * value through it.
+ *
+ * <p>{@link #close} drops the Rust value right away, after which calls to the native methods
+ * throw. Otherwise, the value is dropped once the peer has been garbage collected (see {@link
+ * RustObjectCleaner}).
+ */
+public abstract class RustObject implements AutoCloseable {
+    private final long handle;
+
+    protected RustObject(long handle) {
+        this.handle = handle;
+        RustObjectCleaner.register(this, handle);
+    }
+
+    /** Drops the Rust value. Does nothing if it has already been dropped. */
+    @Override
+    public final void close() {
+        try {
+            release(handle);
+        } finally {
+            // Keep this object (and thus the handle) alive until the call has returned.
+            synchronized (this) {
+            }
+        }
+    }
+
+    private static native void release(long handle);
+
+    static native void drop(long handle);
+}
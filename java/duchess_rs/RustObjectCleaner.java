@@ -0,0 +1,55 @@
+package duchess_rs;
+
+import java.lang.ref.PhantomReference;
+import java.lang.ref.Reference;
+import java.lang.ref.ReferenceQueue;
+import java.util.concurrent.ConcurrentHashMap;
+
+/**
+ * Drops Rust values owned by Java objects (e.g. {@link RustObject}s and {@link RustClosure}s) once
+ * the objects have been garbage collected. This works like {@code java.lang.ref.Cleaner}, which is not available in Java
+ * 8: a daemon thread waits for the objects to become phantom reachable.
+ */
+public final class RustObjectCleaner implements Runnable {
+    private static final ReferenceQueue<Object> QUEUE = new ReferenceQueue<>();
+
+    /** The cleanup of each registered object, which also keeps the references reachable. */
+    private static final ConcurrentHashMap<Reference<?>, Runnable> CLEANUPS = new ConcurrentHashMap<>();
+
+    static {
+        Thread thread = new Thread(new RustObjectCleaner(), "duchess-rust-object-cleaner");
+        thread.setDaemon(true);
+        thread.start();
+    }
+
+    private RustObjectCleaner() {}
+
+    /** Drops the Rust value that {@code handle} points to once {@code object} is unreachable. */
+    public static void register(Object object, long handle) {
+        register(object, () -> RustObject.drop(handle));
+    }
+
+    /**
+     * Runs {@code cleanup} once {@code object} is unreachable. The cleanup must not refer to {@code
+     * object}, or it would keep it reachable.
+     */
+    static void register(Object object, Runnable cleanup) {
+        CLEANUPS.put(new PhantomReference<>(object, QUEUE), cleanup);
+    }
+
+    @Override
+    public void run() {
+        while (true) {
+            try {
+                Runnable cleanup = CLEANUPS.remove(QUEUE.remove());
+                if (cleanup != null) {
+                    cleanup.run();
+                }
+            } catch (InterruptedException e) {
+                // keep cleaning
+            } catch (Throwable e) {
+                // e.g. a panic while dropping a value; keep cleaning the other objects
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+package duchess_rs;
+
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.List;
+import java.util.regex.Matcher;
+import java.util.regex.Pattern;
+
+/**
+ * Thrown when a Rust function invoked from Java (e.g., a native method implemented
+ * with duchess) panics. The message is the panic payload and, if Rust backtraces
+ * were enabled (e.g. via `RUST_BACKTRACE=1`), the Rust frames are prepended to the
+ * Java stack trace.
+ */
+public class RustPanicException extends RuntimeException {
+    private final String rustBacktrace;
+
+    public RustPanicException(String message) {
+        this(message, null);
+    }
+
+    public RustPanicException(String message, String rustBacktrace) {
+        super(message);
+        this.rustBacktrace = rustBacktrace == null || rustBacktrace.isEmpty() ? null : rustBacktrace;
+        if (this.rustBacktrace != null) {
+            StackTraceElement[] rustFrames = parseBacktrace(rustBacktrace);
+            StackTraceElement[] javaFrames = getStackTrace();
+            StackTraceElement[] frames = Arrays.copyOf(rustFrames, rustFrames.length + javaFrames.length);
+            System.arraycopy(javaFrames, 0, frames, rustFrames.length, javaFrames.length);
+            setStackTrace(frames);
+        }
+    }
+
+    /** The Rust backtrace captured when the panic occurred, as rendered by Rust, or null. */
+    public String getRustBacktrace() {
+        return rustBacktrace;
+    }
+
+    // Rust renders backtraces as a sequence of frames like
+    //
+    //    3: my_crate::module::function
+    //              at ./src/module.rs:22:5
+    //
+    // where the `at` line is only present if debug info is available.
+    private static final Pattern SYMBOL = Pattern.compile("^\\s*\\d+:\\s+(.*)$");
+    private static final Pattern LOCATION = Pattern.compile("^\\s*at\\s+(.*):(\\d+)(?::\\d+)?$");
+
+    private static StackTraceElement[] parseBacktrace(String backtrace) {
+        List<StackTraceElement> frames = new ArrayList<>();
+        String symbol = null;
+        for (String line : backtrace.split("\n")) {
+            Matcher symbolMatcher = SYMBOL.matcher(line);
+            if (symbolMatcher.matches()) {
+                if (symbol != null) {
+                    frames.add(frame(symbol, null, -1));
+                }
+                symbol = symbolMatcher.group(1);
+                continue;
+            }
+
+            Matcher locationMatcher = LOCATION.matcher(line);
+            if (symbol != null && locationMatcher.matches()) {
+                frames.add(frame(symbol, locationMatcher.group(1), Integer.parseInt(locationMatcher.group(2))));
+                symbol = null;
+            }
+        }
+        if (symbol != null) {
+            frames.add(frame(symbol, null, -1));
+        }
+        return frames.toArray(new StackTraceElement[0]);
+    }
+
+    private static StackTraceElement frame(String symbol, String file, int line) {
+        // Split `a::b::c` into the "class" `a::b` and the "method" `c`.
+        int separator = symbol.lastIndexOf("::");
+        String declaringClass = separator < 0 ? "<rust>" : symbol.substring(0, separator);
+        String methodName = separator < 0 ? symbol : symbol.substring(separator + 2);
+        return new StackTraceElement(declaringClass, methodName, file, line);
+    }
+}
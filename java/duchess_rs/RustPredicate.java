@@ -0,0 +1,14 @@
+package duchess_rs;
+
+import java.util.function.Predicate;
+
+/** A `java.util.function.Predicate` implemented by a Rust closure. */
+public final class RustPredicate extends RustClosure implements Predicate<Object> {
+    public RustPredicate(long closure) {
+        super(closure);
+    }
+
+    public boolean test(Object t) {
+        return (Boolean) invoke(t, null);
+    }
+}
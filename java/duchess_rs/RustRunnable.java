@@ -0,0 +1,12 @@
+package duchess_rs;
+
+/** A `java.lang.Runnable` implemented by a Rust closure. */
+public final class RustRunnable extends RustClosure implements Runnable {
+    public RustRunnable(long closure) {
+        super(closure);
+    }
+
+    public void run() {
+        invoke(null, null);
+    }
+}
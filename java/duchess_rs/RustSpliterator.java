@@ -0,0 +1,42 @@
+package duchess_rs;
+
+import java.util.Spliterator;
+import java.util.function.Consumer;
+import java.util.stream.Stream;
+import java.util.stream.StreamSupport;
+
+/**
+ * A `java.util.Spliterator` over the items of a Rust iterator. The Rust closure returns the next
+ * item, or this spliterator itself once there are no items left (items may be null).
+ */
+public final class RustSpliterator extends RustClosure implements Spliterator<Object> {
+    public RustSpliterator(long closure) {
+        super(closure);
+    }
+
+    public boolean tryAdvance(Consumer<? super Object> action) {
+        Object next = invoke(this, null);
+        if (next == this) {
+            return false;
+        }
+        action.accept(next);
+        return true;
+    }
+
+    public Spliterator<Object> trySplit() {
+        return null;
+    }
+
+    public long estimateSize() {
+        return Long.MAX_VALUE;
+    }
+
+    public int characteristics() {
+        return ORDERED;
+    }
+
+    /** Returns a sequential stream over the remaining items. */
+    public Stream<Object> stream() {
+        return StreamSupport.stream(this, false);
+    }
+}
@@ -0,0 +1,14 @@
+package duchess_rs;
+
+import java.util.function.Supplier;
+
+/** A `java.util.function.Supplier` implemented by a Rust closure. */
+public final class RustSupplier extends RustClosure implements Supplier<Object> {
+    public RustSupplier(long closure) {
+        super(closure);
+    }
+
+    public Object get() {
+        return invoke(null, null);
+    }
+}
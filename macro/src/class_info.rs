@@ -163,6 +163,7 @@ impl ClassInfo {
     /// We always mirror things declared as public.
     /// In classes, the default privacy indicates "package level" visibility and we do not mirror.
     /// In interfaces, the default privacy indicates "public" visibility and we DO mirror.
+    /// Protected methods are only mirrored on the class's `Protected` view, for use by Rust subclasses.
     pub fn should_mirror_in_rust(&self, privacy: Privacy) -> bool {
         match (privacy, self.kind) {
             (Privacy::Public, _) | (Privacy::Default, ClassKind::Interface) => true,
@@ -172,6 +173,20 @@ impl ClassInfo {
             | (Privacy::Default, ClassKind::Class) => false,
         }
     }
+
+    /// Removes the protected methods, which `class Foo { * }` does not mirror on the `Protected` view
+    /// (their types may not be mirrored); they have to be listed explicitly.
+    pub fn without_protected_methods(&self) -> ClassInfo {
+        ClassInfo {
+            methods: self
+                .methods
+                .iter()
+                .filter(|m| m.flags.privacy != Privacy::Protected)
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Eq, Ord, PartialEq, PartialOrd, Clone, Debug)]
@@ -1,8 +1,8 @@
 use crate::{
     argument::DuchessDeclaration,
     class_info::{
-        ClassInfo, Constructor, DotId, Field, Id, Method, NonRepeatingType, RootMap,
-        SpannedPackageInfo, Type,
+        ClassInfo, ClassKind, ClassRef, Constructor, DotId, Field, Id, Method, NonRepeatingType,
+        Privacy, RootMap, SpannedPackageInfo, Type,
     },
     reflect::Reflector,
     signature::Signature,
@@ -48,7 +48,7 @@ impl SpannedPackageInfo {
         let class_tokens: TokenStream = self
             .classes
             .iter()
-            .map(|class_id| root_map.classes[class_id].to_tokens(&root_map.upcasts))
+            .map(|class_id| root_map.classes[class_id].to_tokens(root_map))
             .collect::<Result<_, _>>()?;
 
         let supers: Vec<TokenStream> = package_id
@@ -73,7 +73,8 @@ impl SpannedPackageInfo {
 }
 
 impl ClassInfo {
-    pub fn to_tokens(&self, upcasts: &Upcasts) -> syn::Result<TokenStream> {
+    pub fn to_tokens(&self, root_map: &RootMap) -> syn::Result<TokenStream> {
+        let upcasts = &root_map.upcasts;
         let struct_name = self.struct_name();
         let cached_class = self.cached_class();
         let this_ty = self.this_type();
@@ -84,7 +85,7 @@ impl ClassInfo {
         let constructors: Vec<_> = self
             .constructors
             .iter()
-            .map(|c| self.constructor(c))
+            .map(|c| self.constructor(root_map, c))
             .collect::<Result<_, _>>()?;
 
         // Convert static methods (not instance methods, those are different)
@@ -93,7 +94,7 @@ impl ClassInfo {
             .iter()
             .filter(|m| self.should_mirror_in_rust(m.flags.privacy))
             .filter(|m| m.flags.is_static)
-            .map(|m| self.static_method(m))
+            .map(|m| self.static_method(root_map, m))
             .collect::<Result<_, _>>()?;
 
         // Convert instance methods (not static methods, those are different)
@@ -102,7 +103,7 @@ impl ClassInfo {
             .iter()
             .filter(|m| self.should_mirror_in_rust(m.flags.privacy))
             .filter(|m| !m.flags.is_static)
-            .map(|m| self.op_struct_method(m))
+            .map(|m| self.op_struct_method(root_map, m))
             .collect::<Result<_, _>>()?;
 
         // Convert instance methods (not static methods, those are different)
@@ -111,7 +112,7 @@ impl ClassInfo {
             .iter()
             .filter(|m| self.should_mirror_in_rust(m.flags.privacy))
             .filter(|m| !m.flags.is_static)
-            .map(|m| self.obj_struct_method(m))
+            .map(|m| self.obj_struct_method(root_map, m))
             .collect::<Result<_, _>>()?;
 
         let assoc_struct_declarations = self.assoc_structs(upcasts, op_methods, obj_methods)?;
@@ -122,9 +123,12 @@ impl ClassInfo {
             .iter()
             .filter(|m| self.should_mirror_in_rust(m.flags.privacy))
             .filter(|m| !m.flags.is_static)
-            .map(|m| self.inherent_object_method(m))
+            .map(|m| self.inherent_object_method(root_map, m, false))
             .collect::<Result<_, _>>()?;
 
+        // Protected instance methods can only be called on the `Protected` view, from Rust subclasses
+        let protected_view = self.protected_view(root_map)?;
+
         // Generate static field getters
         let static_field_getters: Vec<_> = self
             .fields
@@ -206,6 +210,8 @@ impl ClassInfo {
                 // Other upcast impls
                 #upcast_impls
 
+                #protected_view
+
                 impl< #(#java_class_generics,)* > #this_ty
                 where
                     #(#java_class_generics: duchess::JavaObject,)*
@@ -345,6 +351,66 @@ impl ClassInfo {
         Ok(declarations)
     }
 
+    /// Constructs the `Protected` view of a class that is not `final`, which dereferences to the class
+    /// and has a method for each of its protected instance methods.
+    fn protected_view(&self, root_map: &RootMap) -> syn::Result<Option<TokenStream>> {
+        if self.kind != ClassKind::Class || self.flags.is_final {
+            return Ok(None);
+        }
+
+        let name = self.struct_name();
+        let java_class_generics = self.class_generic_names();
+        let this_ty = self.this_type();
+        let view_name =
+            Id::from(format!("ViewAs{}Protected", self.name.class_name())).to_ident(self.span);
+
+        let protected_methods: Vec<_> = self
+            .methods
+            .iter()
+            .filter(|m| m.flags.privacy == Privacy::Protected && !m.flags.is_static)
+            .map(|m| self.inherent_object_method(root_map, m, true))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Some(quote_spanned!(self.span =>
+            #[repr(transparent)]
+            pub struct #view_name<#(#java_class_generics,)*> {
+                this: #name<#(#java_class_generics,)*>,
+            }
+
+            impl<#(#java_class_generics,)*> duchess::plumbing::ProtectedView for #this_ty
+            where
+                #(#java_class_generics: duchess::JavaObject,)*
+            {
+                type Protected = #view_name<#(#java_class_generics,)*>;
+            }
+
+            impl<#(#java_class_generics,)*> duchess::plumbing::FromRef<#this_ty> for #view_name<#(#java_class_generics,)*> {
+                fn from_ref(this: &#this_ty) -> &Self {
+                    // This is safe because of the `#[repr(transparent)]`
+                    // on the struct declaration.
+                    unsafe {
+                        ::core::mem::transmute::<&#this_ty, &Self>(this)
+                    }
+                }
+            }
+
+            impl<#(#java_class_generics,)*> ::core::ops::Deref for #view_name<#(#java_class_generics,)*> {
+                type Target = #this_ty;
+
+                fn deref(&self) -> &#this_ty {
+                    &self.this
+                }
+            }
+
+            impl<#(#java_class_generics,)*> #view_name<#(#java_class_generics,)*>
+            where
+                #(#java_class_generics: duchess::JavaObject,)*
+            {
+                #(#protected_methods)*
+            }
+        )))
+    }
+
     /// Constructs the default "next" type for our [op struct].
     /// This is based on the method resolution order (mro) for the
     /// current type. For example, if `Foo` extends `Bar`, then the
@@ -420,7 +486,11 @@ impl ClassInfo {
         }
     }
 
-    fn constructor(&self, constructor: &Constructor) -> syn::Result<TokenStream> {
+    fn constructor(
+        &self,
+        root_map: &RootMap,
+        constructor: &Constructor,
+    ) -> syn::Result<TokenStream> {
         let mut sig = Signature::new(self.name.class_name(), self.span, &self.generics);
 
         let input_traits: Vec<_> = constructor
@@ -434,7 +504,10 @@ impl ClassInfo {
             .collect();
 
         let ty = self.this_type();
-        let output_trait = quote_spanned!(self.span => duchess::prelude::JavaConstructor<#ty>);
+        let declared = self.declared_exceptions(root_map, &mut sig, &constructor.throws)?;
+        let declared_bound = declared.bound();
+        let output_trait =
+            quote_spanned!(self.span => duchess::prelude::JavaConstructor<#ty> #declared_bound);
 
         let java_class_generics = self.class_generic_names();
 
@@ -447,6 +520,25 @@ impl ClassInfo {
         let name = Literal::string(&self.name.to_string());
         let descriptor = Literal::string(&constructor.descriptor());
 
+        let declared_impl = {
+            let items = declared.impl_items();
+            quote_spanned!(self.span =>
+                impl<
+                    #(#java_class_generics,)*
+                    #(#input_names,)*
+                > duchess::plumbing::DeclaredExceptions for Impl<
+                    #(#java_class_generics,)*
+                    #(#input_names,)*
+                >
+                where
+                    #(#java_class_generics: duchess::JavaObject,)*
+                    #(#input_names : #input_traits,)*
+                {
+                    #items
+                }
+            )
+        };
+
         let output = quote_spanned!(self.span =>
             pub fn new(
                 #(#input_names : impl #input_traits,)*
@@ -490,6 +582,8 @@ impl ClassInfo {
                     }
                 }
 
+                #declared_impl
+
                 impl<
                     #(#java_class_generics,)*
                     #(#input_names,)*
@@ -573,7 +667,7 @@ impl ClassInfo {
     ///
     /// NB. This function (particularly the JvmOp impl) has significant overlap with `static_method`
     /// and `static_field_getter`, so if you make changes here, you may well need changes there.
-    fn op_struct_method(&self, method: &Method) -> syn::Result<TokenStream> {
+    fn op_struct_method(&self, root_map: &RootMap, method: &Method) -> syn::Result<TokenStream> {
         let mut sig = Signature::new(&method.name, self.span, &self.generics)
             .with_internal_generics(&method.generics)?;
 
@@ -592,6 +686,9 @@ impl ClassInfo {
         // Rust method `-> impl JavaMethod<Foo>`, and this variable
         // would be `JavaMethod<Foo>`.
         let output_trait = sig.method_trait(&method.return_ty)?;
+        let declared = self.declared_exceptions(root_map, &mut sig, &method.throws)?;
+        let declared_bound = declared.bound();
+        let output_trait = quote_spanned!(self.span => #output_trait #declared_bound);
 
         let rust_method_name = Id::from(method.name.to_snake_case()).to_ident(self.span);
 
@@ -627,7 +724,7 @@ impl ClassInfo {
         Ok(inherent_method)
     }
 
-    fn obj_struct_method(&self, method: &Method) -> syn::Result<TokenStream> {
+    fn obj_struct_method(&self, root_map: &RootMap, method: &Method) -> syn::Result<TokenStream> {
         let mut sig = Signature::new(&method.name, self.span, &self.generics)
             .with_internal_generics(&method.generics)?;
 
@@ -646,6 +743,9 @@ impl ClassInfo {
         // Rust method `-> impl JavaMethod<Foo>`, and this variable
         // would be `JavaMethod<Foo>`.
         let output_trait = sig.method_trait(&method.return_ty)?;
+        let declared = self.declared_exceptions(root_map, &mut sig, &method.throws)?;
+        let declared_bound = declared.bound();
+        let output_trait = quote_spanned!(self.span => #output_trait #declared_bound);
 
         let rust_method_name = Id::from(method.name.to_snake_case()).to_ident(self.span);
 
@@ -681,7 +781,14 @@ impl ClassInfo {
         Ok(inherent_method)
     }
 
-    fn inherent_object_method(&self, method: &Method) -> syn::Result<TokenStream> {
+    /// Generates the inherent method `Foo::method(this, ...)`, or (if `receiver`) the method
+    /// `view.method(...)` on the `Protected` view of the class, which calls it on `&view.this`.
+    fn inherent_object_method(
+        &self,
+        root_map: &RootMap,
+        method: &Method,
+        receiver: bool,
+    ) -> syn::Result<TokenStream> {
         let mut sig = Signature::new(&method.name, self.span, &self.generics)
             .with_internal_generics(&method.generics)?;
 
@@ -704,6 +811,9 @@ impl ClassInfo {
         // Rust method `-> impl JavaMethod<Foo>`, and this variable
         // would be `JavaMethod<Foo>`.
         let output_trait = sig.method_trait(&method.return_ty)?;
+        let declared = self.declared_exceptions(root_map, &mut sig, &method.throws)?;
+        let declared_bound = declared.bound();
+        let output_trait = quote_spanned!(self.span => #output_trait #declared_bound);
 
         // The appropriate JNI function to call this method.
         let jni_call_fn = sig.jni_call_fn(&method.return_ty)?;
@@ -773,6 +883,22 @@ impl ClassInfo {
 
         // Implementation of `JvmOp` for `m` -- when executed, call the method
         // via JNI, after converting its arguments appropriately.
+        let declared_impl = {
+            let items = declared.impl_items();
+            quote_spanned!(self.span =>
+                impl<#(#method_struct_generics),*> duchess::plumbing::DeclaredExceptions
+                for #rust_method_type_name<#(#method_struct_generics),*>
+                where
+                    #this: duchess::prelude::IntoJava<#this_ty>,
+                    #(#input_names: #input_traits,)*
+                    #(#java_class_generics: duchess::JavaObject,)*
+                    #(#sig_where_clauses,)*
+                {
+                    #items
+                }
+            )
+        };
+
         let jvmop_impl = quote_spanned!(self.span =>
             impl<#(#method_struct_generics),*> ::core::marker::Copy
             for #rust_method_type_name<#(#method_struct_generics),*>
@@ -796,6 +922,8 @@ impl ClassInfo {
                 }
             }
 
+            #declared_impl
+
             impl<#(#method_struct_generics),*> duchess::prelude::JvmOp
             for #rust_method_type_name<#(#method_struct_generics),*>
             where
@@ -859,27 +987,51 @@ impl ClassInfo {
             )
         });
 
-        let inherent_method = quote_spanned!(self.span =>
-            pub fn #rust_method_name<#(#rust_method_generics),*>(
-                #this: impl duchess::prelude::IntoJava<#this_ty>,
-                #(#input_names: impl #input_traits),*
-            ) -> impl #output_trait
-            where
-                #(#sig_where_clauses,)*
-            {
-                #method_struct
+        let inherent_method = if receiver {
+            quote_spanned!(self.span =>
+                pub fn #rust_method_name<'a, #(#rust_method_generics),*>(
+                    &'a self,
+                    #(#input_names: impl #input_traits + 'a),*
+                ) -> impl #output_trait + 'a
+                where
+                    #(#sig_where_clauses,)*
+                {
+                    #method_struct
 
-                #jvmop_impl
+                    #jvmop_impl
 
-                #deref_impl
+                    #deref_impl
 
-                #rust_method_type_name {
-                    #this: #this,
-                    #(#input_names: #input_names,)*
-                    phantom: ::core::default::Default::default(),
+                    #rust_method_type_name {
+                        #this: &self.#this,
+                        #(#input_names: #input_names,)*
+                        phantom: ::core::default::Default::default(),
+                    }
                 }
-            }
-        );
+            )
+        } else {
+            quote_spanned!(self.span =>
+                pub fn #rust_method_name<#(#rust_method_generics),*>(
+                    #this: impl duchess::prelude::IntoJava<#this_ty>,
+                    #(#input_names: impl #input_traits),*
+                ) -> impl #output_trait
+                where
+                    #(#sig_where_clauses,)*
+                {
+                    #method_struct
+
+                    #jvmop_impl
+
+                    #deref_impl
+
+                    #rust_method_type_name {
+                        #this: #this,
+                        #(#input_names: #input_names,)*
+                        phantom: ::core::default::Default::default(),
+                    }
+                }
+            )
+        };
 
         Ok(inherent_method)
     }
@@ -889,7 +1041,7 @@ impl ClassInfo {
     ///
     /// NB. This function (particularly the JvmOp impl) has significant overlap with `object_method`
     /// and `static_field_getter`, so if you make changes here, you may well need changes there.
-    fn static_method(&self, method: &Method) -> syn::Result<TokenStream> {
+    fn static_method(&self, root_map: &RootMap, method: &Method) -> syn::Result<TokenStream> {
         assert!(method.flags.is_static);
 
         let mut sig = Signature::new(&method.name, self.span, &self.generics)
@@ -907,6 +1059,9 @@ impl ClassInfo {
 
         let output_ty = sig.output_type(&method.return_ty)?;
         let output_trait = sig.method_trait(&method.return_ty)?;
+        let declared = self.declared_exceptions(root_map, &mut sig, &method.throws)?;
+        let declared_bound = declared.bound();
+        let output_trait = quote_spanned!(self.span => #output_trait #declared_bound);
         let jni_call_fn = sig.jni_static_call_fn(&method.return_ty)?;
 
         // If this method returns a java object, then this is the
@@ -965,6 +1120,21 @@ impl ClassInfo {
         // Implementation of `JvmOp` for `m` -- when executed, call the method
         // via JNI, after converting its arguments appropriately.
         let this_ty = self.this_type();
+        let declared_impl = {
+            let items = declared.impl_items();
+            quote_spanned!(self.span =>
+                impl<#(#method_struct_generics),*> duchess::plumbing::DeclaredExceptions
+                for #rust_method_type_name<#(#method_struct_generics),*>
+                where
+                    #(#input_names: #input_traits,)*
+                    #(#java_class_generics: duchess::JavaObject,)*
+                    #(#sig_where_clauses,)*
+                {
+                    #items
+                }
+            )
+        };
+
         let jvmop_impl = quote_spanned!(self.span =>
             impl<#(#method_struct_generics),*> ::core::marker::Copy
             for #rust_method_type_name<#(#method_struct_generics),*>
@@ -987,6 +1157,8 @@ impl ClassInfo {
                 }
             }
 
+            #declared_impl
+
             impl<#(#method_struct_generics),*> duchess::prelude::JvmOp
             for #rust_method_type_name<#(#method_struct_generics),*>
             where
@@ -1221,6 +1393,79 @@ impl ClassInfo {
         jni_c_str(self.name.to_jni_name(), self.span)
     }
 
+    /// Returns the exceptions in a `throws` clause as caught by `catch_declared`.
+    ///
+    /// If there are more than four of them, or some of them are not declared in this
+    /// `java_package!` (and so have no Rust type we can name), they are caught as a
+    /// `Throwable` instead, checking the class names at runtime. A type parameter like
+    /// `throws X` catches any `Throwable`.
+    fn declared_exceptions(
+        &self,
+        root_map: &RootMap,
+        sig: &mut Signature,
+        throws: &[ClassRef],
+    ) -> syn::Result<DeclaredExceptions> {
+        let all_mirrored = throws
+            .iter()
+            .all(|c| root_map.find_class(&c.name).is_some());
+        let (caught_ty, try_catch_body) = match throws {
+            [] => (
+                quote_spanned!(self.span => ::core::convert::Infallible),
+                quote_spanned!(self.span => {
+                    let _ = (jvm, exception);
+                    Ok(None)
+                }),
+            ),
+            [class_ref] if all_mirrored => {
+                let exception_ty = sig.forbid_capture(|sig| sig.class_ref_ty(class_ref))?;
+                (
+                    quote_spanned!(self.span => duchess::Local<'jvm, #exception_ty>),
+                    quote_spanned!(self.span => {
+                        duchess::plumbing::catch_as::<#exception_ty>(jvm, exception)
+                    }),
+                )
+            }
+            [_, _] | [_, _, _] | [_, _, _, _] if all_mirrored => {
+                let exception_tys: Vec<_> = throws
+                    .iter()
+                    .map(|c| sig.forbid_capture(|sig| sig.class_ref_ty(c)))
+                    .collect::<Result<_, _>>()?;
+                let caught = Ident::new(&format!("Caught{}", throws.len()), self.span);
+                (
+                    quote_spanned!(self.span => duchess::#caught<'jvm, #(#exception_tys,)*>),
+                    quote_spanned!(self.span => {
+                        <(#(#exception_tys,)*) as duchess::Catchable>::try_catch(jvm, exception)
+                    }),
+                )
+            }
+            _ => {
+                let class_names: Vec<_> = throws
+                    .iter()
+                    .map(|c| {
+                        let (package_name, _) = c.name.split();
+                        if package_name.is_empty() {
+                            // A type parameter, like `throws X`
+                            jni_c_str(DotId::throwable().to_jni_name(), self.span)
+                        } else {
+                            jni_c_str(c.name.to_jni_name(), self.span)
+                        }
+                    })
+                    .collect();
+                (
+                    quote_spanned!(self.span => duchess::Local<'jvm, duchess::java::lang::Throwable>),
+                    quote_spanned!(self.span => {
+                        duchess::plumbing::catch_named(jvm, exception, &[#(#class_names,)*])
+                    }),
+                )
+            }
+        };
+        Ok(DeclaredExceptions {
+            span: self.span,
+            caught_ty,
+            try_catch_body,
+        })
+    }
+
     fn prepare_inputs(&self, input_names: &[Ident], input_types: &[Type]) -> Vec<TokenStream> {
         input_names
             .iter()
@@ -1250,3 +1495,34 @@ fn jni_c_str(contents: impl Into<String>, span: Span) -> TokenStream {
     let byte_string = Literal::byte_string(&contents);
     quote_spanned!(span => unsafe { ::core::ffi::CStr::from_bytes_with_nul_unchecked(#byte_string) })
 }
+
+/// The exceptions declared in a `throws` clause, exposed through a `DeclaredExceptions` impl on the generated op.
+struct DeclaredExceptions {
+    span: Span,
+    caught_ty: TokenStream,
+    try_catch_body: TokenStream,
+}
+
+impl DeclaredExceptions {
+    /// The bound to add to the `impl Trait` returned by the generated method.
+    fn bound(&self) -> TokenStream {
+        let caught_ty = &self.caught_ty;
+        quote_spanned!(self.span =>
+            + for<'jvm> duchess::plumbing::DeclaredExceptions<Caught<'jvm> = #caught_ty>
+        )
+    }
+
+    /// The body of the generated op's `DeclaredExceptions` impl.
+    fn impl_items(&self) -> TokenStream {
+        let caught_ty = &self.caught_ty;
+        let try_catch_body = &self.try_catch_body;
+        quote_spanned!(self.span =>
+            type Caught<'jvm> = #caught_ty;
+
+            fn try_catch<'jvm>(
+                jvm: &mut duchess::Jvm<'jvm>,
+                exception: &duchess::Local<'jvm, duchess::java::lang::Throwable>,
+            ) -> duchess::Result<'jvm, Option<Self::Caught<'jvm>>> #try_catch_body
+        )
+    }
+}
@@ -460,9 +460,10 @@ fn check_all_extend_root<'a>(
     upcasts: &Upcasts,
 ) -> Result<(), syn::Error> {
     if let Some(child) = variants.into_iter().find(|c| {
-        !upcasts
-            .upcasts_for_generated_class(&c.class_name())
-            .contains(&root.this_ref())
+        c.class_name() != root.name
+            && !upcasts
+                .upcasts_for_generated_class(&c.class_name())
+                .contains(&root.this_ref())
     }) {
         Err(syn::Error::new(
             child.class_span(),
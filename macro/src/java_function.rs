@@ -1,7 +1,7 @@
 use std::{iter::once, sync::Arc};
 
-use proc_macro2::{Ident, Literal, TokenStream};
-use quote::quote_spanned;
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
 
 use crate::{
@@ -30,6 +30,14 @@ use crate::{
 ///     * a Rust reference `&J` to the Java object
 ///     * a Rust value of some type `R` which can be produced from `J` via the `JvmOp::to_rust` method.
 ///
+/// The Rust function may also have an argument of type `&mut Jvm<'_>`, which is given the JVM that invoked it.
+///
+/// # Async functions
+///
+/// If the Java method returns a `java.util.concurrent.CompletableFuture<T>`, the Rust function may be an `async fn`
+/// (or return `impl Future`). Java then immediately receives an incomplete future, which is completed with the
+/// output of the Rust future once it resolves. The Rust future must be `Send + 'static`.
+///
 /// # More details
 ///
 /// See the [Duchess book](https://duchess-rs.github.io/duchess/java_function.html) for more details.
@@ -72,10 +80,11 @@ pub fn java_function(selector: MethodSelector, input: syn::ItemFn) -> syn::Resul
     let rust_arguments = driver.rust_arguments(&this_name, &user_argument_names)?;
 
     // The "main body" of the call -- invoke the decorated function with the appropriate arguments.
-    let rust_invocation = quote_spanned!(span =>
-        #input_fn_name(
-            #(#rust_arguments),*
-        )
+    // Converting the arguments may fail, in which case the error is propagated (and thrown as a Java exception).
+    let rust_invocation = fallible_invocation(
+        span,
+        quote_spanned!(span => #input_fn_name),
+        &rust_arguments,
     );
 
     // Wrap that "main body" with whatever we need to convert the returned value back
@@ -234,8 +243,9 @@ impl Driver<'_> {
 
             let java_ty = self.convert_ty(argument_ty)?;
             let ty = match argument_ty {
+                // Java may pass `null`
                 class_info::Type::Ref(_) | class_info::Type::Repeat(_) => {
-                    quote_spanned!(span => &#java_ty)
+                    quote_spanned!(span => Option<&#java_ty>)
                 }
 
                 class_info::Type::Scalar(_) => java_ty,
@@ -251,10 +261,13 @@ impl Driver<'_> {
         &self,
         this_name: &Ident,
         user_names: &[&Ident],
-    ) -> syn::Result<Vec<TokenStream>> {
+    ) -> syn::Result<Vec<RustArgument>> {
         // Extract the `syn::PatType` version of the inputs. Error if `&self` etc is used.
+        // An argument of type `&mut Jvm<'_>` is not mapped to a Java argument; it is given the
+        // `Jvm` that the native function was invoked with. We remember its position.
         let mut input_rust_arguments = vec![];
-        for fn_arg in &self.input.sig.inputs {
+        let mut jvm_argument = None;
+        for (fn_arg, index) in self.input.sig.inputs.iter().zip(0..) {
             match fn_arg {
                 syn::FnArg::Receiver(r) => {
                     return Err(syn::Error::new(
@@ -262,6 +275,15 @@ impl Driver<'_> {
                         "Rust methods cannot be mapped to Java native functions",
                     ));
                 }
+                syn::FnArg::Typed(t) if is_jvm_type(&t.ty) => {
+                    if jvm_argument.is_some() {
+                        return Err(syn::Error::new(
+                            t.span(),
+                            "only one `&mut Jvm` argument is permitted",
+                        ));
+                    }
+                    jvm_argument = Some(index);
+                }
                 syn::FnArg::Typed(t) => {
                     input_rust_arguments.push(t);
                 }
@@ -319,49 +341,30 @@ impl Driver<'_> {
 
         // Push the `this` argument onto `output`
         if !self.method_info.flags.is_static {
-            output.push(self.rust_argument(this_name, false, inputs.next().unwrap())?);
+            output.push(RustArgument::Converted(rust_argument(
+                this_name,
+                false,
+                false,
+                inputs.next().unwrap(),
+            )?));
         }
 
         // Push each subsequent argument
         for (user_name, argument_ty) in user_names.iter().zip(&self.method_info.argument_tys) {
-            output.push(self.rust_argument(
+            output.push(RustArgument::Converted(rust_argument(
                 user_name,
                 argument_ty.is_scalar(),
+                true,
                 inputs.next().unwrap(),
-            )?);
+            )?));
         }
 
-        Ok(output)
-    }
-
-    fn rust_argument(
-        &self,
-        arg_name: &Ident,
-        java_ty_is_scalar: bool,
-        rust_ty: &syn::PatType,
-    ) -> syn::Result<TokenStream> {
-        // Case 1. Decorated Rust function has a `&J` type for this argument.
-        // In that case, we provide the Java object unchanged.
-        if let syn::Type::Reference(_) = &*rust_ty.ty {
-            // If the decorated Rust function argument type is a Rust reference
-            // (`&J`), then just pass the Java type directly.
-            if java_ty_is_scalar {
-                return Err(syn::Error::new(
-                        rust_ty.ty.span(),
-                        &format!("unexpected Rust reference; Java function declares a scalar type for this argument"),
-                    ));
-            }
-
-            return Ok(quote_spanned!(rust_ty.span() => #arg_name));
-        }
-
-        // Case 2. Java type is scalar. Then just pass it.
-        if java_ty_is_scalar {
-            return Ok(quote_spanned!(rust_ty.span() => #arg_name));
+        // Pass along the `Jvm`, if requested
+        if let Some(index) = jvm_argument {
+            output.insert(index, RustArgument::Jvm);
         }
 
-        // Case 3. Decorated Rust function has some Rust type; convert Java reference to that.
-        Ok(quote_spanned!(rust_ty.span() => duchess::JvmOp::to_rust(#arg_name).execute()))
+        Ok(output)
     }
 
     fn return_ty_and_expr(
@@ -370,22 +373,237 @@ impl Driver<'_> {
         env_name: &Ident,
     ) -> syn::Result<(TokenStream, TokenStream)> {
         let span = self.selector.span();
+
+        if self.is_async() {
+            let output_java_ty = self.completable_future_output_ty()?;
+            return Ok((
+                quote_spanned!(span => duchess::plumbing::jni_sys::jobject),
+                quote_spanned!(span => duchess::plumbing::native_function_returning_future::<#output_java_ty, _>(#env_name, |jvm| #return_expr)),
+            ));
+        }
+
         match &self.method_info.return_ty {
             Some(ty) => match ty {
-                class_info::Type::Scalar(ty) => Ok((
-                    ty.to_tokens(span),
-                    quote_spanned!(span => duchess::plumbing::native_function_returning_scalar(#env_name, || #return_expr)),
-                )),
+                class_info::Type::Scalar(ty) => {
+                    let ty = ty.to_tokens(span);
+                    Ok((
+                        ty.clone(),
+                        quote_spanned!(span => duchess::plumbing::native_function_returning_scalar::<#ty, _>(#env_name, |jvm| #return_expr)),
+                    ))
+                }
                 class_info::Type::Ref(_) | class_info::Type::Repeat(_) => {
                     let output_java_ty = self.convert_ty(ty)?;
                     Ok((
                         quote_spanned!(span => duchess::plumbing::jni_sys::jobject),
-                        quote_spanned!(span => duchess::plumbing::native_function_returning_object::<#output_java_ty, _>(#env_name, || #return_expr)),
+                        quote_spanned!(span => duchess::plumbing::native_function_returning_object::<#output_java_ty, _>(#env_name, |jvm| #return_expr)),
                     ))
                 }
             },
 
-            None => Ok((quote_spanned!(span => ()), return_expr)),
+            // The `()` is spanned at the call site rather than at the user's function, so that
+            // `clippy::unused_unit` doesn't flag the generated `-> ()`.
+            None => Ok((
+                quote!(()),
+                quote_spanned!(span => duchess::plumbing::native_function_returning_scalar::<(), _>(#env_name, |jvm| #return_expr)),
+            )),
+        }
+    }
+
+    /// True if the Rust function is an `async fn` or (syntactically) returns `impl Future`.
+    fn is_async(&self) -> bool {
+        if self.input.sig.asyncness.is_some() {
+            return true;
+        }
+        let syn::ReturnType::Type(_, ty) = &self.input.sig.output else {
+            return false;
+        };
+        let syn::Type::ImplTrait(impl_trait) = &**ty else {
+            return false;
+        };
+        impl_trait.bounds.iter().any(|bound| match bound {
+            syn::TypeParamBound::Trait(t) => {
+                t.path.segments.last().is_some_and(|s| s.ident == "Future")
+            }
+            _ => false,
+        })
+    }
+
+    /// For an async function, returns the Rust version of the `T` in the `CompletableFuture<T>`
+    /// that the Java method must return.
+    fn completable_future_output_ty(&self) -> syn::Result<TokenStream> {
+        if let Some(class_info::Type::Ref(class_info::RefType::Class(class_ref))) =
+            &self.method_info.return_ty
+        {
+            if class_ref.name.to_string() == "java.util.concurrent.CompletableFuture" {
+                if let [output_ty] = &class_ref.generics[..] {
+                    return self.convert_ty(&class_info::Type::Ref(output_ty.clone()));
+                }
+            }
         }
+
+        Err(syn::Error::new(
+            self.input.sig.ident.span(),
+            format!(
+                "async Rust functions can only implement Java methods returning `java.util.concurrent.CompletableFuture<T>`, but `{}` returns `{}`",
+                self.method_info.name,
+                match &self.method_info.return_ty {
+                    Some(ty) => ty.to_string(),
+                    None => "void".to_string(),
+                },
+            ),
+        ))
     }
 }
+
+/// An argument passed to the decorated Rust function.
+pub(crate) enum RustArgument {
+    /// The `&mut Jvm<'_>` that the native function was invoked with (in scope as `jvm`).
+    Jvm,
+
+    /// An expression converting a Java argument to the Rust type, which may use `jvm` and `?`.
+    Converted(TokenStream),
+}
+
+/// Evaluates `callee(arguments...)` to a `GlobalResult` of its value. Converted arguments are bound
+/// to variables first, so that they can borrow `jvm` before it is passed along as an argument.
+/// The closure is bound to a variable before it is called, since calling it directly trips
+/// `clippy::redundant_closure_call` in the user's crate.
+pub(crate) fn fallible_invocation(
+    span: Span,
+    callee: TokenStream,
+    arguments: &[RustArgument],
+) -> TokenStream {
+    let mut conversions = vec![];
+    let mut argument_exprs = vec![];
+    for (argument, index) in arguments.iter().zip(0..) {
+        match argument {
+            RustArgument::Jvm => argument_exprs.push(quote_spanned!(span => jvm)),
+            RustArgument::Converted(expr) => {
+                let name = syn::Ident::new(&format!("rust_arg{index}"), span);
+                conversions.push(quote_spanned!(span => let #name = #expr;));
+                argument_exprs.push(quote_spanned!(span => #name));
+            }
+        }
+    }
+
+    quote_spanned!(span => {
+        #[allow(unused_mut)]
+        let mut invoke = || -> duchess::GlobalResult<_> {
+            #(#conversions)*
+            Ok(#callee(#(#argument_exprs),*))
+        };
+        invoke()
+    })
+}
+
+/// Converts the Java argument `arg_name` into the Rust type of the corresponding argument `rust_ty`.
+/// If `nullable`, `arg_name` is an `Option<&J>`; otherwise it is a `&J`.
+pub(crate) fn rust_argument(
+    arg_name: &Ident,
+    java_ty_is_scalar: bool,
+    nullable: bool,
+    rust_ty: &syn::PatType,
+) -> syn::Result<TokenStream> {
+    let span = rust_ty.span();
+
+    // Case 1. Java type is scalar. Then just pass it.
+    if java_ty_is_scalar {
+        let unexpected = if let syn::Type::Reference(_) = &*rust_ty.ty {
+            Some("unexpected Rust reference")
+        } else if is_global_type(&rust_ty.ty) {
+            Some("unexpected `Global` reference")
+        } else {
+            None
+        };
+        if let Some(unexpected) = unexpected {
+            return Err(syn::Error::new(
+                rust_ty.ty.span(),
+                format!("{unexpected}; Java function declares a scalar type for this argument"),
+            ));
+        }
+
+        return Ok(quote_spanned!(span => #arg_name));
+    }
+
+    if !nullable {
+        return Ok(object_argument(
+            quote_spanned!(span => #arg_name),
+            &rust_ty.ty,
+        ));
+    }
+
+    // Case 2. Decorated Rust function has an `Option<T>` type for this argument.
+    // In that case, Java's `null` maps to `None`, and other values are converted to `T`.
+    if let Some(inner_ty) = option_type_argument(&rust_ty.ty) {
+        let value = object_argument(quote_spanned!(span => value), inner_ty);
+        return Ok(quote_spanned!(span => match #arg_name {
+            Some(value) => Some(#value),
+            None => None,
+        }));
+    }
+
+    // Case 3. Otherwise, passing `null` is an error (thrown as a `NullPointerException`).
+    Ok(object_argument(
+        quote_spanned!(span => #arg_name.ok_or(duchess::Error::NullDeref)?),
+        &rust_ty.ty,
+    ))
+}
+
+/// True if `ty` is (syntactically) `&mut Jvm<'_>`, possibly with a path like `duchess::Jvm`.
+pub(crate) fn is_jvm_type(ty: &syn::Type) -> bool {
+    let syn::Type::Reference(r) = ty else {
+        return false;
+    };
+    let syn::Type::Path(p) = &*r.elem else {
+        return false;
+    };
+    r.mutability.is_some()
+        && p.qself.is_none()
+        && p.path.segments.last().is_some_and(|s| s.ident == "Jvm")
+}
+
+/// Converts `value`, a non-null `&J` Java object, into the Rust type `ty` of the corresponding argument.
+fn object_argument(value: TokenStream, ty: &syn::Type) -> TokenStream {
+    let span = ty.span();
+
+    // If the decorated Rust function argument type is a Rust reference (`&J`), then just
+    // pass the Java object directly.
+    if let syn::Type::Reference(_) = ty {
+        return value;
+    }
+
+    // If it is `Global<J>`, we create a global reference to the Java object (e.g., so that an
+    // async function can hold on to it).
+    if is_global_type(ty) {
+        return quote_spanned!(span => duchess::plumbing::convert_argument(jvm, duchess::JvmOp::global(#value))?);
+    }
+
+    // Otherwise, it is some Rust type; convert the Java object to that.
+    quote_spanned!(span => duchess::plumbing::convert_argument(jvm, duchess::JvmOp::to_rust(#value))?)
+}
+
+/// If `ty` is (syntactically) `Option<T>`, returns `T`.
+fn option_type_argument(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(p) = ty else {
+        return None;
+    };
+    let segment = p.path.segments.last()?;
+    if p.qself.is_some() || segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.iter().collect::<Vec<_>>()[..] {
+        [syn::GenericArgument::Type(ty)] => Some(ty),
+        _ => None,
+    }
+}
+
+/// True if `ty` is (syntactically) `Global<J>`, possibly with a path like `duchess::Global`.
+fn is_global_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(p) = ty else {
+        return false;
+    };
+    p.qself.is_none() && p.path.segments.last().is_some_and(|s| s.ident == "Global")
+}
@@ -0,0 +1,349 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote_spanned;
+use syn::spanned::Spanned;
+
+use crate::{
+    argument::JavaPath,
+    class_info::{self, ClassInfo, ClassKind, Method, Type},
+    java_function::{fallible_invocation, is_jvm_type, rust_argument, RustArgument},
+    reflect::{InheritedMethod, Reflector},
+    signature::Signature,
+};
+
+/// Decorator applied to an `impl` block that implements a Java interface with a Rust type.
+///
+/// The `#[java_interface(X)]` takes an argument `X` naming the Java interface, e.g. `java.util.Comparator`.
+/// It decorates a block `impl I for T { ... }`, where `I` is the Rust version of that interface (including
+/// any type arguments, e.g. `java::util::Comparator<java::lang::String>`) and `T` is the Rust type implementing it.
+///
+/// The block must contain a method `&self` for each abstract method of the interface (including those inherited
+/// from its super-interfaces), named after the Java method in snake case. Its arguments and return value are converted in the same way as for a `java_function`.
+/// The macro implements `duchess::JavaInterface<I>` for `T`, so that `duchess::Proxy::new(t).to_java::<I>()`
+/// creates a Java object implementing `I`.
+pub fn java_interface(path: JavaPath, input: syn::ItemImpl) -> syn::Result<TokenStream> {
+    let span = path.span;
+
+    let reflector = Reflector::default();
+    let class_info = reflector.reflect(&path.to_dot_id(), span)?;
+    if class_info.kind != ClassKind::Interface {
+        return Err(syn::Error::new(
+            span,
+            format!("`{}` is not an interface", class_info.name),
+        ));
+    }
+
+    let Some((_, interface_ty, _)) = &input.trait_ else {
+        return Err(syn::Error::new(
+            input.impl_token.span(),
+            format!(
+                "expected `impl {} for T`, naming the Rust type that implements the interface",
+                class_info.name.class_name()
+            ),
+        ));
+    };
+    let self_ty = &input.self_ty;
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    // Within `invoke`, the interface's type parameters are aliases for the type arguments given by the user.
+    let type_aliases = type_aliases(&class_info, interface_ty)?;
+
+    let interface_methods = reflector.inherited_methods(&class_info)?;
+
+    let mut methods = vec![];
+    let mut implemented = vec![];
+    let mut keys = vec![];
+    let mut arms = vec![];
+    for item in &input.items {
+        let syn::ImplItem::Fn(item_fn) = item else {
+            return Err(syn::Error::new(
+                item.span(),
+                "only methods can implement a Java interface",
+            ));
+        };
+        let interface_method = implemented_method(&class_info, &interface_methods, item_fn)?;
+        let driver = Driver {
+            class_info: &class_info,
+            method_info: &interface_method.method,
+            descriptor: &interface_method.descriptor,
+            input: item_fn,
+        };
+        implemented.push(interface_method.key());
+        keys.push(driver.key());
+        arms.push(driver.match_arm()?);
+        methods.push(item_fn);
+    }
+
+    if let Some(missing) = interface_methods
+        .iter()
+        .find(|m| m.method.flags.is_abstract && !implemented.contains(&m.key()))
+    {
+        return Err(syn::Error::new(
+            self_ty.span(),
+            format!(
+                "missing method `{}`, which implements `{}`{}",
+                missing.method.name.to_snake_case(),
+                missing.method.name,
+                if missing.declared_in == class_info.name {
+                    String::new()
+                } else {
+                    format!(" (inherited from `{}`)", missing.declared_in)
+                }
+            ),
+        ));
+    }
+
+    let tokens = quote_spanned!(span =>
+        impl #impl_generics #self_ty #where_clause {
+            #(#methods)*
+        }
+
+        impl #impl_generics duchess::JavaInterface<#interface_ty> for #self_ty #where_clause {
+            const METHODS: &'static [&'static str] = &[#(#keys),*];
+
+            fn invoke<'jvm>(
+                &self,
+                jvm: &mut duchess::Jvm<'jvm>,
+                method: &str,
+                args: &duchess::java::Array<duchess::java::lang::Object>,
+            ) -> duchess::Result<'jvm, Option<duchess::Local<'jvm, duchess::java::lang::Object>>> {
+                #(#type_aliases)*
+
+                match method {
+                    #(#arms)*
+                    _ => duchess::plumbing::unsupported_method(jvm, method),
+                }
+            }
+        }
+    );
+
+    crate::debug_tokens(format!("{}", path), &tokens);
+
+    Ok(tokens)
+}
+
+/// Declares a type alias for each type parameter of the interface, naming the type argument that the user
+/// supplied for it in `interface_ty`.
+pub(crate) fn type_aliases(
+    class_info: &ClassInfo,
+    interface_ty: &syn::Path,
+) -> syn::Result<Vec<TokenStream>> {
+    let span = interface_ty.span();
+    let type_arguments: Vec<_> = match interface_ty.segments.last().map(|s| &s.arguments) {
+        Some(syn::PathArguments::AngleBracketed(args)) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    if type_arguments.len() != class_info.generics.len() {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "expected {} type argument(s) for `{}`, found {}",
+                class_info.generics.len(),
+                class_info.name,
+                type_arguments.len()
+            ),
+        ));
+    }
+
+    Ok(class_info
+        .generics
+        .iter()
+        .zip(type_arguments)
+        .map(|(generic, ty)| {
+            let ident = generic.to_ident(span);
+            quote_spanned!(span => #[allow(non_camel_case_types)] type #ident = #ty;)
+        })
+        .collect())
+}
+
+/// Finds the (unique) method of the interface or of its super-interfaces that `item_fn` implements.
+/// Methods declared in the interface itself hide the inherited methods with the same name, and abstract
+/// methods are preferred to their overloads with a default body.
+pub(crate) fn implemented_method<'a>(
+    class_info: &ClassInfo,
+    interface_methods: &'a [InheritedMethod],
+    item_fn: &syn::ImplItemFn,
+) -> syn::Result<&'a InheritedMethod> {
+    let name = &item_fn.sig.ident;
+    let candidates: Vec<_> = interface_methods
+        .iter()
+        .filter(|m| *name == *m.method.name.to_snake_case())
+        .collect();
+    let declared: Vec<_> = candidates
+        .iter()
+        .copied()
+        .filter(|m| m.declared_in == class_info.name)
+        .collect();
+    let candidates = if declared.is_empty() {
+        candidates
+    } else {
+        declared
+    };
+
+    // An overload with a default body doesn't have to be implemented, so it doesn't make the name ambiguous.
+    let abstract_candidates: Vec<_> = candidates
+        .iter()
+        .copied()
+        .filter(|m| m.method.flags.is_abstract)
+        .collect();
+    let candidates = if abstract_candidates.len() == 1 {
+        abstract_candidates
+    } else {
+        candidates
+    };
+    select_method(class_info, name, candidates)
+}
+
+/// Returns the only one of the `candidates` named `name`, or an error if there are none or several.
+fn select_method<'a, M>(
+    class_info: &ClassInfo,
+    name: &syn::Ident,
+    candidates: Vec<&'a M>,
+) -> syn::Result<&'a M> {
+    match candidates[..] {
+        [method] => Ok(method),
+        [] => Err(syn::Error::new(
+            name.span(),
+            format!("no method `{name}` in `{}`", class_info.name),
+        )),
+        _ => Err(syn::Error::new(
+            name.span(),
+            format!(
+                "`{}` has {} methods named `{name}`; overloaded methods cannot be implemented in Rust",
+                class_info.name,
+                candidates.len()
+            ),
+        )),
+    }
+}
+
+struct Driver<'a> {
+    class_info: &'a ClassInfo,
+    method_info: &'a Method,
+    descriptor: &'a str,
+    input: &'a syn::ImplItemFn,
+}
+
+impl Driver<'_> {
+    fn convert_ty(&self, ty: &Type) -> syn::Result<TokenStream> {
+        Signature::new(
+            &self.method_info.name,
+            self.input.sig.ident.span(),
+            &self.class_info.generics,
+        )
+        .forbid_capture(|sig| sig.java_ty(ty))
+    }
+
+    /// The name and descriptor of this method, which identify it in calls to `JavaInterface::invoke`.
+    fn key(&self) -> Literal {
+        Literal::string(&format!("{}{}", self.method_info.name, self.descriptor))
+    }
+
+    /// The arm of the `match` in `JavaInterface::invoke` that handles calls to this method.
+    fn match_arm(&self) -> syn::Result<TokenStream> {
+        let span = self.input.sig.ident.span();
+        let method_name = &self.input.sig.ident;
+        let key = self.key();
+
+        if let Some(asyncness) = &self.input.sig.asyncness {
+            return Err(syn::Error::new(
+                asyncness.span(),
+                "async methods cannot implement Java interfaces",
+            ));
+        }
+
+        // Extract the arguments of the Rust method, which must take `&self`.
+        let mut inputs = self.input.sig.inputs.iter();
+        match inputs.next() {
+            Some(syn::FnArg::Receiver(r)) if r.reference.is_some() && r.mutability.is_none() => {}
+            _ => {
+                return Err(syn::Error::new(
+                    self.input.sig.span(),
+                    "methods implementing a Java interface must take `&self`",
+                ))
+            }
+        }
+        let inputs: Vec<_> = inputs
+            .map(|fn_arg| match fn_arg {
+                syn::FnArg::Typed(t) => t,
+                syn::FnArg::Receiver(_) => unreachable!("receiver must come first"),
+            })
+            .collect();
+
+        let expected = self.method_info.argument_tys.len();
+        let found = inputs.iter().filter(|t| !is_jvm_type(&t.ty)).count();
+        if found != expected {
+            return Err(syn::Error::new(
+                self.input.sig.span(),
+                format!(
+                    "Rust method should have {expected} argument(s) (besides `&self`), to match `{}`",
+                    self.method_info.name
+                ),
+            ));
+        }
+
+        // Fetch each argument from the array passed by Java, then convert it to the Rust type.
+        let mut fetch_arguments = vec![];
+        let mut rust_arguments = vec![];
+        let mut argument_tys = self.method_info.argument_tys.iter().zip(0..);
+        for input in &inputs {
+            if is_jvm_type(&input.ty) {
+                rust_arguments.push(RustArgument::Jvm);
+                continue;
+            }
+
+            let (argument_ty, index) = argument_tys.next().unwrap();
+            let name = syn::Ident::new(&format!("arg{index}"), span);
+            let index = Literal::i32_unsuffixed(index);
+            let java_ty = self.convert_ty(argument_ty)?;
+            fetch_arguments.push(match argument_ty {
+                class_info::Type::Ref(_) | class_info::Type::Repeat(_) => quote_spanned!(span =>
+                    let #name = unsafe { duchess::plumbing::object_argument::<#java_ty>(jvm, args, #index) }?;
+                    let #name = #name.as_deref();
+                ),
+                class_info::Type::Scalar(_) => quote_spanned!(span =>
+                    let #name = unsafe { duchess::plumbing::scalar_argument::<#java_ty>(jvm, args, #index) }?;
+                ),
+            });
+            rust_arguments.push(RustArgument::Converted(rust_argument(
+                &name,
+                argument_ty.is_scalar(),
+                true,
+                input,
+            )?));
+        }
+
+        let result = match &self.method_info.return_ty {
+            Some(ty @ class_info::Type::Scalar(_)) => {
+                let ty = self.convert_ty(ty)?;
+                quote_spanned!(span => duchess::plumbing::scalar_result::<#ty, _>(jvm, result))
+            }
+            Some(ty) => {
+                let ty = self.convert_ty(ty)?;
+                quote_spanned!(span => duchess::plumbing::object_result::<#ty, _>(jvm, result))
+            }
+            None => quote_spanned!(span => duchess::plumbing::void_result(jvm, result)),
+        };
+
+        let invocation = fallible_invocation(
+            span,
+            quote_spanned!(span => self.#method_name),
+            &rust_arguments,
+        );
+
+        Ok(quote_spanned!(span =>
+            #key => {
+                #(#fetch_arguments)*
+                let result = #invocation;
+                #result
+            }
+        ))
+    }
+}
@@ -0,0 +1,328 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+use crate::{
+    argument::JavaPath,
+    class_info::{self, ClassInfo, ClassKind, Method, Type},
+    java_function::{fallible_invocation, is_jvm_type, rust_argument, RustArgument},
+    java_interface::{implemented_method, type_aliases},
+    reflect::{InheritedMethod, Reflector},
+    signature::Signature,
+};
+
+/// Decorator applied to an `impl` block that implements a Java interface with a Rust type whose values are
+/// handed to Java as `duchess::RustObject`s.
+///
+/// The `#[java_peer(X)]` takes an argument `X` naming the Java interface, e.g. `java.util.Iterator`.
+/// It decorates a block `impl I for T { ... }`, where `I` is the Rust version of that interface (including
+/// any type arguments) and `T` is the Rust type implementing it.
+///
+/// The block must contain a method for each abstract method of the interface, including those inherited from its
+/// super-interfaces (except `close()`, which `duchess_rs.RustObject` implements), named after the Java method in
+/// snake case. Each method takes `&self` or `&mut self`; its arguments and return value are converted in the same way as for a `java_function`.
+/// The macro implements `duchess::JavaPeer<I>` for `T`, so that `duchess::RustObject::new(t).to_java::<I>()`
+/// creates an instance of the peer class.
+pub fn java_peer(path: JavaPath, input: syn::ItemImpl) -> syn::Result<TokenStream> {
+    let span = path.span;
+
+    let reflector = Reflector::default();
+    let class_info = reflector.reflect(&path.to_dot_id(), span)?;
+    if class_info.kind != ClassKind::Interface {
+        return Err(syn::Error::new(
+            span,
+            format!("`{}` is not an interface", class_info.name),
+        ));
+    }
+
+    let Some((_, interface_ty, _)) = &input.trait_ else {
+        return Err(syn::Error::new(
+            input.impl_token.span(),
+            format!(
+                "expected `impl {} for T`, naming the Rust type that implements the interface",
+                class_info.name.class_name()
+            ),
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "generic Rust types cannot have Java peers",
+        ));
+    }
+    let self_ty = &input.self_ty;
+
+    // Within the native functions, the interface's type parameters are aliases for the type arguments given by the
+    // user.
+    let type_aliases = type_aliases(&class_info, interface_ty)?;
+
+    let interface_methods = reflector.inherited_methods(&class_info)?;
+
+    let mut methods = vec![];
+    let mut implemented = vec![];
+    let mut native_functions = vec![];
+    let mut java_functions = vec![];
+    for item in &input.items {
+        let syn::ImplItem::Fn(item_fn) = item else {
+            return Err(syn::Error::new(
+                item.span(),
+                "only methods can implement a Java interface",
+            ));
+        };
+        let interface_method = implemented_method(&class_info, &interface_methods, item_fn)?;
+        if is_close(interface_method) {
+            return Err(syn::Error::new(
+                item_fn.sig.ident.span(),
+                "`close` drops the Rust value; implement `Drop` to run code when it is closed",
+            ));
+        }
+        let driver = Driver {
+            class_info: &class_info,
+            method_info: &interface_method.method,
+            descriptor: &interface_method.descriptor,
+            input: item_fn,
+            self_ty,
+            interface_ty,
+        };
+        native_functions.push(driver.native_function()?);
+        java_functions.push(driver.java_function());
+        implemented.push(interface_method.key());
+        methods.push(item_fn);
+    }
+
+    if let Some(missing) = interface_methods
+        .iter()
+        .find(|m| m.method.flags.is_abstract && !is_close(m) && !implemented.contains(&m.key()))
+    {
+        return Err(syn::Error::new(
+            self_ty.span(),
+            format!(
+                "missing method `{}`, which implements `{}`{}",
+                missing.method.name.to_snake_case(),
+                missing.method.name,
+                if missing.declared_in == class_info.name {
+                    String::new()
+                } else {
+                    format!(" (inherited from `{}`)", missing.declared_in)
+                }
+            ),
+        ));
+    }
+
+    let tokens = quote_spanned!(span =>
+        impl #self_ty {
+            #(#methods)*
+        }
+
+        // The native functions are declared inside a `const _` block so that they are not nameable from Rust code.
+        #[allow(unused_variables, nonstandard_style)]
+        const _: () = {
+            #(#type_aliases)*
+
+            #(#native_functions)*
+
+            unsafe impl duchess::JavaPeer<#interface_ty> for #self_ty {
+                fn peer_class<'jvm>(
+                    jvm: &mut duchess::Jvm<'jvm>,
+                ) -> duchess::Result<'jvm, &'static duchess::plumbing::PeerClass> {
+                    static PEER_CLASS: duchess::plumbing::once_cell::sync::OnceCell<duchess::plumbing::PeerClass> =
+                        duchess::plumbing::once_cell::sync::OnceCell::new();
+                    PEER_CLASS.get_or_try_init(|| {
+                        duchess::plumbing::define_peer_class::<#interface_ty>(
+                            jvm,
+                            // SAFETY: the native functions match the signatures of the interface methods
+                            vec![#(unsafe { #java_functions }),*],
+                        )
+                    })
+                }
+            }
+        };
+    );
+
+    crate::debug_tokens(format!("{}", path), &tokens);
+
+    Ok(tokens)
+}
+
+/// True for `close()`, which `duchess_rs.RustObject` implements (e.g., for `java.lang.AutoCloseable`).
+fn is_close(method: &InheritedMethod) -> bool {
+    method.key() == "close()V"
+}
+
+struct Driver<'a> {
+    class_info: &'a ClassInfo,
+    method_info: &'a Method,
+    descriptor: &'a str,
+    input: &'a syn::ImplItemFn,
+    self_ty: &'a syn::Type,
+    interface_ty: &'a syn::Path,
+}
+
+impl Driver<'_> {
+    fn convert_ty(&self, ty: &Type) -> syn::Result<TokenStream> {
+        Signature::new(
+            &self.method_info.name,
+            self.input.sig.ident.span(),
+            &self.class_info.generics,
+        )
+        .forbid_capture(|sig| sig.java_ty(ty))
+    }
+
+    /// The name of the native function that implements this method.
+    fn native_name(&self) -> syn::Ident {
+        syn::Ident::new(
+            &format!("__duchess_{}", self.input.sig.ident),
+            self.input.sig.ident.span(),
+        )
+    }
+
+    /// The `JavaFunction` that registers the native function with the peer class.
+    fn java_function(&self) -> TokenStream {
+        let span = self.input.sig.ident.span();
+        let interface_ty = self.interface_ty;
+        let native_name = self.native_name();
+        let name = Literal::string(&self.method_info.name);
+        let descriptor = Literal::string(self.descriptor);
+        quote_spanned!(span =>
+            duchess::plumbing::JavaFunction::new(
+                #name,
+                #descriptor,
+                std::ptr::NonNull::new_unchecked(#native_name as *mut ()),
+                <#interface_ty as duchess::JavaObject>::class,
+            )
+        )
+    }
+
+    /// The native function that the JVM invokes for this method, which forwards to the Rust method.
+    fn native_function(&self) -> syn::Result<TokenStream> {
+        let span = self.input.sig.ident.span();
+        let method_name = &self.input.sig.ident;
+        let native_name = self.native_name();
+        let self_ty = self.self_ty;
+        let interface_ty = self.interface_ty;
+
+        if let Some(asyncness) = &self.input.sig.asyncness {
+            return Err(syn::Error::new(
+                asyncness.span(),
+                "async methods cannot implement Java interfaces",
+            ));
+        }
+
+        // Extract the arguments of the Rust method, which must take `&self` or `&mut self`.
+        let mut inputs = self.input.sig.inputs.iter();
+        let with = match inputs.next() {
+            Some(syn::FnArg::Receiver(r)) if r.reference.is_some() => {
+                if r.mutability.is_some() {
+                    quote_spanned!(span => with_mut)
+                } else {
+                    quote_spanned!(span => with)
+                }
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    self.input.sig.span(),
+                    "methods implementing a Java interface must take `&self` or `&mut self`",
+                ))
+            }
+        };
+        let inputs: Vec<_> = inputs
+            .map(|fn_arg| match fn_arg {
+                syn::FnArg::Typed(t) => t,
+                syn::FnArg::Receiver(_) => unreachable!("receiver must come first"),
+            })
+            .collect();
+
+        let expected = self.method_info.argument_tys.len();
+        let found = inputs.iter().filter(|t| !is_jvm_type(&t.ty)).count();
+        if found != expected {
+            return Err(syn::Error::new(
+                self.input.sig.span(),
+                format!(
+                    "Rust method should have {expected} argument(s) (besides `self`), to match `{}`",
+                    self.method_info.name
+                ),
+            ));
+        }
+
+        // The arguments of the native function are those of the Java method.
+        let mut argument_names = vec![];
+        let mut argument_tys = vec![];
+        for (argument_ty, index) in self.method_info.argument_tys.iter().zip(0..) {
+            argument_names.push(syn::Ident::new(&format!("arg{index}"), span));
+            let java_ty = self.convert_ty(argument_ty)?;
+            argument_tys.push(match argument_ty {
+                // Java may pass `null`
+                class_info::Type::Ref(_) | class_info::Type::Repeat(_) => {
+                    quote_spanned!(span => Option<&#java_ty>)
+                }
+                class_info::Type::Scalar(_) => java_ty,
+            });
+        }
+
+        // Convert each of them to the Rust type the method expects.
+        let mut rust_arguments = vec![];
+        let mut java_arguments = self.method_info.argument_tys.iter().zip(&argument_names);
+        for input in &inputs {
+            if is_jvm_type(&input.ty) {
+                rust_arguments.push(RustArgument::Jvm);
+            } else {
+                let (argument_ty, name) = java_arguments.next().unwrap();
+                rust_arguments.push(RustArgument::Converted(rust_argument(
+                    name,
+                    argument_ty.is_scalar(),
+                    true,
+                    input,
+                )?));
+            }
+        }
+
+        let invocation = fallible_invocation(
+            span,
+            quote_spanned!(span => rust.#method_name),
+            &rust_arguments,
+        );
+        let rust_invocation = quote_spanned!(span =>
+            |jvm| {
+                let result = duchess::RustObject::<#self_ty>::#with::<#interface_ty, _>(jvm, this, |jvm, rust| {
+                    #invocation
+                });
+                match result {
+                    Ok(result) => result,
+                    Err(err) => Err(err.into_global(jvm)),
+                }
+            }
+        );
+
+        let (return_ty, native_call) = match &self.method_info.return_ty {
+            Some(class_info::Type::Scalar(ty)) => {
+                let ty = ty.to_tokens(span);
+                (
+                    ty.clone(),
+                    quote_spanned!(span => duchess::plumbing::native_function_returning_scalar::<#ty, _>(jni_env, #rust_invocation)),
+                )
+            }
+            Some(ty) => {
+                let ty = self.convert_ty(ty)?;
+                (
+                    quote_spanned!(span => duchess::plumbing::jni_sys::jobject),
+                    quote_spanned!(span => duchess::plumbing::native_function_returning_object::<#ty, _>(jni_env, #rust_invocation)),
+                )
+            }
+            None => (
+                quote!(()),
+                quote_spanned!(span => duchess::plumbing::native_function_returning_scalar::<(), _>(jni_env, #rust_invocation)),
+            ),
+        };
+
+        Ok(quote_spanned!(span =>
+            fn #native_name(
+                jni_env: duchess::plumbing::EnvPtr<'_>,
+                this: &#interface_ty,
+                #(#argument_names: #argument_tys,)*
+            ) -> #return_ty {
+                // SAFETY: the JVM passes a valid environment
+                unsafe { #native_call }
+            }
+        ))
+    }
+}
@@ -0,0 +1,422 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+use crate::{
+    argument::JavaPath,
+    class_info::{self, ClassInfo, ClassKind, Method, Privacy, Type},
+    java_function::{fallible_invocation, is_jvm_type, rust_argument, RustArgument},
+    java_interface::type_aliases,
+    reflect::{InheritedMethod, Reflector},
+    signature::Signature,
+};
+
+/// Decorator applied to an `impl` block that extends a Java class with a Rust type.
+///
+/// The `#[java_subclass(X)]` takes an argument `X` naming the Java class, e.g. `java.io.InputStream`.
+/// It decorates a block `impl C for T { ... }`, where `C` is the Rust version of that class (including
+/// any type arguments) and `T` is the Rust type extending it.
+///
+/// The block contains a method for each Java method that the subclass overrides (which must include every abstract
+/// method of the class, including those inherited from its superclasses and interfaces), named after the Java method
+/// in snake case. Each method takes `&self`, then `this` (the
+/// Java object, which may be declared as `&duchess::Protected<C>` to call the protected methods of `C`), then the
+/// arguments of the Java method; they are converted in the same way as for a `java_function`. The macro implements
+/// `duchess::JavaSubclass<C>` for `T`, so that `duchess::Subclass::new(t).to_java::<C>()` creates an instance of
+/// the subclass.
+pub fn java_subclass(path: JavaPath, input: syn::ItemImpl) -> syn::Result<TokenStream> {
+    let span = path.span;
+
+    let reflector = Reflector::default();
+    let class_info = reflector.reflect(&path.to_dot_id(), span)?;
+    if class_info.kind != ClassKind::Class || class_info.flags.is_final {
+        return Err(syn::Error::new(
+            span,
+            format!("`{}` is not a class that can be extended", class_info.name),
+        ));
+    }
+    if !class_info
+        .constructors
+        .iter()
+        .any(|c| c.argument_tys.is_empty() && is_overridable(c.flags.privacy))
+    {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "`{}` has no public or protected constructor without arguments",
+                class_info.name
+            ),
+        ));
+    }
+
+    let Some((_, super_ty, _)) = &input.trait_ else {
+        return Err(syn::Error::new(
+            input.impl_token.span(),
+            format!(
+                "expected `impl {} for T`, naming the Rust type that extends the class",
+                class_info.name.class_name()
+            ),
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "generic Rust types cannot extend Java classes",
+        ));
+    }
+    let self_ty = &input.self_ty;
+
+    // Within the native functions, the class's type parameters are aliases for the type arguments given by the user.
+    let type_aliases = type_aliases(&class_info, super_ty)?;
+
+    let class_methods = reflector.inherited_methods(&class_info)?;
+
+    let mut methods = vec![];
+    let mut overridden = vec![];
+    let mut native_functions = vec![];
+    let mut java_functions = vec![];
+    for item in &input.items {
+        let syn::ImplItem::Fn(item_fn) = item else {
+            return Err(syn::Error::new(
+                item.span(),
+                "only methods can override the methods of a Java class",
+            ));
+        };
+        let class_method = overridden_method(&class_info, &class_methods, item_fn)?;
+        let driver = Driver {
+            class_info: &class_info,
+            method_info: &class_method.method,
+            descriptor: &class_method.descriptor,
+            input: item_fn,
+            self_ty,
+            super_ty,
+        };
+        native_functions.push(driver.native_function()?);
+        java_functions.push(driver.java_function());
+        overridden.push(class_method.key());
+        methods.push(item_fn);
+    }
+
+    if let Some(missing) = class_methods
+        .iter()
+        .find(|m| m.method.flags.is_abstract && !overridden.contains(&m.key()))
+    {
+        return Err(syn::Error::new(
+            self_ty.span(),
+            format!(
+                "missing method `{}`, which overrides the abstract method `{}`{}",
+                missing.method.name.to_snake_case(),
+                missing.method.name,
+                if missing.declared_in == class_info.name {
+                    String::new()
+                } else {
+                    format!(" (inherited from `{}`)", missing.declared_in)
+                }
+            ),
+        ));
+    }
+
+    let tokens = quote_spanned!(span =>
+        impl #self_ty {
+            #(#methods)*
+        }
+
+        // The native functions are declared inside a `const _` block so that they are not nameable from Rust code.
+        #[allow(unused_variables, nonstandard_style)]
+        const _: () = {
+            #(#type_aliases)*
+
+            #(#native_functions)*
+
+            unsafe impl duchess::JavaSubclass<#super_ty> for #self_ty {
+                fn subclass<'jvm>(
+                    jvm: &mut duchess::Jvm<'jvm>,
+                ) -> duchess::Result<'jvm, &'static duchess::plumbing::SubclassInfo> {
+                    static SUBCLASS: duchess::plumbing::once_cell::sync::OnceCell<duchess::plumbing::SubclassInfo> =
+                        duchess::plumbing::once_cell::sync::OnceCell::new();
+                    SUBCLASS.get_or_try_init(|| {
+                        // SAFETY: the native functions expect an instance of the subclass as `this`
+                        unsafe {
+                            duchess::plumbing::define_subclass::<#super_ty>(
+                                jvm,
+                                vec![#(#java_functions),*],
+                            )
+                        }
+                    })
+                }
+            }
+        };
+    );
+
+    crate::debug_tokens(format!("{}", path), &tokens);
+
+    Ok(tokens)
+}
+
+/// True if a member with this privacy can be overridden (or invoked) by a subclass in another package.
+fn is_overridable(privacy: Privacy) -> bool {
+    matches!(privacy, Privacy::Public | Privacy::Protected)
+}
+
+/// Finds the (unique) method of the class or of its supertypes that `item_fn` overrides. Among overloaded
+/// methods, the one with as many arguments as `item_fn` is chosen; if there are several, it is an error.
+fn overridden_method<'a>(
+    class_info: &ClassInfo,
+    class_methods: &'a [InheritedMethod],
+    item_fn: &syn::ImplItemFn,
+) -> syn::Result<&'a InheritedMethod> {
+    let name = &item_fn.sig.ident;
+    let mut candidates: Vec<_> = class_methods
+        .iter()
+        .filter(|m| {
+            !m.method.flags.is_final
+                && is_overridable(m.method.flags.privacy)
+                && *name == *m.method.name.to_snake_case()
+        })
+        .collect();
+    if candidates.len() > 1 {
+        // Besides `&self`, the Rust method takes `this` and the arguments of the Java method
+        let arguments = item_fn
+            .sig
+            .inputs
+            .iter()
+            .filter(|fn_arg| match fn_arg {
+                syn::FnArg::Typed(t) => !is_jvm_type(&t.ty),
+                syn::FnArg::Receiver(_) => false,
+            })
+            .count();
+        candidates.retain(|m| m.method.argument_tys.len() + 1 == arguments);
+        if candidates.is_empty() {
+            return Err(syn::Error::new(
+                name.span(),
+                format!(
+                    "no method `{name}` with {} argument(s) that can be overridden in `{}`; don't forget about `this`",
+                    arguments.saturating_sub(1),
+                    class_info.name
+                ),
+            ));
+        }
+        if candidates.len() > 1 {
+            return Err(syn::Error::new(
+                name.span(),
+                format!(
+                    "`{}` has {} methods named `{name}` with {} argument(s) ({}); overloaded methods \
+                     with the same number of arguments cannot be overridden in Rust",
+                    class_info.name,
+                    candidates.len(),
+                    arguments - 1,
+                    candidates
+                        .iter()
+                        .map(|m| format!("`{}`", m.key()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ));
+        }
+    }
+    match candidates[..] {
+        [method] => Ok(method),
+        _ => Err(syn::Error::new(
+            name.span(),
+            format!(
+                "no method `{name}` that can be overridden in `{}`",
+                class_info.name
+            ),
+        )),
+    }
+}
+
+struct Driver<'a> {
+    class_info: &'a ClassInfo,
+    method_info: &'a Method,
+    descriptor: &'a str,
+    input: &'a syn::ImplItemFn,
+    self_ty: &'a syn::Type,
+    super_ty: &'a syn::Path,
+}
+
+impl Driver<'_> {
+    fn convert_ty(&self, ty: &Type) -> syn::Result<TokenStream> {
+        Signature::new(
+            &self.method_info.name,
+            self.input.sig.ident.span(),
+            &self.class_info.generics,
+        )
+        .forbid_capture(|sig| sig.java_ty(ty))
+    }
+
+    /// The name of the native function that implements this method.
+    fn native_name(&self) -> syn::Ident {
+        syn::Ident::new(
+            &format!("__duchess_{}", self.input.sig.ident),
+            self.input.sig.ident.span(),
+        )
+    }
+
+    /// The `JavaFunction` that registers the native function with the subclass.
+    fn java_function(&self) -> TokenStream {
+        let span = self.input.sig.ident.span();
+        let super_ty = self.super_ty;
+        let native_name = self.native_name();
+        let name = Literal::string(&self.method_info.name);
+        let descriptor = Literal::string(self.descriptor);
+        quote_spanned!(span =>
+            duchess::plumbing::JavaFunction::new(
+                #name,
+                #descriptor,
+                std::ptr::NonNull::new_unchecked(#native_name as *mut ()),
+                <#super_ty as duchess::JavaObject>::class,
+            )
+        )
+    }
+
+    /// The native function that the JVM invokes for this method, which forwards to the Rust method.
+    fn native_function(&self) -> syn::Result<TokenStream> {
+        let span = self.input.sig.ident.span();
+        let method_name = &self.input.sig.ident;
+        let native_name = self.native_name();
+        let self_ty = self.self_ty;
+        let super_ty = self.super_ty;
+
+        if let Some(asyncness) = &self.input.sig.asyncness {
+            return Err(syn::Error::new(
+                asyncness.span(),
+                "async methods cannot override Java methods",
+            ));
+        }
+
+        // Extract the arguments of the Rust method, which must take `&self`.
+        let mut inputs = self.input.sig.inputs.iter();
+        match inputs.next() {
+            Some(syn::FnArg::Receiver(r)) if r.reference.is_some() && r.mutability.is_none() => {}
+            _ => {
+                return Err(syn::Error::new(
+                    self.input.sig.span(),
+                    "methods overriding a Java method must take `&self`",
+                ))
+            }
+        }
+        let inputs: Vec<_> = inputs
+            .map(|fn_arg| match fn_arg {
+                syn::FnArg::Typed(t) => t,
+                syn::FnArg::Receiver(_) => unreachable!("receiver must come first"),
+            })
+            .collect();
+
+        let expected = 1 + self.method_info.argument_tys.len();
+        let found = inputs.iter().filter(|t| !is_jvm_type(&t.ty)).count();
+        if found != expected {
+            return Err(syn::Error::new(
+                self.input.sig.span(),
+                format!(
+                    "Rust method should have {expected} argument(s) (besides `&self`), to match `{}`; don't forget about `this`",
+                    self.method_info.name
+                ),
+            ));
+        }
+
+        // The arguments of the native function: `this`, followed by the arguments of the Java method.
+        let this_name = syn::Ident::new("this", span);
+        let mut argument_names = vec![];
+        let mut argument_tys = vec![];
+        for (argument_ty, index) in self.method_info.argument_tys.iter().zip(0..) {
+            argument_names.push(syn::Ident::new(&format!("arg{index}"), span));
+            let java_ty = self.convert_ty(argument_ty)?;
+            argument_tys.push(match argument_ty {
+                // Java may pass `null`
+                class_info::Type::Ref(_) | class_info::Type::Repeat(_) => {
+                    quote_spanned!(span => Option<&#java_ty>)
+                }
+                class_info::Type::Scalar(_) => java_ty,
+            });
+        }
+
+        // Convert each of them to the Rust type the method expects.
+        let mut rust_arguments = vec![];
+        let mut java_arguments = self.method_info.argument_tys.iter().zip(&argument_names);
+        let mut this_argument = true;
+        for input in &inputs {
+            if is_jvm_type(&input.ty) {
+                rust_arguments.push(RustArgument::Jvm);
+            } else if this_argument {
+                rust_arguments.push(RustArgument::Converted(if is_protected_type(&input.ty) {
+                    quote_spanned!(span => duchess::plumbing::FromRef::from_ref(#this_name))
+                } else {
+                    rust_argument(&this_name, false, false, input)?
+                }));
+                this_argument = false;
+            } else {
+                let (argument_ty, name) = java_arguments.next().unwrap();
+                rust_arguments.push(RustArgument::Converted(rust_argument(
+                    name,
+                    argument_ty.is_scalar(),
+                    true,
+                    input,
+                )?));
+            }
+        }
+
+        let invocation = fallible_invocation(
+            span,
+            quote_spanned!(span => rust.#method_name),
+            &rust_arguments,
+        );
+        let rust_invocation = quote_spanned!(span =>
+            |jvm| {
+                let rust = match duchess::plumbing::subclass_value::<#self_ty, #super_ty>(jvm, #this_name) {
+                    Ok(rust) => rust,
+                    Err(err) => return Err(err.into_global(jvm)),
+                };
+                #invocation
+            }
+        );
+
+        let (return_ty, native_call) = match &self.method_info.return_ty {
+            Some(class_info::Type::Scalar(ty)) => {
+                let ty = ty.to_tokens(span);
+                (
+                    ty.clone(),
+                    quote_spanned!(span => duchess::plumbing::native_function_returning_scalar::<#ty, _>(jni_env, #rust_invocation)),
+                )
+            }
+            Some(ty) => {
+                let ty = self.convert_ty(ty)?;
+                (
+                    quote_spanned!(span => duchess::plumbing::jni_sys::jobject),
+                    quote_spanned!(span => duchess::plumbing::native_function_returning_object::<#ty, _>(jni_env, #rust_invocation)),
+                )
+            }
+            None => (
+                quote!(()),
+                quote_spanned!(span => duchess::plumbing::native_function_returning_scalar::<(), _>(jni_env, #rust_invocation)),
+            ),
+        };
+
+        Ok(quote_spanned!(span =>
+            fn #native_name(
+                jni_env: duchess::plumbing::EnvPtr<'_>,
+                #this_name: &#super_ty,
+                #(#argument_names: #argument_tys,)*
+            ) -> #return_ty {
+                // SAFETY: this function is only registered as a native method of the subclass, so `this` is an
+                // instance of it.
+                unsafe { #native_call }
+            }
+        ))
+    }
+}
+
+/// True if `ty` is (syntactically) `&Protected<C>`, possibly with a path like `duchess::Protected`.
+fn is_protected_type(ty: &syn::Type) -> bool {
+    let syn::Type::Reference(r) = ty else {
+        return false;
+    };
+    let syn::Type::Path(p) = &*r.elem else {
+        return false;
+    };
+    p.qself.is_none()
+        && p.path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "Protected")
+}
@@ -1,4 +1,4 @@
-use argument::{DuchessDeclaration, MethodSelector};
+use argument::{DuchessDeclaration, JavaPath, MethodSelector};
 use parse::Parser;
 use proc_macro::TokenStream;
 use rust_format::Formatter;
@@ -9,6 +9,9 @@ mod class_info;
 mod codegen;
 mod derive;
 mod java_function;
+mod java_interface;
+mod java_peer;
+mod java_subclass;
 mod parse;
 mod reflect;
 mod signature;
@@ -60,6 +63,63 @@ pub fn java_function(args: TokenStream, input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_attribute]
+pub fn java_interface(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args: proc_macro2::TokenStream = args.into();
+    let args = match Parser::from(args).parse::<JavaPath>() {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let item_impl = match syn::parse::<syn::ItemImpl>(input) {
+        Ok(item_impl) => item_impl,
+        Err(err) => return err.into_compile_error().into(),
+    };
+
+    match java_interface::java_interface(args, item_impl) {
+        Ok(t) => t.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro_attribute]
+pub fn java_peer(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args: proc_macro2::TokenStream = args.into();
+    let args = match Parser::from(args).parse::<JavaPath>() {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let item_impl = match syn::parse::<syn::ItemImpl>(input) {
+        Ok(item_impl) => item_impl,
+        Err(err) => return err.into_compile_error().into(),
+    };
+
+    match java_peer::java_peer(args, item_impl) {
+        Ok(t) => t.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro_attribute]
+pub fn java_subclass(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args: proc_macro2::TokenStream = args.into();
+    let args = match Parser::from(args).parse::<JavaPath>() {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let item_impl = match syn::parse::<syn::ItemImpl>(input) {
+        Ok(item_impl) => item_impl,
+        Err(err) => return err.into_compile_error().into(),
+    };
+
+    match java_subclass::java_subclass(args, item_impl) {
+        Ok(t) => t.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
 synstructure::decl_derive!([ToRust, attributes(java)] => derive::derive_to_rust);
 
 synstructure::decl_derive!([ToJava, attributes(java)] => derive::derive_to_java);
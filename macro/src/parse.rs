@@ -154,6 +154,19 @@ impl<'p> TextAccum<'p> {
     }
 
     fn accum_token(&mut self, token: &TokenTree) {
+        // `$` separates the name of a nested class from its outer class (e.g. `java.util.Map$Entry`),
+        // so it must not be separated from the identifiers on either side
+        if let TokenTree::Punct(p) = token {
+            if p.as_char() == '$' {
+                if self.text.ends_with(' ') {
+                    self.text.pop();
+                }
+                self.text.push('$');
+                self.span = self.span.join(token.span()).unwrap_or(self.span);
+                return;
+            }
+        }
+
         self.text.push_str(&token.to_string());
 
         // insert whitespace if this is a token that needs to be separated from following tokens
@@ -1,12 +1,21 @@
-use std::{cell::RefCell, collections::BTreeMap, env, path::PathBuf, process::Command, sync::Arc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    env,
+    path::PathBuf,
+    process::Command,
+    sync::Arc,
+};
 
 use proc_macro2::Span;
 
 use crate::{
     argument::{DuchessDeclaration, Ident, JavaPackage, MethodSelector},
     class_info::{
-        ClassDecl, ClassInfo, DotId, Generic, Id, Method, RootMap, SpannedPackageInfo, Type,
+        ClassDecl, ClassInfo, ClassKind, ClassRef, DotId, Generic, Id, Method, RefType, RootMap,
+        SpannedPackageInfo, Type,
     },
+    substitution::{Substitute, Substitution},
     upcasts::Upcasts,
 };
 
@@ -74,7 +83,7 @@ impl JavaPackage {
                 ClassDecl::Reflected(c) => {
                     let dot_id = self.make_absolute_dot_id(c.span, &c.name)?;
                     let info = reflector.reflect(&dot_id, c.span)?;
-                    (dot_id, info.clone())
+                    (dot_id, Arc::new(info.without_protected_methods()))
                 }
                 ClassDecl::Specified(c) => {
                     let dot_id = self.make_absolute_dot_id(c.span, &c.name)?;
@@ -192,6 +201,61 @@ impl Reflector {
             .clone())
     }
 
+    /// Collects the non-static methods of `class_info` and of its superclasses and interfaces.
+    /// A method that is declared again by a more specific class or interface (e.g., to implement it)
+    /// only appears once, as declared there; the methods of classes take precedence over those of interfaces.
+    pub fn inherited_methods(&self, class_info: &ClassInfo) -> syn::Result<Vec<InheritedMethod>> {
+        let mut methods: Vec<InheritedMethod> = vec![];
+        let mut visited = vec![];
+        let mut classes = VecDeque::from([class_info.this_ref()]);
+        let mut interfaces = VecDeque::new();
+        while let Some(class_ref) = classes.pop_front().or_else(|| interfaces.pop_front()) {
+            if visited.contains(&class_ref.name) {
+                continue;
+            }
+            visited.push(class_ref.name.clone());
+
+            let info = self.reflect(&class_ref.name, class_info.span)?;
+
+            // Substitute the type arguments given for the class, or `Object` if it is used as a raw type
+            let object = RefType::Class(ClassRef {
+                name: DotId::object(),
+                generics: vec![],
+            });
+            let type_arguments: Vec<RefType> = if class_ref.generics.is_empty() {
+                info.generics.iter().map(|_| object.clone()).collect()
+            } else {
+                class_ref.generics.clone()
+            };
+            let subst: Substitution<'_> = info
+                .generics
+                .iter()
+                .map(|g| &g.id)
+                .zip(&type_arguments)
+                .collect();
+
+            for method in info.methods.iter().filter(|m| !m.flags.is_static) {
+                let method = InheritedMethod {
+                    method: method.substitute(&subst),
+                    descriptor: method.descriptor(),
+                    declared_in: info.name.clone(),
+                };
+                if !methods.iter().any(|m| m.key() == method.key()) {
+                    methods.push(method);
+                }
+            }
+
+            // The super-interfaces of an interface are listed in its `extends` clause
+            let (superclasses, super_interfaces) = match info.kind {
+                ClassKind::Class => (&info.extends, &info.implements),
+                ClassKind::Interface => (&info.implements, &info.extends),
+            };
+            classes.extend(superclasses.iter().map(|c| c.substitute(&subst)));
+            interfaces.extend(super_interfaces.iter().map(|c| c.substitute(&subst)));
+        }
+        Ok(methods)
+    }
+
     ///
     pub fn reflect_method(&self, method_selector: &MethodSelector) -> syn::Result<ReflectedMethod> {
         match method_selector {
@@ -227,6 +291,27 @@ impl Reflector {
     }
 }
 
+/// A non-static method of a class or interface, either declared in it or inherited from one of its supertypes.
+pub struct InheritedMethod {
+    /// The method, with the type parameters of the supertype that declares it replaced by the type
+    /// arguments given in the `extends` and `implements` clauses, so that it only refers to the type
+    /// parameters of the class or interface.
+    pub method: Method,
+
+    /// The descriptor of the method as declared, which identifies it at runtime.
+    pub descriptor: String,
+
+    /// The class or interface that declares the method.
+    pub declared_in: DotId,
+}
+
+impl InheritedMethod {
+    /// The name and descriptor of this method.
+    pub fn key(&self) -> String {
+        format!("{}{}", self.method.name, self.descriptor)
+    }
+}
+
 pub type ConstructorIndex = usize;
 pub type MethodIndex = usize;
 
@@ -1,7 +1,7 @@
 use std::iter::FromIterator;
 use std::{collections::BTreeMap, sync::Arc};
 
-use crate::class_info::{ClassRef, Id, RefType, Type};
+use crate::class_info::{ClassRef, Generic, Id, Method, RefType, Type};
 
 pub struct Substitution<'s> {
     map: BTreeMap<&'s Id, &'s RefType>,
@@ -55,6 +55,46 @@ impl Substitute for ClassRef {
     }
 }
 
+impl Substitute for Generic {
+    fn substitute(&self, subst: &Substitution<'_>) -> Self {
+        let Generic { id, extends } = self;
+        Generic {
+            id: id.clone(),
+            extends: extends.substitute(subst),
+        }
+    }
+}
+
+impl Substitute for Method {
+    fn substitute(&self, subst: &Substitution<'_>) -> Self {
+        // The method's own type parameters shadow those of the class
+        let subst: Substitution<'_> = subst
+            .map
+            .iter()
+            .filter(|(id, _)| !self.generics.iter().any(|g| g.id == ***id))
+            .map(|(&id, &ty)| (id, ty))
+            .collect();
+        let subst = &subst;
+        Method {
+            flags: self.flags,
+            name: self.name.clone(),
+            generics: self.generics.substitute(subst),
+            argument_tys: self.argument_tys.substitute(subst),
+            return_ty: self.return_ty.substitute(subst),
+            throws: self.throws.substitute(subst),
+        }
+    }
+}
+
+impl<F> Substitute for Option<F>
+where
+    F: Substitute,
+{
+    fn substitute(&self, subst: &Substitution<'_>) -> Self {
+        self.as_ref().map(|e| e.substitute(subst))
+    }
+}
+
 impl<F> Substitute for Vec<F>
 where
     F: Substitute,
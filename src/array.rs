@@ -1,11 +1,16 @@
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
 
 use crate::{
     cast::Upcast,
     java::{self, lang::Class},
     jvm::JavaView,
-    plumbing::{FromRef, JavaObjectExt},
-    to_java::ToJavaImpl,
+    ops::IntoScalar,
+    plumbing::{EnvPtr, FromRef, JavaObjectExt, ObjectPtr},
+    to_java::{in_element_frame, ToJavaImpl},
     AsJRef, Error, IntoRust, JDeref, JavaObject, JavaType, Jvm, JvmOp, Local, Nullable,
     ScalarMethod, TryJDeref,
 };
@@ -84,6 +89,45 @@ impl<T: JavaType> TryJDeref for JavaArray<T> {
 pub trait JavaArrayExt<T: JavaType>: JvmOp {
     type Length: ScalarMethod<jni_sys::jsize>;
     fn length(self) -> Self::Length;
+
+    /// Reads the element at `index`, throwing an `ArrayIndexOutOfBoundsException` if it is out of bounds.
+    fn get<I>(self, index: I) -> Get<Self, T, I>
+    where
+        T: ArrayAccess,
+        I: IntoScalar<jni_sys::jsize>;
+
+    /// Stores `value` at `index`, throwing an `ArrayIndexOutOfBoundsException` if it is out of bounds
+    /// (or an `ArrayStoreException` if the array cannot hold `value`'s class).
+    fn set<I, V>(self, index: I, value: V) -> Set<Self, T, I, V>
+    where
+        I: IntoScalar<jni_sys::jsize>,
+        V: JvmOp,
+        for<'jvm> V::Output<'jvm>: IntoArrayElement<T>;
+
+    /// Copies the elements starting at `start` into `buffer`, throwing an
+    /// `ArrayIndexOutOfBoundsException` if the array has fewer than `start + buffer.len()` elements.
+    fn read_region<I>(self, start: I, buffer: &mut [T]) -> ReadRegion<'_, Self, T, I>
+    where
+        T: ArrayElement,
+        I: IntoScalar<jni_sys::jsize>;
+
+    /// Copies `elements` into the array starting at `start`, throwing an
+    /// `ArrayIndexOutOfBoundsException` if the array has fewer than `start + elements.len()` elements.
+    fn write_region<I>(self, start: I, elements: &[T]) -> WriteRegion<'_, Self, T, I>
+    where
+        T: ArrayElement,
+        I: IntoScalar<jni_sys::jsize>;
+
+    /// Produces an iterator that copies the elements of the array out in chunks of `chunk_size`
+    /// elements (the last chunk may be shorter), so that large arrays can be processed without
+    /// copying them into a single `Vec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    fn chunks(self, chunk_size: usize) -> Chunks<Self, T>
+    where
+        T: ArrayElement;
 }
 
 impl<This, T> JavaArrayExt<T> for This
@@ -99,6 +143,70 @@ where
             element: PhantomData,
         }
     }
+
+    fn get<I>(self, index: I) -> Get<Self, T, I>
+    where
+        T: ArrayAccess,
+        I: IntoScalar<jni_sys::jsize>,
+    {
+        Get {
+            this: self,
+            index,
+            element: PhantomData,
+        }
+    }
+
+    fn set<I, V>(self, index: I, value: V) -> Set<Self, T, I, V>
+    where
+        I: IntoScalar<jni_sys::jsize>,
+        V: JvmOp,
+        for<'jvm> V::Output<'jvm>: IntoArrayElement<T>,
+    {
+        Set {
+            this: self,
+            index,
+            value,
+            element: PhantomData,
+        }
+    }
+
+    fn read_region<I>(self, start: I, buffer: &mut [T]) -> ReadRegion<'_, Self, T, I>
+    where
+        T: ArrayElement,
+        I: IntoScalar<jni_sys::jsize>,
+    {
+        ReadRegion {
+            this: self,
+            start,
+            buffer: NonNull::from(&mut *buffer).cast(),
+            len: buffer.len(),
+            phantom: PhantomData,
+        }
+    }
+
+    fn write_region<I>(self, start: I, elements: &[T]) -> WriteRegion<'_, Self, T, I>
+    where
+        T: ArrayElement,
+        I: IntoScalar<jni_sys::jsize>,
+    {
+        WriteRegion {
+            this: self,
+            start,
+            elements,
+        }
+    }
+
+    fn chunks(self, chunk_size: usize) -> Chunks<Self, T>
+    where
+        T: ArrayElement,
+    {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        Chunks {
+            this: self,
+            chunk_size,
+            element: PhantomData,
+        }
+    }
 }
 
 #[derive_where::derive_where(Copy, Clone)]
@@ -127,9 +235,733 @@ where
     }
 }
 
+/// Element types of Java arrays, whose elements can be read one at a time with [`JavaArrayExt::get`].
+pub trait ArrayAccess: JavaType + Sized {
+    /// The result of reading an element (e.g., `Option<Local<'jvm, T>>` for an array of objects).
+    type Element<'jvm>;
+
+    fn get_element<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        array: &JavaArray<Self>,
+        index: jni_sys::jsize,
+    ) -> crate::Result<'jvm, Self::Element<'jvm>>;
+}
+
+/// Values that can be stored into an array of `T` with [`JavaArrayExt::set`].
+pub trait IntoArrayElement<T: JavaType> {
+    fn set_element<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        array: &JavaArray<T>,
+        index: jni_sys::jsize,
+    ) -> crate::Result<'jvm, ()>;
+}
+
+impl<T: JavaObject> ArrayAccess for T {
+    type Element<'jvm> = Option<Local<'jvm, T>>;
+
+    fn get_element<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        array: &JavaArray<Self>,
+        index: jni_sys::jsize,
+    ) -> crate::Result<'jvm, Self::Element<'jvm>> {
+        let array = array.as_raw();
+        // SAFETY: `array` is a valid reference to an array of `T`
+        unsafe {
+            jvm.env().invoke(
+                |env| env.GetObjectArrayElement,
+                |env, f| f(env, array.as_ptr(), index),
+            )
+        }
+    }
+}
+
+impl<T, R> IntoArrayElement<T> for R
+where
+    T: JavaObject,
+    R: AsJRef<T>,
+{
+    fn set_element<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        array: &JavaArray<T>,
+        index: jni_sys::jsize,
+    ) -> crate::Result<'jvm, ()> {
+        let value = self.as_jref()?.as_raw();
+        let array = array.as_raw();
+        // SAFETY: `array` is a valid reference to an array of `T`, and `value` to a `T`
+        unsafe {
+            jvm.env().invoke(
+                |env| env.SetObjectArrayElement,
+                |env, f| f(env, array.as_ptr(), index, value.as_ptr()),
+            )
+        }
+    }
+}
+
+#[derive_where::derive_where(Copy, Clone; This, I)]
+pub struct Get<This: JvmOp, T, I> {
+    this: This,
+    index: I,
+    element: PhantomData<T>,
+}
+
+impl<This, T, I> JvmOp for Get<This, T, I>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<JavaArray<T>>,
+    T: ArrayAccess,
+    I: IntoScalar<jni_sys::jsize>,
+{
+    type Output<'jvm> = T::Element<'jvm>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let index = self.index.execute_with(jvm)?;
+        T::get_element(jvm, this.as_jref()?, index)
+    }
+}
+
+#[derive_where::derive_where(Copy, Clone; This, I, V)]
+pub struct Set<This: JvmOp, T, I, V> {
+    this: This,
+    index: I,
+    value: V,
+    element: PhantomData<T>,
+}
+
+impl<This, T, I, V> JvmOp for Set<This, T, I, V>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<JavaArray<T>>,
+    T: JavaType,
+    I: IntoScalar<jni_sys::jsize>,
+    V: JvmOp,
+    for<'jvm> V::Output<'jvm>: IntoArrayElement<T>,
+{
+    type Output<'jvm> = ();
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let index = self.index.execute_with(jvm)?;
+        let value = self.value.execute_with(jvm)?;
+        value.set_element(jvm, this.as_jref()?, index)
+    }
+}
+
+/// Created by [`JavaArrayExt::read_region`].
+//
+// The buffer is held as a raw pointer so that the operation can be `Copy`, like every other `JvmOp`.
+// This is sound because the buffer stays mutably borrowed for `'a` by every copy, and copies can
+// only execute one after the other (on the thread that created them).
+#[derive_where::derive_where(Copy, Clone; This, I)]
+pub struct ReadRegion<'a, This: JvmOp, T, I> {
+    this: This,
+    start: I,
+    buffer: NonNull<T>,
+    len: usize,
+    phantom: PhantomData<&'a mut [T]>,
+}
+
+impl<This, T, I> JvmOp for ReadRegion<'_, This, T, I>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<JavaArray<T>>,
+    T: ArrayElement,
+    I: IntoScalar<jni_sys::jsize>,
+{
+    type Output<'jvm> = ();
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let start = self.start.execute_with(jvm)?;
+        // SAFETY: `this` is a valid reference to an array of `T`, and the buffer is borrowed for as
+        // long as `self` exists (see above)
+        unsafe {
+            T::get_region(
+                jvm.env(),
+                this.as_jref()?.as_raw(),
+                start,
+                self.len,
+                self.buffer.as_ptr(),
+            )
+        }
+    }
+}
+
+/// Created by [`JavaArrayExt::write_region`].
+#[derive_where::derive_where(Copy, Clone; This, I)]
+pub struct WriteRegion<'a, This: JvmOp, T, I> {
+    this: This,
+    start: I,
+    elements: &'a [T],
+}
+
+impl<This, T, I> JvmOp for WriteRegion<'_, This, T, I>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<JavaArray<T>>,
+    T: ArrayElement,
+    I: IntoScalar<jni_sys::jsize>,
+{
+    type Output<'jvm> = ();
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let start = self.start.execute_with(jvm)?;
+        // SAFETY: `this` is a valid reference to an array of `T`
+        unsafe {
+            T::set_region(
+                jvm.env(),
+                this.as_jref()?.as_raw(),
+                start,
+                self.elements.len(),
+                self.elements.as_ptr(),
+            )
+        }
+    }
+}
+
+/// Created by [`JavaArrayExt::chunks`].
+#[derive_where::derive_where(Copy, Clone; This)]
+pub struct Chunks<This: JvmOp, T> {
+    this: This,
+    chunk_size: usize,
+    element: PhantomData<T>,
+}
+
+impl<This, T> JvmOp for Chunks<This, T>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<JavaArray<T>>,
+    T: ArrayElement,
+{
+    type Output<'jvm> = ArrayChunks<'jvm, T>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let array = jvm.local(this.as_jref()?);
+        let len = (&array).length().execute_with(jvm)?;
+        Ok(ArrayChunks {
+            array,
+            start: 0,
+            len,
+            // Chunks can never be longer than the array itself
+            chunk_size: self.chunk_size.try_into().unwrap_or(jni_sys::jsize::MAX),
+        })
+    }
+}
+
+/// An iterator over the elements of a Java primitive array, copied out in fixed-size chunks.
+/// Produced by executing [`JavaArrayExt::chunks`].
+///
+/// Like [`JavaIterator`](crate::JavaIterator), it takes the [`Jvm`] it was created with on every call to
+/// [`next`](ArrayChunks::next); use [`with_jvm`](ArrayChunks::with_jvm) to get a Rust [`Iterator`].
+pub struct ArrayChunks<'jvm, T: ArrayElement> {
+    array: Local<'jvm, JavaArray<T>>,
+    start: jni_sys::jsize,
+    len: jni_sys::jsize,
+    chunk_size: jni_sys::jsize,
+}
+
+impl<'jvm, T: ArrayElement> ArrayChunks<'jvm, T> {
+    /// Returns the next chunk, or `None` if the whole array has been copied out.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self, jvm: &mut Jvm<'jvm>) -> Option<crate::Result<'jvm, Vec<T>>> {
+        if self.start >= self.len {
+            return None;
+        }
+
+        let chunk_len = self.chunk_size.min(self.len - self.start) as usize;
+        let mut chunk = Vec::with_capacity(chunk_len);
+        // SAFETY: `array` is a valid reference to an array of `T`, and the chunk is within its bounds
+        let result = unsafe {
+            T::get_region(
+                jvm.env(),
+                self.array.as_raw(),
+                self.start,
+                chunk_len,
+                chunk.as_mut_ptr(),
+            )
+        };
+        if let Err(err) = result {
+            // The array can't change length, so this is unexpected; don't keep going
+            self.start = self.len;
+            return Some(Err(err));
+        }
+        // SAFETY: `get_region` initialized the first `chunk_len` elements
+        unsafe { chunk.set_len(chunk_len) };
+
+        self.start += chunk_len as jni_sys::jsize;
+        Some(Ok(chunk))
+    }
+
+    /// Returns the bounds on the number of chunks left, like [`Iterator::size_hint`].
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.len - self.start).max(0) as usize;
+        let chunks = remaining.div_ceil(self.chunk_size as usize);
+        (chunks, Some(chunks))
+    }
+
+    /// Returns a Rust [`Iterator`] over the chunks, which borrows `jvm` until it is dropped.
+    pub fn with_jvm<'a>(
+        mut self,
+        jvm: &'a mut Jvm<'jvm>,
+    ) -> impl Iterator<Item = crate::Result<'jvm, Vec<T>>> + 'a
+    where
+        'jvm: 'a,
+        T: 'a,
+    {
+        std::iter::from_fn(move || self.next(jvm))
+    }
+}
+
+impl<R, J> ToJavaImpl<JavaArray<J>> for [R]
+where
+    R: ToJavaImpl<J>,
+    J: Upcast<java::lang::Object> + Upcast<J>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, JavaArray<J>>>> {
+        let Ok(len) = rust.len().try_into() else {
+            return Err(Error::SliceTooLong(rust.len()));
+        };
+
+        let class = J::class(jvm)?;
+        let class = class.as_raw();
+        let env = jvm.env();
+        let array: Option<Local<JavaArray<J>>> = unsafe {
+            // SAFETY: `class` is a valid reference to a class
+            env.invoke(
+                |env| env.NewObjectArray,
+                |env, f| f(env, len, class.as_ptr(), std::ptr::null_mut()),
+            )
+        }?;
+        let Some(array) = array else {
+            // NewObjectArray should never return null unless an exception occurred (which we've already checked)
+            return Err(Error::JvmInternal(format!(
+                "failed to allocate `{}[{}]`",
+                std::any::type_name::<J>(),
+                len
+            )));
+        };
+
+        for (element, index) in rust.iter().zip(0..) {
+            in_element_frame(jvm, |jvm| {
+                // Null elements are left as they are
+                if let Some(element) = R::to_java_impl(element, jvm)? {
+                    IntoArrayElement::<J>::set_element(&element, jvm, &array, index)?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(Some(array))
+    }
+}
+
+impl<R, J> ToJavaImpl<JavaArray<J>> for Vec<R>
+where
+    R: ToJavaImpl<J>,
+    J: Upcast<java::lang::Object> + Upcast<J>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, JavaArray<J>>>> {
+        <[R]>::to_java_impl(rust, jvm)
+    }
+}
+
+impl<R, J> IntoRust<Vec<R>> for &JavaArray<J>
+where
+    J: JavaObject,
+    for<'a> &'a J: IntoRust<R>,
+{
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Vec<R>> {
+        let len = self.length().execute_with(jvm)?;
+        let mut vec = Vec::with_capacity(len as usize);
+        for index in 0..len {
+            in_element_frame(jvm, |jvm| {
+                let element = J::get_element(jvm, self, index)?.ok_or(Error::NullDeref)?;
+                vec.push(element.into_rust(jvm)?);
+                Ok(())
+            })?;
+        }
+        Ok(vec)
+    }
+}
+
+/// The element type of a Java primitive array, whose elements can be copied in bulk with
+/// [`JavaArrayExt::read_region`] and [`JavaArrayExt::write_region`] or borrowed in place with
+/// [`JavaArray::elements`].
+pub trait ArrayElement: JavaType + Copy {
+    /// Calls `Get<Type>ArrayElements`.
+    ///
+    /// # Safety
+    ///
+    /// `array` must be a valid reference to a Java array of `Self`.
+    unsafe fn get_elements(
+        env: EnvPtr<'_>,
+        array: ObjectPtr,
+        is_copy: *mut jni_sys::jboolean,
+    ) -> *mut Self;
+
+    /// Calls `Release<Type>ArrayElements`.
+    ///
+    /// # Safety
+    ///
+    /// `elements` must have been returned by [`get_elements`](ArrayElement::get_elements) for `array`, and not
+    /// released yet (unless `mode` is `JNI_COMMIT`).
+    unsafe fn release_elements(
+        env: EnvPtr<'_>,
+        array: ObjectPtr,
+        elements: *mut Self,
+        mode: jni_sys::jint,
+    );
+
+    /// Calls `Get<Type>ArrayRegion` to copy `len` elements starting at `start` into `buffer`.
+    ///
+    /// # Safety
+    ///
+    /// `array` must be a valid reference to a Java array of `Self`, and `buffer` must be valid for
+    /// writes of `len` elements.
+    unsafe fn get_region<'jvm>(
+        env: EnvPtr<'jvm>,
+        array: ObjectPtr,
+        start: jni_sys::jsize,
+        len: usize,
+        buffer: *mut Self,
+    ) -> crate::Result<'jvm, ()>;
+
+    /// Calls `Set<Type>ArrayRegion` to copy `len` elements from `elements` into the array, starting
+    /// at `start`.
+    ///
+    /// # Safety
+    ///
+    /// `array` must be a valid reference to a Java array of `Self`, and `elements` must be valid for
+    /// reads of `len` elements.
+    unsafe fn set_region<'jvm>(
+        env: EnvPtr<'jvm>,
+        array: ObjectPtr,
+        start: jni_sys::jsize,
+        len: usize,
+        elements: *const Self,
+    ) -> crate::Result<'jvm, ()>;
+}
+
+impl<T: ArrayElement> JavaArray<T> {
+    /// Borrows the elements of this array in place, without copying them, for the duration of `op`.
+    /// This is the fastest way to read a large array, but the JVM may suspend garbage collection (and
+    /// block other threads) until `op` returns, so `op` must be short.
+    ///
+    /// # Safety
+    ///
+    /// `op` must not call into the JVM in any way, e.g. via a nested [`Jvm::with`], a call to
+    /// `execute()`, or another `Jvm` that it captured. The elements must not be modified while `op`
+    /// runs, neither by Java code on another thread nor from Rust through another borrow of the same array.
+    pub unsafe fn with_critical<'jvm, R>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        op: impl FnOnce(&[T]) -> R,
+    ) -> crate::Result<'jvm, R> {
+        // Nothing was modified, so there is nothing to copy back
+        let critical = Critical::new(self, jvm, jni_sys::JNI_ABORT)?;
+        Ok(op(critical.as_slice()))
+    }
+
+    /// Like [`with_critical`](JavaArray::with_critical), but allows `op` to modify the elements.
+    ///
+    /// # Safety
+    ///
+    /// As for [`with_critical`](JavaArray::with_critical); in addition, the elements must not be read in
+    /// any other way while `op` runs.
+    pub unsafe fn with_critical_mut<'jvm, R>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        op: impl FnOnce(&mut [T]) -> R,
+    ) -> crate::Result<'jvm, R> {
+        let mut critical = Critical::new(self, jvm, 0)?;
+        Ok(op(critical.as_slice_mut()))
+    }
+
+    /// Borrows the elements of this array, which the JVM either pins in place or copies. Unlike
+    /// [`with_critical`](JavaArray::with_critical), the JVM remains fully usable while the returned
+    /// guard is alive, through [`ArrayElements::jvm`]. Modifications are written back to the array when
+    /// the guard is dropped (or [`commit`](ArrayElements::commit)ted), unless it is
+    /// [`abort`](ArrayElements::abort)ed.
+    ///
+    /// # Safety
+    ///
+    /// If the JVM pins the array in place, the guard's slice is the array's own memory. While the guard is
+    /// alive, the elements must not be modified by Java code (on any thread), nor from Rust through another
+    /// borrow of the same array (e.g. in a nested [`Jvm::with`] call, or through [`ArrayElements::jvm`]);
+    /// if the guard is used to modify them, they must not be read in any other way either.
+    pub unsafe fn elements<'a, 'jvm>(
+        &'a self,
+        jvm: &'a mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, ArrayElements<'a, 'jvm, T>> {
+        let env = jvm.env();
+        let len = self.length().execute_with(jvm)? as usize;
+        let mut is_copy = jni_sys::JNI_FALSE;
+        // SAFETY: `self` is a valid reference to an array of `T`
+        let elements = unsafe { T::get_elements(env, self.as_raw(), &mut is_copy) };
+        let Some(elements) = NonNull::new(elements) else {
+            env.check_exception()?;
+            return Err(Error::JvmInternal(format!(
+                "failed to get the elements of `{}[{len}]`",
+                std::any::type_name::<T>()
+            )));
+        };
+        Ok(ArrayElements {
+            jvm,
+            array: self,
+            elements,
+            len,
+            is_copy: is_copy == jni_sys::JNI_TRUE,
+        })
+    }
+}
+
+/// Releases a critical array region when dropped, even if the closure borrowing it panics.
+struct Critical<'a, 'jvm, T: JavaType> {
+    env: EnvPtr<'jvm>,
+    array: &'a JavaArray<T>,
+    elements: NonNull<T>,
+    len: usize,
+    mode: jni_sys::jint,
+}
+
+impl<'a, 'jvm, T: JavaType> Critical<'a, 'jvm, T> {
+    fn new(
+        array: &'a JavaArray<T>,
+        jvm: &mut Jvm<'jvm>,
+        mode: jni_sys::jint,
+    ) -> crate::Result<'jvm, Self> {
+        let env = jvm.env();
+        let len = array.length().execute_with(jvm)? as usize;
+        let array_raw = array.as_raw();
+        // SAFETY: `array` is a valid reference to an array
+        let elements = unsafe {
+            env.invoke_unchecked(
+                |env| env.GetPrimitiveArrayCritical,
+                |env, f| f(env, array_raw.as_ptr(), std::ptr::null_mut()),
+            )
+        };
+        let Some(elements) = NonNull::new(elements.cast::<T>()) else {
+            env.check_exception()?;
+            return Err(Error::JvmInternal(format!(
+                "failed to get the elements of `{}[{len}]`",
+                std::any::type_name::<T>()
+            )));
+        };
+        Ok(Critical {
+            env,
+            array,
+            elements,
+            len,
+            mode,
+        })
+    }
+
+    fn as_slice(&self) -> &[T] {
+        // SAFETY: the JVM gave us `len` elements of type `T`
+        unsafe { std::slice::from_raw_parts(self.elements.as_ptr(), self.len) }
+    }
+
+    fn as_slice_mut(&mut self) -> &mut [T] {
+        // SAFETY: as above, and the elements are not aliased until they are released
+        unsafe { std::slice::from_raw_parts_mut(self.elements.as_ptr(), self.len) }
+    }
+}
+
+impl<T: JavaType> Drop for Critical<'_, '_, T> {
+    fn drop(&mut self) {
+        let array_raw = self.array.as_raw();
+        let elements = self.elements.as_ptr().cast();
+        // SAFETY: `elements` were returned by `GetPrimitiveArrayCritical` for this array
+        unsafe {
+            self.env.invoke_unchecked(
+                |env| env.ReleasePrimitiveArrayCritical,
+                |env, f| f(env, array_raw.as_ptr(), elements, self.mode),
+            )
+        }
+    }
+}
+
+/// The elements of a Java primitive array, borrowed with [`JavaArray::elements`]. Dereferences to a
+/// slice of the elements.
+///
+/// If the JVM made a copy of the elements ([`is_copy`](ArrayElements::is_copy)), modifications only
+/// become visible to Java when they are committed, which happens when the guard is dropped.
+pub struct ArrayElements<'a, 'jvm, T: ArrayElement> {
+    // Borrowed mutably so that the elements cannot be borrowed a second time while the guard is alive
+    jvm: &'a mut Jvm<'jvm>,
+    array: &'a JavaArray<T>,
+    elements: NonNull<T>,
+    len: usize,
+    is_copy: bool,
+}
+
+impl<'jvm, T: ArrayElement> ArrayElements<'_, 'jvm, T> {
+    /// The JVM, to be used while the elements are borrowed.
+    pub fn jvm(&mut self) -> &mut Jvm<'jvm> {
+        self.jvm
+    }
+
+    /// True if the JVM copied the elements rather than pinning the array in place.
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+
+    /// Writes any modifications back to the array, keeping the elements borrowed.
+    pub fn commit(&mut self) {
+        // SAFETY: the elements have not been released yet
+        unsafe { self.release(jni_sys::JNI_COMMIT) }
+    }
+
+    /// Releases the elements, discarding any modifications that have not been committed yet (if the
+    /// elements are a copy).
+    pub fn abort(self) {
+        let mut this = std::mem::ManuallyDrop::new(self);
+        // SAFETY: the elements have not been released yet, and are not used afterwards
+        unsafe { this.release(jni_sys::JNI_ABORT) }
+    }
+
+    /// # Safety
+    ///
+    /// The elements must not have been released yet (unless `mode` is `JNI_COMMIT`).
+    unsafe fn release(&mut self, mode: jni_sys::jint) {
+        T::release_elements(
+            self.jvm.env(),
+            self.array.as_raw(),
+            self.elements.as_ptr(),
+            mode,
+        )
+    }
+}
+
+impl<T: ArrayElement> Deref for ArrayElements<'_, '_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // SAFETY: the JVM gave us `len` elements of type `T`
+        unsafe { std::slice::from_raw_parts(self.elements.as_ptr(), self.len) }
+    }
+}
+
+impl<T: ArrayElement> DerefMut for ArrayElements<'_, '_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // SAFETY: as above, and the elements are only reachable through this guard
+        unsafe { std::slice::from_raw_parts_mut(self.elements.as_ptr(), self.len) }
+    }
+}
+
+impl<T: ArrayElement> Drop for ArrayElements<'_, '_, T> {
+    fn drop(&mut self) {
+        // SAFETY: the elements have not been released yet, and are not used afterwards
+        unsafe { self.release(0) }
+    }
+}
+
 macro_rules! primivite_array {
-    ($([$rust:ty]: $java_name:literal $java_ty:ident $new_fn:ident $get_fn:ident $set_fn:ident,)*) => {
+    ($([$rust:ty]: $java_name:literal $java_ty:ident $new_fn:ident $get_fn:ident $set_fn:ident $get_elements_fn:ident $release_elements_fn:ident,)*) => {
         $(
+            impl ArrayElement for $rust {
+                unsafe fn get_elements(
+                    env: EnvPtr<'_>,
+                    array: ObjectPtr,
+                    is_copy: *mut jni_sys::jboolean,
+                ) -> *mut Self {
+                    env.invoke_unchecked(|env| env.$get_elements_fn, |env, f| f(env, array.as_ptr(), is_copy))
+                        .cast::<$rust>()
+                }
+
+                unsafe fn release_elements(
+                    env: EnvPtr<'_>,
+                    array: ObjectPtr,
+                    elements: *mut Self,
+                    mode: jni_sys::jint,
+                ) {
+                    env.invoke_unchecked(|env| env.$release_elements_fn, |env, f| f(
+                        env,
+                        array.as_ptr(),
+                        elements.cast::<jni_sys::$java_ty>(),
+                        mode,
+                    ))
+                }
+
+                unsafe fn get_region<'jvm>(
+                    env: EnvPtr<'jvm>,
+                    array: ObjectPtr,
+                    start: jni_sys::jsize,
+                    len: usize,
+                    buffer: *mut Self,
+                ) -> crate::Result<'jvm, ()> {
+                    let Ok(len) = len.try_into() else {
+                        return Err(Error::SliceTooLong(len))
+                    };
+                    env.invoke(|env| env.$get_fn, |env, f| f(
+                        env,
+                        array.as_ptr(),
+                        start,
+                        len,
+                        buffer.cast::<jni_sys::$java_ty>(),
+                    ))
+                }
+
+                unsafe fn set_region<'jvm>(
+                    env: EnvPtr<'jvm>,
+                    array: ObjectPtr,
+                    start: jni_sys::jsize,
+                    len: usize,
+                    elements: *const Self,
+                ) -> crate::Result<'jvm, ()> {
+                    let Ok(len) = len.try_into() else {
+                        return Err(Error::SliceTooLong(len))
+                    };
+                    env.invoke(|env| env.$set_fn, |env, f| f(
+                        env,
+                        array.as_ptr(),
+                        start,
+                        len,
+                        elements.cast::<jni_sys::$java_ty>(),
+                    ))
+                }
+            }
+
+            impl ArrayAccess for $rust {
+                type Element<'jvm> = $rust;
+
+                fn get_element<'jvm>(
+                    jvm: &mut Jvm<'jvm>,
+                    array: &JavaArray<Self>,
+                    index: jni_sys::jsize,
+                ) -> crate::Result<'jvm, Self::Element<'jvm>> {
+                    let mut element = std::mem::MaybeUninit::<$rust>::uninit();
+                    unsafe {
+                        // SAFETY: `array` is a valid reference to an array of `$rust`; `element` is
+                        // only read if it was written
+                        <$rust>::get_region(jvm.env(), array.as_raw(), index, 1, element.as_mut_ptr())?;
+                        Ok(element.assume_init())
+                    }
+                }
+            }
+
+            impl IntoArrayElement<$rust> for $rust {
+                fn set_element<'jvm>(
+                    &self,
+                    jvm: &mut Jvm<'jvm>,
+                    array: &JavaArray<$rust>,
+                    index: jni_sys::jsize,
+                ) -> crate::Result<'jvm, ()> {
+                    // SAFETY: `array` is a valid reference to an array of `$rust`
+                    unsafe { <$rust>::set_region(jvm.env(), array.as_raw(), index, 1, self) }
+                }
+            }
+
             impl JvmOp for &[$rust] {
                 type Output<'jvm> = Local<'jvm, JavaArray<$rust>>;
 
@@ -210,14 +1042,34 @@ macro_rules! primivite_array {
     };
 }
 
+impl IntoRust<Vec<u8>> for &JavaArray<i8> {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Vec<u8>> {
+        let len = self.length().execute_with(jvm)? as usize;
+        let mut bytes = Vec::<u8>::with_capacity(len);
+        // SAFETY: `self` is a valid reference to a `byte[]`, and `bytes` has room for `len` bytes,
+        // which have the same layout whether signed or not
+        unsafe {
+            i8::get_region(
+                jvm.env(),
+                self.as_raw(),
+                0,
+                len,
+                bytes.as_mut_ptr().cast::<i8>(),
+            )?;
+            bytes.set_len(len);
+        }
+        Ok(bytes)
+    }
+}
+
 // Bool is represented as u8 in JNI
 primivite_array! {
-    [bool]: "boolean" jboolean NewBooleanArray GetBooleanArrayRegion SetBooleanArrayRegion,
-    [i8]: "byte" jbyte NewByteArray GetByteArrayRegion SetByteArrayRegion,
-    [u16]: "char" jchar NewCharArray GetCharArrayRegion SetCharArrayRegion,
-    [i16]: "short" jshort NewShortArray GetShortArrayRegion SetShortArrayRegion,
-    [i32]: "int" jint NewIntArray GetIntArrayRegion SetIntArrayRegion,
-    [i64]: "long" jlong NewLongArray GetLongArrayRegion SetLongArrayRegion,
-    [f32]: "float" jfloat NewFloatArray GetFloatArrayRegion SetFloatArrayRegion,
-    [f64]: "double" jdouble NewDoubleArray GetDoubleArrayRegion SetDoubleArrayRegion,
+    [bool]: "boolean" jboolean NewBooleanArray GetBooleanArrayRegion SetBooleanArrayRegion GetBooleanArrayElements ReleaseBooleanArrayElements,
+    [i8]: "byte" jbyte NewByteArray GetByteArrayRegion SetByteArrayRegion GetByteArrayElements ReleaseByteArrayElements,
+    [u16]: "char" jchar NewCharArray GetCharArrayRegion SetCharArrayRegion GetCharArrayElements ReleaseCharArrayElements,
+    [i16]: "short" jshort NewShortArray GetShortArrayRegion SetShortArrayRegion GetShortArrayElements ReleaseShortArrayElements,
+    [i32]: "int" jint NewIntArray GetIntArrayRegion SetIntArrayRegion GetIntArrayElements ReleaseIntArrayElements,
+    [i64]: "long" jlong NewLongArray GetLongArrayRegion SetLongArrayRegion GetLongArrayElements ReleaseLongArrayElements,
+    [f32]: "float" jfloat NewFloatArray GetFloatArrayRegion SetFloatArrayRegion GetFloatArrayElements ReleaseFloatArrayElements,
+    [f64]: "double" jdouble NewDoubleArray GetDoubleArrayRegion SetDoubleArrayRegion GetDoubleArrayElements ReleaseDoubleArrayElements,
 }
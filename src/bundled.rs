@@ -0,0 +1,174 @@
+//! Java classes that ship with duchess itself. Their sources live in the `java` directory at the root of the crate;
+//! `build.rs` compiles them and the resulting class files are embedded into the library. They are defined into the
+//! JVM (using the bootstrap class loader, so that they are visible everywhere) the first time duchess touches it.
+
+use std::ffi::CStr;
+
+use once_cell::sync::OnceCell;
+
+use crate::{find::find_class, Error, Jvm};
+
+// The generated mirrors include views of each method that duchess itself doesn't need.
+#[allow(dead_code)]
+mod auto {
+    // Make current crate available as `duchess` for use by the generated code.
+    // NB. in doctests, the current crate is already available as duchess.
+    #[cfg(not(doctest))]
+    use crate as duchess;
+
+    duchess_macro::java_package! {
+        package duchess_rs;
+
+        public class duchess_rs.RustPanicException extends java.lang.RuntimeException {
+            public duchess_rs.RustPanicException(java.lang.String, java.lang.String);
+            public java.lang.String getRustBacktrace();
+        }
+
+        public class duchess_rs.RustCompletableFuture<T> extends java.util.concurrent.CompletableFuture<T> {
+            public duchess_rs.RustCompletableFuture();
+            public boolean completeNull();
+        }
+
+        public abstract class duchess_rs.RustClosure {
+        }
+
+        public final class duchess_rs.RustFunction extends duchess_rs.RustClosure implements java.util.function.Function<java.lang.Object, java.lang.Object> {
+            public duchess_rs.RustFunction(long);
+        }
+
+        public final class duchess_rs.RustBiFunction extends duchess_rs.RustClosure implements java.util.function.BiFunction<java.lang.Object, java.lang.Object, java.lang.Object> {
+            public duchess_rs.RustBiFunction(long);
+        }
+
+        public final class duchess_rs.RustConsumer extends duchess_rs.RustClosure implements java.util.function.Consumer<java.lang.Object> {
+            public duchess_rs.RustConsumer(long);
+        }
+
+        public final class duchess_rs.RustBiConsumer extends duchess_rs.RustClosure implements java.util.function.BiConsumer<java.lang.Object, java.lang.Object> {
+            public duchess_rs.RustBiConsumer(long);
+        }
+
+        public final class duchess_rs.RustSupplier extends duchess_rs.RustClosure implements java.util.function.Supplier<java.lang.Object> {
+            public duchess_rs.RustSupplier(long);
+        }
+
+        public final class duchess_rs.RustPredicate extends duchess_rs.RustClosure implements java.util.function.Predicate<java.lang.Object> {
+            public duchess_rs.RustPredicate(long);
+        }
+
+        public final class duchess_rs.RustRunnable extends duchess_rs.RustClosure implements java.lang.Runnable {
+            public duchess_rs.RustRunnable(long);
+        }
+
+        public final class duchess_rs.RustCallable extends duchess_rs.RustClosure implements java.util.concurrent.Callable<java.lang.Object> {
+            public duchess_rs.RustCallable(long);
+        }
+
+        public final class duchess_rs.RustIterator extends duchess_rs.RustClosure implements java.util.Iterator<java.lang.Object> {
+            public duchess_rs.RustIterator(long);
+        }
+
+        public final class duchess_rs.RustSpliterator extends duchess_rs.RustClosure implements java.util.Spliterator<java.lang.Object> {
+            public duchess_rs.RustSpliterator(long);
+            public java.util.stream.Stream<java.lang.Object> stream();
+        }
+
+        public final class duchess_rs.RustList implements java.util.List<java.lang.Object> {
+            public duchess_rs.RustList(int, java.util.function.Function<java.lang.Object, java.lang.Object>);
+        }
+
+        public final class duchess_rs.RustMap implements java.util.Map<java.lang.Object, java.lang.Object> {
+            public duchess_rs.RustMap(java.util.function.Function<java.lang.Object, java.lang.Object>, int, java.util.function.Supplier<java.lang.Object>);
+        }
+
+        public final class duchess_rs.RustInvocationHandler {
+            public static java.lang.Object newProxy(java.lang.Class, long);
+        }
+
+        public abstract class duchess_rs.RustObject implements java.lang.AutoCloseable {
+            public final void close();
+        }
+
+        public final class duchess_rs.RustObjectCleaner implements java.lang.Runnable {
+            public static void register(java.lang.Object, long);
+        }
+
+        public final class duchess_rs.RustFutureCompletion implements java.util.function.BiConsumer<java.lang.Object, java.lang.Throwable> {
+            public duchess_rs.RustFutureCompletion(long);
+            public long detach();
+        }
+    }
+}
+
+pub use auto::duchess_rs::*;
+
+/// The module itself, for use by the `#[java_function]`s implementing the bundled native methods.
+pub(crate) use auto::duchess_rs;
+
+struct BundledClass {
+    jni_name: &'static CStr,
+    bytes: &'static [u8],
+}
+
+macro_rules! bundled_class {
+    ($jni_name:literal) => {
+        BundledClass {
+            jni_name: match CStr::from_bytes_with_nul(concat!($jni_name, "\0").as_bytes()) {
+                Ok(name) => name,
+                Err(_) => panic!("class name contains a nul byte"),
+            },
+            bytes: include_bytes!(concat!(env!("OUT_DIR"), "/java/", $jni_name, ".class")),
+        }
+    };
+}
+
+/// Classes are listed such that superclasses are defined before their subclasses.
+static BUNDLED_CLASSES: &[BundledClass] = &[
+    bundled_class!("duchess_rs/RustPanicException"),
+    bundled_class!("duchess_rs/RustFutureCompletion"),
+    bundled_class!("duchess_rs/RustCompletableFuture"),
+    bundled_class!("duchess_rs/RustClosure"),
+    bundled_class!("duchess_rs/RustFunction"),
+    bundled_class!("duchess_rs/RustBiFunction"),
+    bundled_class!("duchess_rs/RustConsumer"),
+    bundled_class!("duchess_rs/RustBiConsumer"),
+    bundled_class!("duchess_rs/RustSupplier"),
+    bundled_class!("duchess_rs/RustPredicate"),
+    bundled_class!("duchess_rs/RustRunnable"),
+    bundled_class!("duchess_rs/RustCallable"),
+    bundled_class!("duchess_rs/RustIterator"),
+    bundled_class!("duchess_rs/RustSpliterator"),
+    bundled_class!("duchess_rs/RustList"),
+    bundled_class!("duchess_rs/RustMap"),
+    bundled_class!("duchess_rs/RustMapEntrySet"),
+    bundled_class!("duchess_rs/RustMapEntryIterator"),
+    bundled_class!("duchess_rs/RustInvocationHandler"),
+    bundled_class!("duchess_rs/RustObject"),
+    bundled_class!("duchess_rs/RustObjectCleaner"),
+];
+
+/// Defines the bundled classes into the JVM, unless that has already been done.
+pub(crate) fn define_bundled_classes<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+    static DEFINED: OnceCell<()> = OnceCell::new();
+
+    DEFINED
+        .get_or_try_init(|| {
+            for class in BUNDLED_CLASSES {
+                // The classes may already be known to the JVM, e.g. if it was launched by another
+                // copy of duchess that has since defined them.
+                match find_class(jvm, class.jni_name) {
+                    Ok(_) => continue,
+                    Err(Error::Thrown(_)) => {}
+                    Err(e) => return Err(e),
+                }
+
+                // SAFETY: the bytes were produced by `javac` for the class named `jni_name`
+                unsafe { jvm.env().define_class(class.jni_name, None, class.bytes) }?;
+            }
+            jvm.register_native_methods(&crate::future::native_methods())?;
+            jvm.register_native_methods(&crate::closure::native_methods())?;
+            jvm.register_native_methods(&crate::proxy::native_methods())?;
+            jvm.register_native_methods(&crate::rust_object::native_methods())
+        })
+        .copied()
+}
@@ -0,0 +1,141 @@
+//! Zero-copy interop with direct `java.nio.ByteBuffer`s, whose contents live outside of the Java heap.
+
+use std::ffi::c_void;
+
+use crate::{
+    java::{lang::IllegalArgumentException, nio::ByteBuffer},
+    jvm::{new_exception, JavaObjectExt},
+    rust_object::drop_when_collected,
+    Error, Jvm, JvmOp, Local,
+};
+
+impl<'jvm> Jvm<'jvm> {
+    /// Creates a direct `java.nio.ByteBuffer` that views the bytes of `bytes`, without copying them.
+    /// Java owns `bytes` from then on: it is dropped once the buffer (and any buffer derived from it,
+    /// e.g. with `slice`) has been garbage collected.
+    /// Returns [`Error::SliceTooLong`] if there are more than `i32::MAX` bytes.
+    ///
+    /// ```rust,no_run
+    /// # use duchess::{java, prelude::*, Jvm};
+    /// # fn main() -> duchess::GlobalResult<()> {
+    /// let capacity = Jvm::with(|jvm| {
+    ///     let buffer = jvm.new_direct_byte_buffer(vec![0u8; 4096])?;
+    ///     buffer.capacity().execute_with(jvm)
+    /// })?;
+    /// assert_eq!(capacity, 4096);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_direct_byte_buffer<B>(
+        &mut self,
+        bytes: B,
+    ) -> crate::Result<'jvm, Local<'jvm, ByteBuffer>>
+    where
+        B: AsMut<[u8]> + Send + Sync + 'static,
+    {
+        // Box the bytes so that they do not move, even if `B` stores them inline (e.g., an array).
+        let mut bytes = Box::new(bytes);
+        let slice = (*bytes).as_mut();
+        // The capacity of a buffer is an `int`
+        let Ok(capacity) = i32::try_from(slice.len()) else {
+            return Err(Error::SliceTooLong(slice.len()));
+        };
+        let address = slice.as_mut_ptr();
+
+        let buffer: Option<Local<'jvm, ByteBuffer>> = unsafe {
+            self.env().invoke(
+                |env| env.NewDirectByteBuffer,
+                |env, f| f(env, address as *mut c_void, capacity.into()),
+            )
+        }?;
+        let Some(buffer) = buffer else {
+            return Err(Error::JvmInternal(
+                "failed to create a direct `java.nio.ByteBuffer`".to_string(),
+            ));
+        };
+
+        drop_when_collected(self, buffer.as_ref(), bytes)?;
+        Ok(buffer)
+    }
+
+    /// Borrows the contents of a direct `java.nio.ByteBuffer`, from index 0 to its capacity (regardless of
+    /// its position and limit), for as long as this `Jvm` is borrowed. Returns an error, thrown as an
+    /// `IllegalArgumentException`, if `buffer` is not direct.
+    ///
+    /// # Safety
+    ///
+    /// The contents must not be modified while the slice is alive: neither by Java code (on any thread),
+    /// nor through another slice returned by [`direct_buffer_mut`](Jvm::direct_buffer_mut) for the same
+    /// memory (e.g. in a nested [`Jvm::with`] call, or for a buffer created with `duplicate()` or `slice()`).
+    pub unsafe fn direct_buffer<'a>(
+        &'a mut self,
+        buffer: &'a ByteBuffer,
+    ) -> crate::Result<'jvm, &'a [u8]> {
+        let (address, capacity) = self.direct_buffer_region(buffer)?;
+        // SAFETY: the buffer keeps its memory alive while it is reachable, which it is for `'a`;
+        // the caller guarantees that it is not modified
+        Ok(unsafe { std::slice::from_raw_parts(address, capacity) })
+    }
+
+    /// Like [`direct_buffer`](Jvm::direct_buffer), but borrows the contents mutably. Returns an error if
+    /// `buffer` is read-only.
+    ///
+    /// # Safety
+    ///
+    /// The contents must not be accessed in any other way while the slice is alive: neither by Java code
+    /// (on any thread), nor through another slice returned by `direct_buffer` or `direct_buffer_mut` for the
+    /// same memory (e.g. in a nested [`Jvm::with`] call, or for a buffer created with `duplicate()` or `slice()`).
+    pub unsafe fn direct_buffer_mut<'a>(
+        &'a mut self,
+        buffer: &'a ByteBuffer,
+    ) -> crate::Result<'jvm, &'a mut [u8]> {
+        if buffer.is_read_only().execute_with(self)? {
+            return Err(new_exception::<IllegalArgumentException>(
+                self,
+                "buffer is read-only",
+            ));
+        }
+        let (address, capacity) = self.direct_buffer_region(buffer)?;
+        // SAFETY: as for `direct_buffer`; the caller guarantees that the memory is not aliased
+        Ok(unsafe { std::slice::from_raw_parts_mut(address, capacity) })
+    }
+
+    /// Returns the address and capacity of a direct buffer. The address is only null if the capacity is 0.
+    fn direct_buffer_region(
+        &mut self,
+        buffer: &ByteBuffer,
+    ) -> crate::Result<'jvm, (*mut u8, usize)> {
+        let env = self.env();
+        let buffer_raw = buffer.as_raw();
+        // SAFETY: `buffer` is a valid reference
+        let capacity = unsafe {
+            env.invoke_unchecked(
+                |env| env.GetDirectBufferCapacity,
+                |env, f| f(env, buffer_raw.as_ptr()),
+            )
+        };
+        let Ok(capacity) = usize::try_from(capacity) else {
+            return Err(new_exception::<IllegalArgumentException>(
+                self,
+                "not a direct buffer",
+            ));
+        };
+        if capacity == 0 {
+            return Ok((std::ptr::NonNull::dangling().as_ptr(), 0));
+        }
+
+        // SAFETY: `buffer` is a valid reference
+        let address = unsafe {
+            env.invoke_unchecked(
+                |env| env.GetDirectBufferAddress,
+                |env, f| f(env, buffer_raw.as_ptr()),
+            )
+        };
+        if address.is_null() {
+            return Err(Error::JvmInternal(
+                "direct buffer has no address".to_string(),
+            ));
+        }
+        Ok((address as *mut u8, capacity))
+    }
+}
@@ -0,0 +1,249 @@
+//! A minimal writer for JVM class files, just capable enough to define the subclasses that duchess
+//! generates at runtime (see `subclass.rs`). The format is described in
+//! [chapter 4 of the JVM specification](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html).
+
+use std::collections::HashMap;
+
+pub(crate) const ACC_PUBLIC: u16 = 0x0001;
+pub(crate) const ACC_PRIVATE: u16 = 0x0002;
+pub(crate) const ACC_FINAL: u16 = 0x0010;
+pub(crate) const ACC_SUPER: u16 = 0x0020;
+pub(crate) const ACC_NATIVE: u16 = 0x0100;
+pub(crate) const ACC_SYNTHETIC: u16 = 0x1000;
+
+/// Java 8, the oldest version that duchess supports. Classes of this version do not need a
+/// `StackMapTable` for straight-line code.
+const MAJOR_VERSION: u16 = 52;
+
+/// Entries of the constant pool.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Constant {
+    Utf8(String),
+    Class(u16),
+    NameAndType(u16, u16),
+    Fieldref(u16, u16),
+    Methodref(u16, u16),
+}
+
+/// The bytecode of a method.
+pub(crate) struct Code {
+    pub(crate) max_stack: u16,
+    pub(crate) max_locals: u16,
+    pub(crate) bytecode: Vec<u8>,
+}
+
+struct Member {
+    access_flags: u16,
+    name: u16,
+    descriptor: u16,
+    code: Option<Code>,
+}
+
+/// Accumulates the contents of a class file; names use the JNI form (e.g., `java/lang/Object`).
+pub(crate) struct ClassWriter {
+    constants: Vec<Constant>,
+    constant_indices: HashMap<Constant, u16>,
+    access_flags: u16,
+    this_class: u16,
+    super_class: u16,
+    interfaces: Vec<u16>,
+    fields: Vec<Member>,
+    methods: Vec<Member>,
+}
+
+impl ClassWriter {
+    pub(crate) fn new(access_flags: u16, name: &str, super_name: &str) -> Self {
+        let mut writer = ClassWriter {
+            constants: vec![],
+            constant_indices: HashMap::new(),
+            access_flags,
+            this_class: 0,
+            super_class: 0,
+            interfaces: vec![],
+            fields: vec![],
+            methods: vec![],
+        };
+        writer.this_class = writer.class(name);
+        writer.super_class = writer.class(super_name);
+        writer
+    }
+
+    fn constant(&mut self, constant: Constant) -> u16 {
+        if let Some(&index) = self.constant_indices.get(&constant) {
+            return index;
+        }
+        self.constants.push(constant.clone());
+        // Indices into the constant pool start at 1
+        let index = u16::try_from(self.constants.len()).expect("too many constants");
+        self.constant_indices.insert(constant, index);
+        index
+    }
+
+    fn utf8(&mut self, s: &str) -> u16 {
+        self.constant(Constant::Utf8(s.to_string()))
+    }
+
+    fn class(&mut self, name: &str) -> u16 {
+        let name = self.utf8(name);
+        self.constant(Constant::Class(name))
+    }
+
+    fn name_and_type(&mut self, name: &str, descriptor: &str) -> u16 {
+        let name = self.utf8(name);
+        let descriptor = self.utf8(descriptor);
+        self.constant(Constant::NameAndType(name, descriptor))
+    }
+
+    /// Returns the constant pool index of a reference to a field, for use in bytecode.
+    pub(crate) fn field_ref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        let class = self.class(class);
+        let name_and_type = self.name_and_type(name, descriptor);
+        self.constant(Constant::Fieldref(class, name_and_type))
+    }
+
+    /// Returns the constant pool index of a reference to a method, for use in bytecode.
+    pub(crate) fn method_ref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        let class = self.class(class);
+        let name_and_type = self.name_and_type(name, descriptor);
+        self.constant(Constant::Methodref(class, name_and_type))
+    }
+
+    pub(crate) fn add_interface(&mut self, name: &str) {
+        let interface = self.class(name);
+        self.interfaces.push(interface);
+    }
+
+    pub(crate) fn add_field(&mut self, access_flags: u16, name: &str, descriptor: &str) {
+        let name = self.utf8(name);
+        let descriptor = self.utf8(descriptor);
+        self.fields.push(Member {
+            access_flags,
+            name,
+            descriptor,
+            code: None,
+        });
+    }
+
+    /// Adds a method, which has `code` unless it is `native` or `abstract`.
+    pub(crate) fn add_method(
+        &mut self,
+        access_flags: u16,
+        name: &str,
+        descriptor: &str,
+        code: Option<Code>,
+    ) {
+        let name = self.utf8(name);
+        let descriptor = self.utf8(descriptor);
+        self.methods.push(Member {
+            access_flags,
+            name,
+            descriptor,
+            code,
+        });
+    }
+
+    pub(crate) fn into_bytes(mut self) -> Vec<u8> {
+        let code_attribute = self.utf8("Code");
+
+        let mut out = Output(vec![]);
+        out.u4(0xCAFEBABE);
+        out.u2(0); // minor version
+        out.u2(MAJOR_VERSION);
+
+        out.u2(self.constants.len() as u16 + 1);
+        for constant in &self.constants {
+            match constant {
+                Constant::Utf8(s) => {
+                    let bytes = cesu8::to_java_cesu8(s);
+                    out.u1(1);
+                    out.u2(u16::try_from(bytes.len()).expect("string constant too long"));
+                    out.bytes(&bytes);
+                }
+                Constant::Class(name) => {
+                    out.u1(7);
+                    out.u2(*name);
+                }
+                Constant::NameAndType(name, descriptor) => {
+                    out.u1(12);
+                    out.u2(*name);
+                    out.u2(*descriptor);
+                }
+                Constant::Fieldref(class, name_and_type) => {
+                    out.u1(9);
+                    out.u2(*class);
+                    out.u2(*name_and_type);
+                }
+                Constant::Methodref(class, name_and_type) => {
+                    out.u1(10);
+                    out.u2(*class);
+                    out.u2(*name_and_type);
+                }
+            }
+        }
+
+        out.u2(self.access_flags);
+        out.u2(self.this_class);
+        out.u2(self.super_class);
+        out.u2(self.interfaces.len() as u16);
+        for interface in &self.interfaces {
+            out.u2(*interface);
+        }
+
+        for members in [&self.fields, &self.methods] {
+            out.u2(members.len() as u16);
+            for member in members {
+                out.u2(member.access_flags);
+                out.u2(member.name);
+                out.u2(member.descriptor);
+                match &member.code {
+                    None => out.u2(0),
+                    Some(code) => {
+                        out.u2(1);
+                        out.u2(code_attribute);
+                        // max_stack + max_locals + code_length + code + exception_table_length + attributes_count
+                        out.u4(2 + 2 + 4 + code.bytecode.len() as u32 + 2 + 2);
+                        out.u2(code.max_stack);
+                        out.u2(code.max_locals);
+                        out.u4(code.bytecode.len() as u32);
+                        out.bytes(&code.bytecode);
+                        out.u2(0); // exception table
+                        out.u2(0); // attributes
+                    }
+                }
+            }
+        }
+
+        out.u2(0); // attributes
+        out.0
+    }
+}
+
+/// Big-endian output, as the class file format requires.
+struct Output(Vec<u8>);
+
+impl Output {
+    fn u1(&mut self, v: u8) {
+        self.0.push(v);
+    }
+
+    fn u2(&mut self, v: u16) {
+        self.0.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn u4(&mut self, v: u32) {
+        self.0.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn bytes(&mut self, v: &[u8]) {
+        self.0.extend_from_slice(v);
+    }
+}
+
+/// Opcodes used by the generated bytecode.
+pub(crate) mod opcodes {
+    pub(crate) const ALOAD_0: u8 = 0x2a;
+    pub(crate) const LLOAD_1: u8 = 0x1f;
+    pub(crate) const INVOKESPECIAL: u8 = 0xb7;
+    pub(crate) const PUTFIELD: u8 = 0xb5;
+    pub(crate) const RETURN: u8 = 0xb1;
+}
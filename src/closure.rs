@@ -0,0 +1,368 @@
+//! Passes Rust closures to Java as instances of functional interfaces like `java.util.function.Function`.
+
+use std::sync::Arc;
+
+// Make current crate available as `duchess` for use by the generated code.
+// NB. in doctests, the current crate is already available as duchess.
+#[cfg(not(doctest))]
+use crate as duchess;
+
+use crate::{
+    bundled::{define_bundled_classes, duchess_rs},
+    cast::Upcast,
+    java,
+    java::lang::Object,
+    java_function,
+    jvm::{JavaObjectExt, ToJavaScalar},
+    link::JavaFn,
+    plumbing::{JavaFunction, ToJavaImpl},
+    proxy::{object_result, scalar_result, void_result},
+    Error, IntoRust, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// A Rust closure that can be converted (with [`to_java`](crate::ToJava::to_java)) into a Java
+/// object implementing one of the following functional interfaces:
+///
+/// | Rust closure                  | Java interface                                |
+/// | ----------------------------- | --------------------------------------------- |
+/// | `Fn(A) -> R`                  | `java.util.function.Function<T, U>`           |
+/// | `Fn(A, B) -> R`               | `java.util.function.BiFunction<T, U, V>`      |
+/// | `Fn(A)`                       | `java.util.function.Consumer<T>`              |
+/// | `Fn(A, B)`                    | `java.util.function.BiConsumer<T, U>`         |
+/// | `Fn() -> R`                   | `java.util.function.Supplier<T>`              |
+/// | `Fn(A) -> bool`               | `java.util.function.Predicate<T>`             |
+/// | `Fn()`                        | `java.lang.Runnable`                          |
+/// | `Fn() -> R`                   | `java.util.concurrent.Callable<T>`            |
+///
+/// Each argument is converted from its Java type to the Rust type the closure expects via
+/// [`IntoRust`] (e.g., a `java.lang.String` to a `String`); passing `null` throws a
+/// `NullPointerException`. The result is converted to Java as for the return value of a
+/// [`java_function`](crate::java_function): in particular, it may be a `Result` whose error is thrown
+/// as a Java exception. Panics are thrown as [`RustPanicException`](crate::RustPanicException)s.
+///
+/// The closure is dropped once the Java object has been garbage collected.
+///
+/// ```rust,no_run
+/// # use duchess::{java, prelude::*, Closure};
+/// # fn main() -> duchess::GlobalResult<()> {
+/// let map = java::util::HashMap::<java::lang::String, java::lang::String>::new().global().execute()?;
+/// let append = Closure::new(|old: String, new: String| format!("{old}, {new}"));
+/// let merged: Option<String> = map
+///     .merge("greeting", "hello", append.to_java::<java::util::function::BiFunction<_, _, _>>())
+///     .to_rust()
+///     .execute()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive_where::derive_where(Clone)]
+pub struct Closure<Args, R> {
+    f: Arc<dyn RustFn<Args, Output = R>>,
+}
+
+impl<Args, R> Closure<Args, R> {
+    pub fn new(f: impl RustFn<Args, Output = R>) -> Self {
+        Closure { f: Arc::new(f) }
+    }
+}
+
+/// Rust closures that take the arguments `Args` (a tuple) and can be wrapped in a [`Closure`].
+/// Implemented for closures with up to two arguments.
+pub trait RustFn<Args>: Send + Sync + 'static {
+    type Output;
+
+    fn call(&self, args: Args) -> Self::Output;
+}
+
+impl<F, R> RustFn<()> for F
+where
+    F: Fn() -> R + Send + Sync + 'static,
+{
+    type Output = R;
+
+    fn call(&self, (): ()) -> R {
+        self()
+    }
+}
+
+impl<F, A, R> RustFn<(A,)> for F
+where
+    F: Fn(A) -> R + Send + Sync + 'static,
+{
+    type Output = R;
+
+    fn call(&self, (a,): (A,)) -> R {
+        self(a)
+    }
+}
+
+impl<F, A, B, R> RustFn<(A, B)> for F
+where
+    F: Fn(A, B) -> R + Send + Sync + 'static,
+{
+    type Output = R;
+
+    fn call(&self, (a, b): (A, B)) -> R {
+        self(a, b)
+    }
+}
+
+/// The type-erased closure that a `duchess_rs.RustClosure` points to. It is invoked with the
+/// arguments passed by Java (unused arguments are `null`) and returns the (possibly boxed) result.
+pub(crate) type Invoke = dyn for<'jvm> Fn(
+        &mut Jvm<'jvm>,
+        Option<&Object>,
+        Option<&Object>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>>
+    + Send
+    + Sync;
+
+/// Creates the Java object wrapping `invoke` by calling `new` (one of the constructors of the
+/// `duchess_rs.RustClosure` subclasses) with a pointer that the Java object takes ownership of.
+pub(crate) fn new_java_closure<'jvm, C, J>(
+    jvm: &mut Jvm<'jvm>,
+    new: impl FnOnce(i64) -> C,
+    invoke: Box<Invoke>,
+) -> crate::Result<'jvm, Local<'jvm, J>>
+where
+    for<'a> C: JvmOp<Output<'a> = Local<'a, duchess_rs::RustClosure>>,
+    J: JavaObject,
+{
+    define_bundled_classes(jvm)?;
+
+    let closure: *mut Box<Invoke> = Box::into_raw(Box::new(invoke));
+    match new(closure as i64).execute_with(jvm) {
+        // SAFETY: each `RustClosure` subclass implements its functional interface `J`
+        Ok(object) => Ok(unsafe { object.cast::<J>() }),
+        Err(err) => {
+            // SAFETY: the Java object wasn't created, so we still own `closure`
+            drop(unsafe { Box::from_raw(closure) });
+            Err(err)
+        }
+    }
+}
+
+/// Converts an argument passed by Java, which is known to be a `T`, to the closure argument type `A`.
+pub(crate) fn argument<'jvm, T, A>(
+    jvm: &mut Jvm<'jvm>,
+    arg: Option<&Object>,
+) -> crate::Result<'jvm, A>
+where
+    T: JavaObject,
+    for<'a> &'a T: IntoRust<A>,
+{
+    let arg = arg.ok_or(Error::NullDeref)?;
+    // SAFETY: the functional interface was instantiated with `T`, so Java only passes `T`s
+    let arg: &T = unsafe { T::from_raw(arg.as_raw()) };
+    arg.into_rust(jvm)
+}
+
+impl<T, U, A, R> ToJavaImpl<java::util::function::Function<T, U>> for Closure<(A,), R>
+where
+    T: JavaObject,
+    U: Upcast<Object>,
+    for<'a> &'a T: IntoRust<A>,
+    A: 'static,
+    R: ToJavaImpl<U> + 'static,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::function::Function<T, U>>>> {
+        let f = rust.f.clone();
+        new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustFunction::new(closure).upcast(),
+            Box::new(move |jvm, a, _| {
+                let a = argument::<T, A>(jvm, a)?;
+                object_result::<U, R>(jvm, f.call((a,)))
+            }),
+        )
+        .map(Some)
+    }
+}
+
+impl<T, U, V, A, B, R> ToJavaImpl<java::util::function::BiFunction<T, U, V>> for Closure<(A, B), R>
+where
+    T: JavaObject,
+    U: JavaObject,
+    V: Upcast<Object>,
+    for<'a> &'a T: IntoRust<A>,
+    for<'a> &'a U: IntoRust<B>,
+    A: 'static,
+    B: 'static,
+    R: ToJavaImpl<V> + 'static,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::function::BiFunction<T, U, V>>>> {
+        let f = rust.f.clone();
+        new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustBiFunction::new(closure).upcast(),
+            Box::new(move |jvm, a, b| {
+                let a = argument::<T, A>(jvm, a)?;
+                let b = argument::<U, B>(jvm, b)?;
+                object_result::<V, R>(jvm, f.call((a, b)))
+            }),
+        )
+        .map(Some)
+    }
+}
+
+impl<T, A, R> ToJavaImpl<java::util::function::Consumer<T>> for Closure<(A,), R>
+where
+    T: JavaObject,
+    for<'a> &'a T: IntoRust<A>,
+    A: 'static,
+    R: ToJavaScalar<()> + 'static,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::function::Consumer<T>>>> {
+        let f = rust.f.clone();
+        new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustConsumer::new(closure).upcast(),
+            Box::new(move |jvm, a, _| {
+                let a = argument::<T, A>(jvm, a)?;
+                void_result(jvm, f.call((a,)))
+            }),
+        )
+        .map(Some)
+    }
+}
+
+impl<T, U, A, B, R> ToJavaImpl<java::util::function::BiConsumer<T, U>> for Closure<(A, B), R>
+where
+    T: JavaObject,
+    U: JavaObject,
+    for<'a> &'a T: IntoRust<A>,
+    for<'a> &'a U: IntoRust<B>,
+    A: 'static,
+    B: 'static,
+    R: ToJavaScalar<()> + 'static,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::function::BiConsumer<T, U>>>> {
+        let f = rust.f.clone();
+        new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustBiConsumer::new(closure).upcast(),
+            Box::new(move |jvm, a, b| {
+                let a = argument::<T, A>(jvm, a)?;
+                let b = argument::<U, B>(jvm, b)?;
+                void_result(jvm, f.call((a, b)))
+            }),
+        )
+        .map(Some)
+    }
+}
+
+impl<T, R> ToJavaImpl<java::util::function::Supplier<T>> for Closure<(), R>
+where
+    T: Upcast<Object>,
+    R: ToJavaImpl<T> + 'static,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::function::Supplier<T>>>> {
+        let f = rust.f.clone();
+        new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustSupplier::new(closure).upcast(),
+            Box::new(move |jvm, _, _| object_result::<T, R>(jvm, f.call(()))),
+        )
+        .map(Some)
+    }
+}
+
+impl<T, A, R> ToJavaImpl<java::util::function::Predicate<T>> for Closure<(A,), R>
+where
+    T: JavaObject,
+    for<'a> &'a T: IntoRust<A>,
+    A: 'static,
+    R: ToJavaScalar<bool> + 'static,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::function::Predicate<T>>>> {
+        let f = rust.f.clone();
+        new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustPredicate::new(closure).upcast(),
+            Box::new(move |jvm, a, _| {
+                let a = argument::<T, A>(jvm, a)?;
+                // `RustPredicate.test` unboxes the result
+                scalar_result::<bool, R>(jvm, f.call((a,)))
+            }),
+        )
+        .map(Some)
+    }
+}
+
+impl<R> ToJavaImpl<java::lang::Runnable> for Closure<(), R>
+where
+    R: ToJavaScalar<()> + 'static,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::lang::Runnable>>> {
+        let f = rust.f.clone();
+        new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustRunnable::new(closure).upcast(),
+            Box::new(move |jvm, _, _| void_result(jvm, f.call(()))),
+        )
+        .map(Some)
+    }
+}
+
+impl<T, R> ToJavaImpl<java::util::concurrent::Callable<T>> for Closure<(), R>
+where
+    T: Upcast<Object>,
+    R: ToJavaImpl<T> + 'static,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::concurrent::Callable<T>>>> {
+        let f = rust.f.clone();
+        new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustCallable::new(closure).upcast(),
+            Box::new(move |jvm, _, _| object_result::<T, R>(jvm, f.call(()))),
+        )
+        .map(Some)
+    }
+}
+
+#[java_function(duchess_rs.RustClosure::call)]
+fn call<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    closure: i64,
+    a: Option<&Object>,
+    b: Option<&Object>,
+) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+    // SAFETY: `closure` was created by `new_java_closure` and is only freed once the Java object
+    // has been garbage collected, which cannot happen while it is invoking the closure.
+    let invoke = unsafe { &*(closure as *const Box<Invoke>) };
+    invoke(jvm, a, b)
+}
+
+#[java_function(duchess_rs.RustClosure::drop)]
+fn drop_closure(closure: i64) {
+    // SAFETY: `closure` was created by `new_java_closure`, and Java drops it exactly once
+    drop(unsafe { Box::from_raw(closure as *mut Box<Invoke>) });
+}
+
+/// The native methods of `duchess_rs.RustClosure`, registered when the bundled classes are defined.
+pub(crate) fn native_methods() -> Vec<JavaFunction> {
+    vec![call::java_fn(), drop_closure::java_fn()]
+}
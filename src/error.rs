@@ -1,12 +1,12 @@
 use std::{
     fmt::{Debug, Display},
+    ops::Deref,
     result,
+    sync::Arc,
 };
 
-use thiserror::Error;
-
 use crate::AsJRef;
-use crate::{java::lang::Throwable, Global, Jvm, JvmOp, Local};
+use crate::{java::lang::Throwable, ExceptionSnapshot, Global, Jvm, Local};
 
 /// Result returned by most Java operations that may contain a local reference
 /// to a thrown exception.
@@ -16,44 +16,124 @@ pub type Result<'jvm, T> = result::Result<T, Error<Local<'jvm, Throwable>>>;
 /// exception as a global reference.
 pub type GlobalResult<T> = result::Result<T, Error<Global<Throwable>>>;
 
-#[derive(Error)]
 pub enum Error<T: AsJRef<Throwable>> {
-    /// A reference to an uncaught Java exception
-    #[error("Java invocation threw: {}", try_extract_message(.0))]
-    Thrown(T),
+    /// A reference to an uncaught Java exception, along with a snapshot of its details
+    Thrown(Thrown<T>),
 
-    #[error(
-        "slice was too long (`{0}`) to convert to a Java array, which are limited to `i32::MAX`"
-    )]
     SliceTooLong(usize),
 
-    #[error("attempted to deref a null Java object pointer")]
     NullDeref,
 
-    #[error("attempted to nest `Jvm::with` calls")]
-    NestedUsage,
-
-    #[error("JVM already exists")]
     JvmAlreadyExists,
 
     #[cfg(feature = "dylibjvm")]
-    #[error(transparent)]
-    UnableToLoadLibjvm(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    UnableToLoadLibjvm(Box<dyn std::error::Error + Send + Sync + 'static>),
 
-    #[error("{0}")]
     JvmInternal(String),
 }
 
-fn try_extract_message(exception: &impl AsJRef<Throwable>) -> String {
-    let message = Jvm::with(|jvm| {
-        let exception = jvm.local(exception.as_jref()?);
-        exception
-            .to_string()
-            .assert_not_null()
-            .to_rust()
-            .execute_with(jvm)
-    });
-    message.unwrap_or_else(|_| "<unable to get exception message>".into())
+/// An exception thrown by Java code (see [`Error::Thrown`]): a reference to the `Throwable` itself, which it derefs
+/// to, and -- unless disabled with [`JvmBuilder::snapshot_exceptions`](crate::jvm::JvmBuilder::snapshot_exceptions)
+/// -- an [`ExceptionSnapshot`] of its details, taken when duchess caught it.
+///
+/// Formatting the [`Error`] uses the snapshot, so it doesn't need to call back into the JVM, and its
+/// [`source`](std::error::Error::source) is the snapshot of the exception's Java cause.
+pub struct Thrown<T> {
+    exception: T,
+    snapshot: Option<Arc<ExceptionSnapshot>>,
+}
+
+impl<T> Thrown<T> {
+    pub(crate) fn with_snapshot(exception: T, snapshot: Option<Arc<ExceptionSnapshot>>) -> Self {
+        Thrown {
+            exception,
+            snapshot,
+        }
+    }
+
+    /// The snapshot of the exception, if one was taken.
+    pub fn snapshot(&self) -> Option<&ExceptionSnapshot> {
+        self.snapshot.as_deref()
+    }
+
+    /// Returns the reference to the exception.
+    pub fn into_inner(self) -> T {
+        self.exception
+    }
+
+    /// Takes the snapshot again (e.g. after adding a suppressed exception), keeping the previous one if
+    /// that fails, or if snapshots are disabled.
+    pub(crate) fn retake_snapshot(&mut self, jvm: &mut Jvm<'_>)
+    where
+        T: AsJRef<Throwable>,
+    {
+        let Ok(exception) = self.exception.as_jref() else {
+            return;
+        };
+        if let Some(snapshot) = crate::snapshot::capture(jvm.env(), exception) {
+            self.snapshot = Some(snapshot);
+        }
+    }
+
+    /// Wraps another reference to the same exception, sharing the snapshot.
+    pub(crate) fn with_exception<U>(&self, exception: U) -> Thrown<U> {
+        Thrown::with_snapshot(exception, self.snapshot.clone())
+    }
+
+    /// Replaces the reference to the exception with `op(reference)`, keeping the snapshot.
+    pub fn map<U>(self, op: impl FnOnce(T) -> U) -> Thrown<U> {
+        Thrown {
+            exception: op(self.exception),
+            snapshot: self.snapshot,
+        }
+    }
+}
+
+/// Wraps an exception without a snapshot, e.g. one created by Rust code to be thrown.
+impl<T> From<T> for Thrown<T> {
+    fn from(exception: T) -> Self {
+        Thrown::with_snapshot(exception, None)
+    }
+}
+
+impl<T> Debug for Thrown<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Thrown")
+            .field("snapshot", &self.snapshot)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Deref for Thrown<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.exception
+    }
+}
+
+impl<T> Display for Error<T>
+where
+    T: AsJRef<Throwable>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Thrown(thrown) => match thrown.snapshot() {
+                Some(snapshot) => write!(f, "Java invocation threw: {snapshot}"),
+                // Formatting never calls into the JVM, which may not be usable here (e.g. on another thread)
+                None => write!(f, "Java invocation threw an exception (no snapshot was taken)"),
+            },
+            Error::SliceTooLong(len) => write!(
+                f,
+                "slice was too long (`{len}`) to convert to a Java array or buffer, which are limited to `i32::MAX`"
+            ),
+            Error::NullDeref => write!(f, "attempted to deref a null Java object pointer"),
+            Error::JvmAlreadyExists => write!(f, "JVM already exists"),
+            #[cfg(feature = "dylibjvm")]
+            Error::UnableToLoadLibjvm(e) => Display::fmt(e, f),
+            Error::JvmInternal(message) => write!(f, "{message}"),
+        }
+    }
 }
 
 impl<T> Debug for Error<T>
@@ -65,13 +145,39 @@ where
     }
 }
 
+impl<T> std::error::Error for Error<T>
+where
+    T: AsJRef<Throwable>,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Thrown(thrown) => {
+                let cause = thrown.snapshot()?.cause()?;
+                Some(cause)
+            }
+            #[cfg(feature = "dylibjvm")]
+            Error::UnableToLoadLibjvm(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "dylibjvm")]
+impl<T> From<Box<dyn std::error::Error + Send + Sync + 'static>> for Error<T>
+where
+    T: AsJRef<Throwable>,
+{
+    fn from(e: Box<dyn std::error::Error + Send + Sync + 'static>) -> Self {
+        Error::UnableToLoadLibjvm(e)
+    }
+}
+
 impl<'jvm> Error<Local<'jvm, Throwable>> {
     pub fn into_global(self, jvm: &mut Jvm<'jvm>) -> Error<Global<Throwable>> {
         match self {
-            Error::Thrown(t) => Error::Thrown(jvm.global(&t)),
+            Error::Thrown(t) => Error::Thrown(t.map(|t| jvm.global::<Throwable>(&t))),
             Error::SliceTooLong(s) => Error::SliceTooLong(s),
             Error::NullDeref => Error::NullDeref,
-            Error::NestedUsage => Error::NestedUsage,
             Error::JvmAlreadyExists => Error::JvmAlreadyExists,
             #[cfg(feature = "dylibjvm")]
             Error::UnableToLoadLibjvm(e) => Error::UnableToLoadLibjvm(e),
@@ -0,0 +1,260 @@
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::{jvm::panic_message, oneshot, Error, GlobalResult, Jvm, JvmOp};
+
+/// Future returned by [`JvmOp::execute_async`]. When first polled, it sends the op to one of
+/// duchess's JVM-attached worker threads and then resolves once the op has finished executing.
+#[must_use = "futures do nothing unless polled"]
+pub struct ExecuteAsync<J, R> {
+    state: State<J, R>,
+}
+
+enum State<J, R> {
+    Unstarted(J),
+    Running(oneshot::Receiver<std::thread::Result<GlobalResult<R>>>),
+    Done,
+}
+
+impl<J, R> ExecuteAsync<J, R>
+where
+    for<'jvm> J: JvmOp<Output<'jvm> = R>,
+    J: Send + 'static,
+    R: Send + 'static,
+{
+    pub(crate) fn new(j: J) -> Self {
+        ExecuteAsync {
+            state: State::Unstarted(j),
+        }
+    }
+}
+
+// The op is never pinned in place: it is moved to a worker thread when the future is first polled.
+impl<J, R> Unpin for ExecuteAsync<J, R> {}
+
+impl<J, R> Future for ExecuteAsync<J, R>
+where
+    for<'jvm> J: JvmOp<Output<'jvm> = R>,
+    J: Send + 'static,
+    R: Send + 'static,
+{
+    type Output = GlobalResult<R>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<GlobalResult<R>> {
+        loop {
+            match std::mem::replace(&mut self.state, State::Done) {
+                State::Unstarted(j) => {
+                    let (sender, receiver) = oneshot::channel();
+                    worker_pool().submit(Box::new(move || {
+                        let result = std::panic::catch_unwind(AssertUnwindSafe(|| j.execute()));
+                        sender.send(result);
+                    }));
+                    self.state = State::Running(receiver);
+                }
+                State::Running(mut receiver) => {
+                    return match Pin::new(&mut receiver).poll(cx) {
+                        Poll::Pending => {
+                            self.state = State::Running(receiver);
+                            Poll::Pending
+                        }
+                        Poll::Ready(Some(Ok(result))) => Poll::Ready(result),
+                        // Re-raise panics from the op in the awaiting task, as `execute` would have
+                        Poll::Ready(Some(Err(panic))) => std::panic::resume_unwind(panic),
+                        Poll::Ready(None) => Poll::Ready(Err(Error::JvmInternal(
+                            "duchess worker thread exited without completing the operation"
+                                .to_string(),
+                        ))),
+                    };
+                }
+                State::Done => panic!("`ExecuteAsync` polled after completion"),
+            }
+        }
+    }
+}
+
+/// A future spawned by duchess, e.g. to drive the body of an async native function.
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+type Spawn = Box<dyn Fn(BoxFuture) + Send + Sync + 'static>;
+
+static EXECUTOR: OnceCell<Spawn> = OnceCell::new();
+
+/// Installs the function used to spawn futures onto the user's executor. Has no effect (other
+/// than a warning) if an executor was already installed.
+pub(crate) fn set_executor(spawn: Spawn) {
+    if EXECUTOR.set(spawn).is_err() {
+        tracing::warn!("duchess executor already configured");
+    }
+}
+
+/// Runs `future` to completion in the background, on the executor installed with
+/// [`set_executor`] or, by default, on duchess's worker threads.
+pub(crate) fn spawn(future: BoxFuture) {
+    match EXECUTOR.get() {
+        Some(spawn) => spawn(future),
+        None => Arc::new(Task {
+            state: Mutex::new(TaskState::Idle(future)),
+        })
+        .wake(),
+    }
+}
+
+/// A future spawned onto the worker threads. Each wakeup polls it once on some worker thread.
+struct Task {
+    state: Mutex<TaskState>,
+}
+
+/// The lock on the state is only held to move between states, never while the future is polled,
+/// so that a future that wakes itself does not block the worker that picks up the wakeup.
+enum TaskState {
+    /// Waiting to be woken.
+    Idle(BoxFuture),
+    /// Woken, and submitted to the worker threads to be polled.
+    Scheduled(BoxFuture),
+    /// Being polled by a worker thread. If `woken`, it is submitted again once that poll returns.
+    Running { woken: bool },
+    /// Completed (or panicked).
+    Done,
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+        *state = match std::mem::replace(&mut *state, TaskState::Done) {
+            TaskState::Idle(future) => {
+                worker_pool().submit(Box::new({
+                    let task = self.clone();
+                    move || task.poll()
+                }));
+                TaskState::Scheduled(future)
+            }
+            TaskState::Running { .. } => TaskState::Running { woken: true },
+            state @ (TaskState::Scheduled(_) | TaskState::Done) => state,
+        };
+    }
+}
+
+impl Task {
+    fn poll(self: Arc<Self>) {
+        let mut future = {
+            let mut state = self.state.lock().unwrap();
+            match std::mem::replace(&mut *state, TaskState::Running { woken: false }) {
+                TaskState::Scheduled(future) => future,
+                other => {
+                    *state = other;
+                    return;
+                }
+            }
+        };
+
+        // If the future panics, the state stays `Running` and later wakeups are ignored
+        let waker = Waker::from(self.clone());
+        let poll = future.as_mut().poll(&mut Context::from_waker(&waker));
+
+        let mut state = self.state.lock().unwrap();
+        *state = match (poll, &*state) {
+            (Poll::Ready(()), _) => TaskState::Done,
+            (Poll::Pending, TaskState::Running { woken: true }) => {
+                worker_pool().submit(Box::new({
+                    let task = self.clone();
+                    move || task.poll()
+                }));
+                TaskState::Scheduled(future)
+            }
+            (Poll::Pending, _) => TaskState::Idle(future),
+        };
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs `job` on one of duchess's worker threads, which are attached to the JVM.
+pub(crate) fn submit(job: Job) {
+    worker_pool().submit(job);
+}
+
+/// Number of worker threads, fixed once the pool is started.
+static WORKER_THREADS: OnceCell<usize> = OnceCell::new();
+
+static WORKER_POOL: OnceCell<WorkerPool> = OnceCell::new();
+
+/// Configures the number of worker threads used by [`JvmOp::execute_async`]. Has no effect
+/// (other than a warning) if the pool was already started with a different size.
+pub(crate) fn set_worker_threads(threads: usize) {
+    assert!(threads > 0, "duchess needs at least one worker thread");
+    let configured = *WORKER_THREADS.get_or_init(|| threads);
+    if configured != threads {
+        tracing::warn!(
+            configured,
+            requested = threads,
+            "duchess worker threads already configured"
+        );
+    }
+}
+
+fn worker_pool() -> &'static WorkerPool {
+    WORKER_POOL.get_or_init(|| {
+        let threads = *WORKER_THREADS.get_or_init(|| {
+            std::thread::available_parallelism().map_or(4, NonZeroUsize::get)
+        });
+        WorkerPool::start(threads)
+    })
+}
+
+/// A fixed set of threads that are permanently attached to the JVM and execute jobs from a shared queue.
+/// The threads live as long as the process, just like the JVM itself.
+struct WorkerPool {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl WorkerPool {
+    fn start(threads: usize) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..threads {
+            let receiver = receiver.clone();
+            std::thread::Builder::new()
+                .name(format!("duchess-worker-{index}"))
+                .spawn(move || Self::run_worker(receiver))
+                .expect("failed to spawn duchess worker thread");
+        }
+        WorkerPool { jobs }
+    }
+
+    fn run_worker(receiver: Arc<Mutex<mpsc::Receiver<Job>>>) {
+        // Attach once up front rather than on every job. If this fails, each job will report the
+        // error itself when it tries to attach again.
+        if let Err(err) = Jvm::attach_thread_permanently() {
+            tracing::warn!(?err, "couldn't attach duchess worker thread to the JVM");
+        }
+
+        loop {
+            let job = receiver.lock().unwrap().recv();
+            match job {
+                Ok(job) => {
+                    // Jobs report their own panics where they can (e.g. `execute_async`); a panic
+                    // that escapes must not take the worker thread down with it.
+                    if let Err(panic) = std::panic::catch_unwind(AssertUnwindSafe(job)) {
+                        let message = panic_message(&*panic);
+                        tracing::error!(message, "duchess worker job panicked");
+                    }
+                }
+                Err(mpsc::RecvError) => return,
+            }
+        }
+    }
+
+    fn submit(&self, job: Job) {
+        // If sending fails, the job is dropped along with its result sender, which the future reports as an error.
+        let _ = self.jobs.send(job);
+    }
+}
+
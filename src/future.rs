@@ -0,0 +1,273 @@
+//! Bridges Java's `CompletableFuture` into Rust [`Future`]s.
+
+use std::{
+    any::Any,
+    future::{Future, IntoFuture},
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+// Make current crate available as `duchess` for use by the generated code.
+// NB. in doctests, the current crate is already available as duchess.
+#[cfg(not(doctest))]
+use crate as duchess;
+
+use crate::{
+    bundled::duchess_rs,
+    cast::Upcast,
+    execute_async, java,
+    java::util::concurrent::CompletableFuture,
+    java_function,
+    jvm::{error_to_throwable, native_function_returning_object, rust_panic_to_throwable},
+    link::JavaFn,
+    oneshot,
+    plumbing::{EnvPtr, JavaFunction, ToJavaImpl},
+    Error, Global, GlobalResult, JavaObject, Jvm, JvmOp, Local, Thrown, ToJavaThrowable,
+};
+
+/// How a `CompletableFuture` completed: with a (possibly null) value, or exceptionally.
+type Completion = GlobalResult<Option<Global<java::lang::Object>>>;
+
+/// The Rust end of a `duchess_rs.RustFutureCompletion` callback, which owns a boxed `CompletionSender`.
+type CompletionSender = oneshot::Sender<Completion>;
+
+/// A Rust [`Future`] that resolves once a Java `CompletableFuture` completes. Created by
+/// awaiting a `Global<CompletableFuture<T>>` (or via [`JavaFuture::new`]).
+///
+/// Rather than polling or blocking a thread, the first poll has one of duchess's worker threads
+/// (see [`JvmOp::execute_async`]) register a completion callback on the `CompletableFuture`
+/// (via `whenComplete`) that wakes the task once the Java future is done. The future resolves to:
+///
+/// * `Ok(Some(value))` if the Java future completed normally with a non-null `value`;
+/// * `Ok(None)` if it completed normally with `null` (e.g. a `CompletableFuture<Void>`, which
+///   couldn't be awaited if `null` were reported as [`Error::NullDeref`]);
+/// * `Err(Error::Thrown(exception))` if it completed exceptionally (`CompletionException`s
+///   are unwrapped to their cause).
+#[must_use = "futures do nothing unless polled"]
+pub struct JavaFuture<T: JavaObject> {
+    state: State<T>,
+}
+
+enum State<T: JavaObject> {
+    Unregistered(Global<CompletableFuture<T>>),
+    Waiting(oneshot::Receiver<Completion>),
+    Done,
+}
+
+impl<T: JavaObject> JavaFuture<T> {
+    pub fn new(future: Global<CompletableFuture<T>>) -> Self {
+        JavaFuture {
+            state: State::Unregistered(future),
+        }
+    }
+}
+
+impl<T: JavaObject> Unpin for JavaFuture<T> {}
+
+impl<T: JavaObject> Future for JavaFuture<T> {
+    type Output = GlobalResult<Option<Global<T>>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match std::mem::replace(&mut self.state, State::Done) {
+                State::Unregistered(future) => {
+                    // Calling into the JVM could block the executor's thread (or attach it to the JVM),
+                    // so the callback is registered on a worker thread instead
+                    let (sender, receiver) = oneshot::channel();
+                    execute_async::submit(Box::new(move || register_completion(future, sender)));
+                    self.state = State::Waiting(receiver);
+                }
+                State::Waiting(mut completion) => {
+                    return match Pin::new(&mut completion).poll(cx) {
+                        Poll::Pending => {
+                            self.state = State::Waiting(completion);
+                            Poll::Pending
+                        }
+                        Poll::Ready(Some(result)) => Poll::Ready(result.map(|value| {
+                            // SAFETY: the `CompletableFuture<T>` completed with this value, so it is a `T`
+                            value.map(|value| unsafe { value.cast::<T>() })
+                        })),
+                        Poll::Ready(None) => Poll::Ready(Err(Error::JvmInternal(
+                            "CompletableFuture completion callback was dropped without being invoked"
+                                .to_string(),
+                        ))),
+                    };
+                }
+                State::Done => panic!("`JavaFuture` polled after completion"),
+            }
+        }
+    }
+}
+
+impl<T: JavaObject> IntoFuture for Global<CompletableFuture<T>> {
+    type Output = GlobalResult<Option<Global<T>>>;
+    type IntoFuture = JavaFuture<T>;
+
+    fn into_future(self) -> JavaFuture<T> {
+        JavaFuture::new(self)
+    }
+}
+
+/// Invoked as the body from a JNI native function that returns a `CompletableFuture<J>` and is
+/// implemented by an async Rust function. `op` invokes the Rust function, producing its future
+/// (or an error if its arguments couldn't be converted, which is thrown right away).
+/// Returns an incomplete `CompletableFuture` to the JVM right away and spawns the Rust future
+/// (see [`Jvm::set_executor`]), completing the Java future with the converted output once it
+/// resolves. Errors and panics complete the Java future exceptionally instead.
+///
+/// # Safety
+///
+/// Must be invoked as the entire body of a JNI native function, with
+/// `env` being the `EnvPtr` argument provided.
+pub unsafe fn native_function_returning_future<'env, J, F>(
+    env: EnvPtr<'env>,
+    op: impl FnOnce(&mut Jvm<'env>) -> GlobalResult<F>,
+) -> jni_sys::jobject
+where
+    J: Upcast<java::lang::Object> + Upcast<J>,
+    F: Future + Send + 'static,
+    F::Output: ToJavaImpl<J>,
+{
+    native_function_returning_object::<CompletableFuture<J>, _>(
+        env,
+        |jvm| -> crate::Result<'env, Local<'env, CompletableFuture<J>>> {
+            let future = op(jvm).map_err(|err| err.to_java_throwable(jvm))?;
+            let java_future = duchess_rs::RustCompletableFuture::<J>::new().execute_with(jvm)?;
+            let completer = jvm.global(&*java_future);
+            execute_async::spawn(Box::pin(async move {
+                let output = catch_unwind(future).await;
+                complete_java_future(completer, output);
+            }));
+            Ok(java_future.upcast())
+        },
+    )
+}
+
+/// Resolves to the output of `future`, or to the panic payload if polling it panics.
+async fn catch_unwind<F: Future>(future: F) -> std::thread::Result<F::Output> {
+    let mut future = Box::pin(future);
+    std::future::poll_fn(move |cx| {
+        match std::panic::catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    })
+    .await
+}
+
+/// Completes `java_future` with the output of the Rust future that was driving it.
+fn complete_java_future<J, R>(
+    java_future: Global<duchess_rs::RustCompletableFuture<J>>,
+    output: Result<R, Box<dyn Any + Send + 'static>>,
+) where
+    J: Upcast<java::lang::Object> + Upcast<J>,
+    R: ToJavaImpl<J>,
+{
+    let result = Jvm::with(|jvm| {
+        let exception = match output {
+            Ok(output) => match R::to_java_impl(&output, jvm) {
+                Ok(Some(value)) => {
+                    java_future.complete(&value).execute_with(jvm)?;
+                    return Ok(());
+                }
+                Ok(None) => {
+                    java_future.complete_null().execute_with(jvm)?;
+                    return Ok(());
+                }
+                Err(err) => error_to_throwable(jvm, err)?,
+            },
+            Err(panic) => rust_panic_to_throwable(jvm, panic)?,
+        };
+        java_future
+            .complete_exceptionally(&exception)
+            .execute_with(jvm)?;
+        Ok(())
+    });
+    if let Err(err) = result {
+        tracing::warn!(?err, "unable to complete CompletableFuture returned by async native function");
+    }
+}
+
+/// Registers a `duchess_rs.RustFutureCompletion` callback on `future` that sends its outcome to `sender`.
+/// If `future` is already complete, the callback runs (and `sender` is used) before this returns.
+/// If the callback can't be registered, the error is sent instead.
+fn register_completion<T: JavaObject>(
+    future: Global<CompletableFuture<T>>,
+    sender: CompletionSender,
+) {
+    let sender = Box::into_raw(Box::new(sender)) as i64;
+
+    // Set while the Java callback owns `sender`, to free it once invoked or collected
+    let mut handed_over = false;
+    let result = Jvm::with(|jvm| {
+        let callback = duchess_rs::RustFutureCompletion::new(sender).execute_with(jvm)?;
+        handed_over = true;
+
+        if let Err(err) = future.when_complete(&callback).execute_with(jvm) {
+            // Take the sender back, unless the callback was invoked anyway
+            if callback.detach().execute_with(jvm)? != 0 {
+                handed_over = false;
+            }
+            return Err(err);
+        }
+        Ok(())
+    });
+    if let Err(err) = result {
+        if handed_over {
+            // The callback still owns the sender, so the awaiting task is woken once it is collected
+            tracing::warn!(?err, "unable to register completion callback on CompletableFuture");
+        } else {
+            // SAFETY: the callback never got (or gave back) ownership of `sender`
+            unsafe { take_sender(sender) }.send(Err(err));
+        }
+    }
+}
+
+/// Takes back ownership of the sender that was handed to a `duchess_rs.RustFutureCompletion`.
+///
+/// # Safety
+///
+/// `sender` must have been created by [`register_completion`] and not yet been taken back.
+/// The Java callback guarantees that it passes each sender to exactly one of its native methods,
+/// or gives it back through `detach()`.
+unsafe fn take_sender(sender: i64) -> Box<CompletionSender> {
+    Box::from_raw(sender as *mut CompletionSender)
+}
+
+#[java_function(duchess_rs.RustFutureCompletion::complete)]
+fn complete(jvm: &mut Jvm<'_>, sender: i64, value: &java::lang::Object) {
+    let value = jvm.global(value);
+    unsafe { take_sender(sender) }.send(Ok(Some(value)));
+}
+
+#[java_function(duchess_rs.RustFutureCompletion::completeNull)]
+fn complete_null(sender: i64) {
+    unsafe { take_sender(sender) }.send(Ok(None));
+}
+
+#[java_function(duchess_rs.RustFutureCompletion::completeExceptionally)]
+fn complete_exceptionally(jvm: &mut Jvm<'_>, sender: i64, exception: &java::lang::Throwable) {
+    let snapshot = crate::snapshot::capture(jvm.env(), exception);
+    let exception = jvm.global(exception);
+    unsafe { take_sender(sender) }.send(Err(Error::Thrown(Thrown::with_snapshot(
+        exception, snapshot,
+    ))));
+}
+
+#[java_function(duchess_rs.RustFutureCompletion::drop)]
+fn drop_sender(sender: i64) {
+    // Dropping the sender wakes the receiver, which reports that the callback was never invoked
+    drop(unsafe { take_sender(sender) });
+}
+
+/// The native methods of `duchess_rs.RustFutureCompletion`, registered when the bundled classes are defined.
+pub(crate) fn native_methods() -> Vec<JavaFunction> {
+    vec![
+        complete::java_fn(),
+        complete_null::java_fn(),
+        complete_exceptionally::java_fn(),
+        drop_sender::java_fn(),
+    ]
+}
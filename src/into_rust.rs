@@ -1,6 +1,19 @@
-use std::marker::PhantomData;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
 
-use crate::{Global, JavaObject, Jvm, JvmOp, Local};
+use crate::{
+    java::{
+        self,
+        lang::{Character, IllegalArgumentException, Throwable},
+    },
+    jvm::new_exception,
+    to_java::in_element_frame,
+    try_catch::DeclaredExceptions,
+    AsJRef, Error, Global, JavaObject, Jvm, JvmOp, Local,
+};
 
 /// Types that are able to be converted back into a Rust `T`, either because they will produce a Rust primitive `T` or
 /// or because we can convert into them via a JNI call.
@@ -30,6 +43,29 @@ identity_rust_op! {
     i16,
     i32,
     i64,
+    f32,
+    f64,
+}
+
+/// Java `char`s are UTF-16 code units, so converting one into a Rust `char` throws an
+/// `IllegalArgumentException` if it is half of a surrogate pair.
+impl IntoRust<char> for u16 {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, char> {
+        match char::from_u32(u32::from(self)) {
+            Some(c) => Ok(c),
+            None => Err(new_exception::<IllegalArgumentException>(
+                jvm,
+                &format!("unpaired surrogate `{self:#06x}` is not a valid `char`"),
+            )),
+        }
+    }
+}
+
+impl IntoRust<char> for &Character {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, char> {
+        let unit: u16 = self.into_rust(jvm)?;
+        unit.into_rust(jvm)
+    }
 }
 
 impl<O, E, JO, JE> IntoRust<Result<O, E>> for Result<JO, JE>
@@ -57,6 +93,155 @@ where
     }
 }
 
+/// Runs `op` on each element of `iterable`, each in its own local frame. Null elements are an error.
+fn for_each_element<'jvm, E: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    iterable: &java::lang::Iterable<E>,
+    mut op: impl for<'frame> FnMut(&mut Jvm<'frame>, &E) -> crate::Result<'frame, ()>,
+) -> crate::Result<'jvm, ()> {
+    let iterator = iterable.iterator().assert_not_null().execute_with(jvm)?;
+    while iterator.has_next().execute_with(jvm)? {
+        in_element_frame(jvm, |jvm| {
+            let element = iterator.next().execute_with(jvm)?.ok_or(Error::NullDeref)?;
+            op(jvm, &element)
+        })?;
+    }
+    Ok(())
+}
+
+macro_rules! collection_into_rust {
+    ($($collection:ident,)*) => {
+        $(
+            impl<E, R> IntoRust<Vec<R>> for &java::util::$collection<E>
+            where
+                E: JavaObject,
+                for<'a> &'a E: IntoRust<R>,
+            {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Vec<R>> {
+                    let mut vec = Vec::new();
+                    for_each_element(jvm, self.as_jref()?, |jvm, element| {
+                        vec.push(element.into_rust(jvm)?);
+                        Ok(())
+                    })?;
+                    Ok(vec)
+                }
+            }
+
+            impl<E, R, S> IntoRust<HashSet<R, S>> for &java::util::$collection<E>
+            where
+                E: JavaObject,
+                for<'a> &'a E: IntoRust<R>,
+                R: Eq + Hash,
+                S: BuildHasher + Default,
+            {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, HashSet<R, S>> {
+                    let mut set = HashSet::default();
+                    for_each_element(jvm, self.as_jref()?, |jvm, element| {
+                        set.insert(element.into_rust(jvm)?);
+                        Ok(())
+                    })?;
+                    Ok(set)
+                }
+            }
+
+            impl<E, R> IntoRust<BTreeSet<R>> for &java::util::$collection<E>
+            where
+                E: JavaObject,
+                for<'a> &'a E: IntoRust<R>,
+                R: Ord,
+            {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, BTreeSet<R>> {
+                    let mut set = BTreeSet::new();
+                    for_each_element(jvm, self.as_jref()?, |jvm, element| {
+                        set.insert(element.into_rust(jvm)?);
+                        Ok(())
+                    })?;
+                    Ok(set)
+                }
+            }
+        )*
+    };
+}
+
+collection_into_rust! {
+    Collection,
+    List,
+    ArrayList,
+    Set,
+    HashSet,
+    SortedSet,
+    NavigableSet,
+    TreeSet,
+}
+
+/// Runs `op` on each key and value of `map`, each entry in its own local frame. Null keys and values are an error.
+fn for_each_entry<'jvm, K: JavaObject, V: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    map: &java::util::Map<K, V>,
+    mut op: impl for<'frame> FnMut(&mut Jvm<'frame>, &K, &V) -> crate::Result<'frame, ()>,
+) -> crate::Result<'jvm, ()> {
+    let entries = map.entry_set().assert_not_null().execute_with(jvm)?;
+    for_each_element(jvm, entries.as_jref()?, |jvm, entry| {
+        let key = entry.get_key().execute_with(jvm)?.ok_or(Error::NullDeref)?;
+        let value = entry
+            .get_value()
+            .execute_with(jvm)?
+            .ok_or(Error::NullDeref)?;
+        op(jvm, &key, &value)
+    })
+}
+
+macro_rules! map_into_rust {
+    ($($map:ident,)*) => {
+        $(
+            impl<K, V, RK, RV, S> IntoRust<HashMap<RK, RV, S>> for &java::util::$map<K, V>
+            where
+                K: JavaObject,
+                V: JavaObject,
+                for<'a> &'a K: IntoRust<RK>,
+                for<'a> &'a V: IntoRust<RV>,
+                RK: Eq + Hash,
+                S: BuildHasher + Default,
+            {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, HashMap<RK, RV, S>> {
+                    let mut map = HashMap::default();
+                    for_each_entry(jvm, self.as_jref()?, |jvm, key, value| {
+                        map.insert(key.into_rust(jvm)?, value.into_rust(jvm)?);
+                        Ok(())
+                    })?;
+                    Ok(map)
+                }
+            }
+
+            impl<K, V, RK, RV> IntoRust<BTreeMap<RK, RV>> for &java::util::$map<K, V>
+            where
+                K: JavaObject,
+                V: JavaObject,
+                for<'a> &'a K: IntoRust<RK>,
+                for<'a> &'a V: IntoRust<RV>,
+                RK: Ord,
+            {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, BTreeMap<RK, RV>> {
+                    let mut map = BTreeMap::new();
+                    for_each_entry(jvm, self.as_jref()?, |jvm, key, value| {
+                        map.insert(key.into_rust(jvm)?, value.into_rust(jvm)?);
+                        Ok(())
+                    })?;
+                    Ok(map)
+                }
+            }
+        )*
+    };
+}
+
+map_into_rust! {
+    Map,
+    HashMap,
+    SortedMap,
+    NavigableMap,
+    TreeMap,
+}
+
 impl<R, J> IntoRust<R> for Local<'_, J>
 where
     J: JavaObject,
@@ -112,3 +297,17 @@ where
         Ok(rust)
     }
 }
+
+impl<This, R> DeclaredExceptions for ToRustOp<This, R>
+where
+    This: JvmOp + DeclaredExceptions,
+{
+    type Caught<'jvm> = This::Caught<'jvm>;
+
+    fn try_catch<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        exception: &Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Option<Self::Caught<'jvm>>> {
+        This::try_catch(jvm, exception)
+    }
+}
@@ -0,0 +1,203 @@
+use std::marker::PhantomData;
+
+use crate::{
+    java, to_java::in_element_frame, Error, IntoRust, JavaObject, Jvm, JvmOp, Local, TryJDeref,
+};
+
+/// Java objects whose elements can be iterated over lazily with [`JvmOp::iter`]: `java.util.Iterator`,
+/// `java.lang.Iterable` and its subtypes (e.g. `List` or `Set`), and `java.util.stream.Stream`.
+pub trait JavaIterable: JavaObject {
+    /// The class of the elements.
+    type Item: JavaObject;
+
+    /// Returns a `java.util.Iterator` over the elements.
+    fn java_iterator<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Local<'jvm, java::util::Iterator<Self::Item>>>;
+}
+
+impl<E: JavaObject> JavaIterable for java::util::Iterator<E> {
+    type Item = E;
+
+    fn java_iterator<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Local<'jvm, java::util::Iterator<E>>> {
+        Ok(jvm.local(self))
+    }
+}
+
+impl<E: JavaObject> JavaIterable for java::util::stream::Stream<E> {
+    type Item = E;
+
+    fn java_iterator<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Local<'jvm, java::util::Iterator<E>>> {
+        self.iterator().assert_not_null().execute_with(jvm)
+    }
+}
+
+macro_rules! iterable {
+    ($($iterable:ty,)*) => {
+        $(
+            impl<E: JavaObject> JavaIterable for $iterable {
+                type Item = E;
+
+                fn java_iterator<'jvm>(
+                    &self,
+                    jvm: &mut Jvm<'jvm>,
+                ) -> crate::Result<'jvm, Local<'jvm, java::util::Iterator<E>>> {
+                    self.iterator().assert_not_null().execute_with(jvm)
+                }
+            }
+        )*
+    };
+}
+
+iterable! {
+    java::lang::Iterable<E>,
+    java::util::Collection<E>,
+    java::util::List<E>,
+    java::util::ArrayList<E>,
+    java::util::Set<E>,
+    java::util::HashSet<E>,
+    java::util::SortedSet<E>,
+    java::util::NavigableSet<E>,
+    java::util::TreeSet<E>,
+}
+
+#[derive_where::derive_where(Copy, Clone; This)]
+pub struct IterOp<This: JvmOp, J> {
+    this: This,
+    phantom: PhantomData<J>,
+}
+
+impl<This, J> IterOp<This, J>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: TryJDeref<Java = J>,
+    J: JavaIterable,
+{
+    pub(crate) fn new(this: This) -> Self {
+        IterOp {
+            this,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<This, J> JvmOp for IterOp<This, J>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: TryJDeref<Java = J>,
+    J: JavaIterable,
+{
+    type Output<'jvm> = JavaIterator<'jvm, J::Item>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let iterator = this.try_jderef()?.java_iterator(jvm)?;
+        Ok(JavaIterator { iterator })
+    }
+}
+
+/// An iterator over a Java `java.util.Iterator`, produced by [`JvmOp::iter`]. Each call to
+/// [`next`](JavaIterator::next) calls `hasNext()` and `next()` on the Java iterator, yielding `None` for
+/// `null` elements.
+///
+/// Like a lending iterator, it takes the [`Jvm`] it was created with on every call, so that each element is
+/// a local reference of that `Jvm` (and cannot outlive a local frame it was created in). Every element is a
+/// new local reference, which is freed when it is dropped. To iterate over a huge (or unbounded) sequence,
+/// either drop each element before moving on to the next one, or use [`to_rust`](JavaIterator::to_rust) to
+/// convert the elements as they are produced.
+///
+/// ```rust,ignore
+/// let mut names = users.iter().execute_with(jvm)?;
+/// while let Some(name) = names.next(jvm) {
+///     ...
+/// }
+/// ```
+pub struct JavaIterator<'jvm, E: JavaObject> {
+    iterator: Local<'jvm, java::util::Iterator<E>>,
+}
+
+impl<'jvm, E: JavaObject> JavaIterator<'jvm, E> {
+    /// Converts each element into a Rust `R` (in its own local frame), like [`JvmOp::to_rust`].
+    /// `null` elements are an error.
+    pub fn to_rust<R>(self) -> ToRustIterator<'jvm, E, R>
+    where
+        for<'a> &'a E: IntoRust<R>,
+    {
+        ToRustIterator {
+            inner: self,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the next element, or `None` if there are no elements left.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(
+        &mut self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> Option<crate::Result<'jvm, Option<Local<'jvm, E>>>> {
+        if let Err(err) = self.has_next(jvm)? {
+            return Some(Err(err));
+        }
+        Some(self.iterator.next().execute_with(jvm))
+    }
+
+    /// Calls `hasNext()`, returning `None` if there are no elements left.
+    fn has_next(&self, jvm: &mut Jvm<'jvm>) -> Option<crate::Result<'jvm, ()>> {
+        match self.iterator.has_next().execute_with(jvm) {
+            Ok(true) => Some(Ok(())),
+            Ok(false) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// An iterator that converts the elements of a Java `java.util.Iterator` into Rust values, produced by
+/// [`JavaIterator::to_rust`]. Like [`JavaIterator`], it takes the [`Jvm`] on every call; use
+/// [`with_jvm`](ToRustIterator::with_jvm) to get a Rust [`Iterator`].
+pub struct ToRustIterator<'jvm, E: JavaObject, R> {
+    inner: JavaIterator<'jvm, E>,
+    phantom: PhantomData<fn() -> R>,
+}
+
+impl<'jvm, E, R> ToRustIterator<'jvm, E, R>
+where
+    E: JavaObject,
+    for<'a> &'a E: IntoRust<R>,
+{
+    /// Returns the next element, converted to Rust, or `None` if there are no elements left.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self, jvm: &mut Jvm<'jvm>) -> Option<crate::Result<'jvm, R>> {
+        if let Err(err) = self.inner.has_next(jvm)? {
+            return Some(Err(err));
+        }
+
+        let iterator = &self.inner.iterator;
+        let mut rust = None;
+        let result = in_element_frame(jvm, |jvm| {
+            let element = iterator.next().execute_with(jvm)?.ok_or(Error::NullDeref)?;
+            rust = Some(element.into_rust(jvm)?);
+            Ok(())
+        });
+        Some(result.map(|()| rust.expect("element was converted")))
+    }
+
+    /// Returns a Rust [`Iterator`] over the converted elements, which borrows `jvm` until it is dropped.
+    pub fn with_jvm<'a>(
+        mut self,
+        jvm: &'a mut Jvm<'jvm>,
+    ) -> impl Iterator<Item = crate::Result<'jvm, R>> + 'a
+    where
+        'jvm: 'a,
+        E: 'a,
+        R: 'a,
+    {
+        std::iter::from_fn(move || self.next(jvm))
+    }
+}
@@ -54,6 +54,90 @@ mod auto {
             public java.lang.RuntimeException();
         }
 
+        public class java.lang.NullPointerException extends java.lang.RuntimeException {
+            public java.lang.NullPointerException();
+        }
+
+        public final class java.lang.Boolean {
+            public static java.lang.Boolean valueOf(boolean);
+            public boolean booleanValue();
+        }
+
+        public final class java.lang.Byte {
+            public static java.lang.Byte valueOf(byte);
+            public byte byteValue();
+        }
+
+        public final class java.lang.Short {
+            public static java.lang.Short valueOf(short);
+            public short shortValue();
+        }
+
+        public final class java.lang.Character {
+            public static java.lang.Character valueOf(char);
+            public char charValue();
+        }
+
+        public final class java.lang.Integer {
+            public static java.lang.Integer valueOf(int);
+            public int intValue();
+        }
+
+        public final class java.lang.Long {
+            public static java.lang.Long valueOf(long);
+            public long longValue();
+        }
+
+        public final class java.lang.Float {
+            public static java.lang.Float valueOf(float);
+            public float floatValue();
+        }
+
+        public final class java.lang.Double {
+            public static java.lang.Double valueOf(double);
+            public double doubleValue();
+        }
+
+        public interface java.lang.Iterable<T> {
+            public abstract java.util.Iterator<T> iterator();
+        }
+
+        public interface java.lang.AutoCloseable {
+            public abstract void close() throws java.lang.Exception;
+        }
+
+        public interface java.lang.Runnable {
+            public abstract void run();
+        }
+
+        public final class java.lang.System {
+            public static void gc();
+        }
+
+        public class java.lang.IllegalArgumentException extends java.lang.RuntimeException {
+            public java.lang.IllegalArgumentException();
+        }
+
+        public class java.lang.IllegalStateException extends java.lang.RuntimeException {
+            public java.lang.IllegalStateException();
+        }
+
+        public class java.lang.UnsupportedOperationException extends java.lang.RuntimeException {
+            public java.lang.UnsupportedOperationException(java.lang.String);
+        }
+
+        public class java.lang.IndexOutOfBoundsException extends java.lang.RuntimeException {
+            public java.lang.IndexOutOfBoundsException();
+        }
+
+        public class java.lang.ArrayIndexOutOfBoundsException extends java.lang.IndexOutOfBoundsException {
+            public java.lang.ArrayIndexOutOfBoundsException();
+        }
+
+        public class java.lang.ArrayStoreException extends java.lang.RuntimeException {
+            public java.lang.ArrayStoreException();
+        }
+
         // NB: In Java, this is `Class<T>`, but we model it as the erased version
         // `Class`. This is beacuse there are a lot of methods, including some that we would
         // like to model such as `arrayType()`, that return a `Class<?>`, and we cannot model
@@ -80,6 +164,10 @@ mod auto {
             public java.lang.Class[] getInterfaces();
             public java.lang.Class getComponentType();
             public java.lang.Class arrayType();
+            public java.lang.ClassLoader getClassLoader();
+        }
+
+        public abstract class java.lang.ClassLoader {
         }
 
         public final class java.lang.String {
@@ -95,9 +183,96 @@ mod auto {
         }
 
 
+        package java.io;
+
+        public class java.io.IOException extends java.lang.Exception {
+            public java.io.IOException(java.lang.String);
+        }
+
+        public interface java.io.Closeable extends java.lang.AutoCloseable {
+            public abstract void close() throws java.io.IOException;
+        }
+
+        public abstract class java.io.Reader implements java.io.Closeable {
+            public int read() throws java.io.IOException;
+            public abstract void close() throws java.io.IOException;
+        }
+
+        public class java.io.StringReader extends java.io.Reader {
+            public java.io.StringReader(java.lang.String);
+            public int read() throws java.io.IOException;
+            public void close();
+        }
+
         package java.util;
 
-        public interface java.util.List<E> {
+        public interface java.util.Iterator<E> {
+            public abstract boolean hasNext();
+            public abstract E next();
+        }
+
+        public interface java.util.Spliterator<T> {
+            public abstract boolean tryAdvance(java.util.function.Consumer<? super T>);
+            public abstract long estimateSize();
+            public abstract int characteristics();
+        }
+
+        public interface java.util.Collection<E> extends java.lang.Iterable<E> {
+            public abstract int size();
+            public abstract boolean isEmpty();
+            public abstract boolean contains(java.lang.Object);
+            public abstract java.util.Iterator<E> iterator();
+            public abstract boolean add(E);
+            public abstract boolean remove(java.lang.Object);
+            public abstract void clear();
+            public default java.util.stream.Stream<E> stream();
+        }
+
+        public interface java.util.Set<E> extends java.util.Collection<E> {
+            public abstract int size();
+            public abstract boolean isEmpty();
+            public abstract boolean contains(java.lang.Object);
+            public abstract java.util.Iterator<E> iterator();
+            public abstract boolean add(E);
+            public abstract boolean remove(java.lang.Object);
+            public abstract void clear();
+        }
+
+        public class java.util.HashSet<E> implements java.util.Set<E> {
+            public java.util.HashSet();
+            public java.util.Iterator<E> iterator();
+            public int size();
+            public boolean isEmpty();
+            public boolean contains(java.lang.Object);
+            public boolean add(E);
+            public boolean remove(java.lang.Object);
+            public void clear();
+        }
+
+        public interface java.util.SortedSet<E> extends java.util.Set<E> {
+            public abstract E first();
+            public abstract E last();
+        }
+
+        public interface java.util.NavigableSet<E> extends java.util.SortedSet<E> {
+            public abstract E pollFirst();
+            public abstract E pollLast();
+        }
+
+        public class java.util.TreeSet<E> implements java.util.NavigableSet<E> {
+            public java.util.TreeSet();
+            public java.util.Iterator<E> iterator();
+            public int size();
+            public boolean isEmpty();
+            public boolean contains(java.lang.Object);
+            public boolean add(E);
+            public boolean remove(java.lang.Object);
+            public void clear();
+            public E first();
+            public E last();
+        }
+
+        public interface java.util.List<E> extends java.util.Collection<E> {
             public abstract int size();
             public abstract boolean isEmpty();
             public abstract boolean contains(java.lang.Object);
@@ -151,22 +326,22 @@ mod auto {
             public abstract V remove(java.lang.Object);
             public abstract void putAll(java.util.Map<? extends K, ? extends V>);
             public abstract void clear();
-            // public abstract java.util.Set<K> keySet();
-            // public abstract java.util.Collection<V> values();
-            // public abstract java.util.Set<java.util.Map$Entry<K, V>> entrySet();
+            public abstract java.util.Set<K> keySet();
+            public abstract java.util.Collection<V> values();
+            public abstract java.util.Set<java.util.Map$Entry<K, V>> entrySet();
             public abstract boolean equals(java.lang.Object);
             public abstract int hashCode();
             public default V getOrDefault(java.lang.Object, V);
-            // public default void forEach(java.util.function.BiConsumer<? super K, ? super V>);
-            // public default void replaceAll(java.util.function.BiFunction<? super K, ? super V, ? extends V>);
+            public default void forEach(java.util.function.BiConsumer<? super K, ? super V>);
+            public default void replaceAll(java.util.function.BiFunction<? super K, ? super V, ? extends V>);
             public default V putIfAbsent(K, V);
             // public default boolean remove(java.lang.Object, java.lang.Object);
             // public default boolean replace(K, V, V);
             // public default V replace(K, V);
-            // // public default V computeIfAbsent(K, java.util.function.Function<? super K, ? extends V>);
-            // public default V computeIfPresent(K, java.util.function.BiFunction<? super K, ? super V, ? extends V>);
-            // public default V compute(K, java.util.function.BiFunction<? super K, ? super V, ? extends V>);
-            // public default V merge(K, V, java.util.function.BiFunction<? super V, ? super V, ? extends V>);
+            public default V computeIfAbsent(K, java.util.function.Function<? super K, ? extends V>);
+            public default V computeIfPresent(K, java.util.function.BiFunction<? super K, ? super V, ? extends V>);
+            public default V compute(K, java.util.function.BiFunction<? super K, ? super V, ? extends V>);
+            public default V merge(K, V, java.util.function.BiFunction<? super V, ? super V, ? extends V>);
             // public static <K, V> java.util.Map<K, V> of();
             // public static <K, V> java.util.Map<K, V> of(K, V);
             // public static <K, V> java.util.Map<K, V> of(K, V, K, V);
@@ -200,23 +375,56 @@ mod auto {
             public V remove(java.lang.Object);
             public void clear();
             public boolean containsValue(java.lang.Object);
-            // public java.util.Set<K> keySet();
-            // public java.util.Collection<V> values();
-            // public java.util.Set<java.util.Map$Entry<K, V>> entrySet();
+            public java.util.Set<K> keySet();
+            public java.util.Collection<V> values();
+            public java.util.Set<java.util.Map$Entry<K, V>> entrySet();
             public V getOrDefault(java.lang.Object, V);
             public V putIfAbsent(K, V);
             // public boolean remove(java.lang.Object, java.lang.Object);
             // public boolean replace(K, V, V);
             // public V replace(K, V);
-            // public V computeIfAbsent(K, java.util.function.Function<? super K, ? extends V>);
-            // public V computeIfPresent(K, java.util.function.BiFunction<? super K, ? super V, ? extends V>);
-            // public V compute(K, java.util.function.BiFunction<? super K, ? super V, ? extends V>);
-            // public V merge(K, V, java.util.function.BiFunction<? super V, ? super V, ? extends V>);
-            // public void forEach(java.util.function.BiConsumer<? super K, ? super V>);
-            // public void replaceAll(java.util.function.BiFunction<? super K, ? super V, ? extends V>);
+            public V computeIfAbsent(K, java.util.function.Function<? super K, ? extends V>);
+            public V computeIfPresent(K, java.util.function.BiFunction<? super K, ? super V, ? extends V>);
+            public V compute(K, java.util.function.BiFunction<? super K, ? super V, ? extends V>);
+            public V merge(K, V, java.util.function.BiFunction<? super V, ? super V, ? extends V>);
+            public void forEach(java.util.function.BiConsumer<? super K, ? super V>);
+            public void replaceAll(java.util.function.BiFunction<? super K, ? super V, ? extends V>);
             public java.lang.Object clone();
         }
 
+        public interface java.util.Map$Entry<K, V> {
+            public abstract K getKey();
+            public abstract V getValue();
+            public abstract V setValue(V);
+        }
+
+        public interface java.util.SortedMap<K, V> extends java.util.Map<K, V> {
+            public abstract K firstKey();
+            public abstract K lastKey();
+        }
+
+        public interface java.util.NavigableMap<K, V> extends java.util.SortedMap<K, V> {
+            public abstract java.util.Map$Entry<K, V> firstEntry();
+            public abstract java.util.Map$Entry<K, V> lastEntry();
+        }
+
+        public class java.util.TreeMap<K, V> implements java.util.NavigableMap<K, V> {
+            public java.util.TreeMap();
+            public int size();
+            public boolean containsKey(java.lang.Object);
+            public boolean containsValue(java.lang.Object);
+            public V get(java.lang.Object);
+            public K firstKey();
+            public K lastKey();
+            public void putAll(java.util.Map<? extends K, ? extends V>);
+            public V put(K, V);
+            public V remove(java.lang.Object);
+            public void clear();
+            public java.util.Set<K> keySet();
+            public java.util.Collection<V> values();
+            public java.util.Set<java.util.Map$Entry<K, V>> entrySet();
+        }
+
         public class java.util.Date { // implements java.io.Serializable, java.lang.Cloneable, java.lang.Comparable<java.util.Date> {
             public java.util.Date();
             //   public java.util.Date(long);
@@ -258,6 +466,90 @@ mod auto {
             //   static {};
         }
 
+        package java.util.stream;
+
+        public interface java.util.stream.BaseStream<T, S extends java.util.stream.BaseStream<T, S>> extends java.lang.AutoCloseable {
+            public abstract java.util.Iterator<T> iterator();
+            public abstract boolean isParallel();
+            public abstract void close();
+        }
+
+        public interface java.util.stream.Stream<T> extends java.util.stream.BaseStream<T, java.util.stream.Stream<T>> {
+            public abstract java.util.stream.Stream<T> limit(long);
+            public abstract java.util.stream.Stream<T> skip(long);
+            public abstract java.util.stream.Stream<T> distinct();
+            public abstract java.util.stream.Stream<T> sorted();
+            public abstract long count();
+        }
+
+        package java.util.concurrent;
+
+        public class java.util.concurrent.CompletableFuture<T> { // implements java.util.concurrent.Future<T>, java.util.concurrent.CompletionStage<T> {
+            public java.util.concurrent.CompletableFuture();
+            public boolean isDone();
+            public T join();
+            public T getNow(T);
+            public boolean complete(T);
+            public boolean completeExceptionally(java.lang.Throwable);
+            public java.util.concurrent.CompletableFuture<T> whenComplete(java.util.function.BiConsumer<? super T, ? super java.lang.Throwable>);
+            public boolean cancel(boolean);
+            public boolean isCancelled();
+            public boolean isCompletedExceptionally();
+            public java.lang.String toString();
+            // public static <U> java.util.concurrent.CompletableFuture<U> completedFuture(U);
+            // public static java.util.concurrent.CompletableFuture<java.lang.Void> runAsync(java.lang.Runnable);
+        }
+
+        public interface java.util.concurrent.Callable<V> {
+            public abstract V call() throws java.lang.Exception;
+        }
+
+        package java.util.function;
+
+        public interface java.util.function.Function<T, R> {
+            public abstract R apply(T);
+        }
+
+        public interface java.util.function.BiFunction<T, U, R> {
+            public abstract R apply(T, U);
+        }
+
+        public interface java.util.function.Consumer<T> {
+            public abstract void accept(T);
+        }
+
+        public interface java.util.function.BiConsumer<T, U> {
+            public abstract void accept(T, U);
+        }
+
+        public interface java.util.function.Supplier<T> {
+            public abstract T get();
+        }
+
+        public interface java.util.function.Predicate<T> {
+            public abstract boolean test(T);
+        }
+
+        package java.nio;
+
+        public abstract class java.nio.Buffer {
+            public final int capacity();
+            public final int position();
+            public final int limit();
+            public final int remaining();
+            public abstract boolean isReadOnly();
+            public abstract boolean isDirect();
+        }
+
+        public abstract class java.nio.ByteBuffer extends java.nio.Buffer {
+            public static java.nio.ByteBuffer allocateDirect(int);
+            public static java.nio.ByteBuffer allocate(int);
+            public static java.nio.ByteBuffer wrap(byte[]);
+            public abstract java.nio.ByteBuffer asReadOnlyBuffer();
+            public abstract byte get(int);
+            public abstract java.nio.ByteBuffer put(int, byte);
+        }
+
         package java.time;
 
         public final class java.time.Instant {
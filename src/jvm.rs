@@ -1,24 +1,33 @@
 use crate::{
+    bundled::RustPanicException,
     cast::{AsUpcast, TryDowncast, Upcast},
+    execute_async::{self, ExecuteAsync},
     find::find_class,
     global::{GlobalOp, IntoGlobal},
     into_rust::ToRustOp,
-    java::lang::{Class, Throwable},
+    iter::{IterOp, JavaIterable},
+    java::lang::{AutoCloseable, Class, Throwable},
     link::{IntoJavaFns, JavaFunction},
     not_null::NotNull,
     plumbing::{FromRef, ToJavaImpl},
     raw::{self, EnvPtr, JvmPtr, ObjectPtr},
     thread,
-    try_catch::TryCatch,
-    AsJRef, Error, Global, GlobalResult, IntoRust, Local, ToJava, TryJDeref,
+    throw::Throw,
+    to_java::ToJavaThrowable,
+    try_catch::{Catchable, DeclaredExceptions, Finally, TryCatch, TryCatchAny, TryCatchDeclared},
+    with_resource::WithResource,
+    AsJRef, Error, Global, GlobalResult, IntoRust, Local, Thrown, ToJava, TryJDeref,
 };
 
 use std::{
     any::Any,
+    backtrace::{Backtrace, BacktraceStatus},
+    cell::Cell,
     collections::HashMap,
     ffi::{c_char, c_void, CStr},
     fmt::Display,
     panic::AssertUnwindSafe,
+    sync::Once,
 };
 
 use once_cell::sync::OnceCell;
@@ -89,6 +98,96 @@ pub trait JvmOp: Copy {
         TryCatch::new(self)
     }
 
+    /// Catch exceptions of any of the classes in the tuple `L`, like Java's multi-catch
+    /// (`catch (A | B e)`), e.g. `op.catch_any::<(java::io::IOException, java::lang::IllegalStateException)>()`.
+    /// Executing the resulting op returns `Ok(Err(e))`, where `e` is a [`Caught2`](crate::Caught2)
+    /// (for two classes) with a variant for the first class in `L` that the exception is an instance of.
+    fn catch_any<L>(self) -> TryCatchAny<Self, L>
+    where
+        L: Catchable,
+    {
+        TryCatchAny::new(self)
+    }
+
+    /// Execute `finally` after this op, whether or not this op fails, like a Java `finally` block.
+    /// The output of `finally` is discarded; if it fails, its error is returned instead of the
+    /// outcome of this op.
+    fn finally<F>(self, finally: F) -> Finally<Self, F>
+    where
+        F: JvmOp,
+    {
+        Finally::new(self, finally)
+    }
+
+    /// Given a JVM op that returns a `java.lang.AutoCloseable` (as a `Local` or a `Global`), run `body` with it and
+    /// then call `close()` on it, like Java's try-with-resources. If both `body` and `close()` throw,
+    /// the exception from `close()` is added as a suppressed exception of the one from `body`,
+    /// which is returned; a `null` resource is not closed.
+    ///
+    /// ```rust,ignore
+    /// let first = java::io::StringReader::new(text)
+    ///     .with_resource(|reader, jvm| reader.read().execute_with(jvm))
+    ///     .execute()?;
+    /// ```
+    ///
+    /// Since JVM ops are `Copy`, so must `body` be (which is the case for closures that only capture references),
+    /// and its output can't be tied to the JVM. [`Jvm::with_resource`] has neither restriction.
+    fn with_resource<F, T>(self, body: F) -> WithResource<Self, F>
+    where
+        for<'jvm> Self::Output<'jvm>: AsJRef<AutoCloseable>,
+        F: Copy + for<'jvm> FnOnce(&Self::Output<'jvm>, &mut Jvm<'jvm>) -> crate::Result<'jvm, T>,
+    {
+        WithResource::new(self, body)
+    }
+
+    /// Given the op for a Java method or constructor, catch the exceptions listed in its `throws` clause
+    /// (as declared in `java_package!`). Executing the resulting op returns `Ok(Err(e))` with an
+    /// exception of one of those classes (a [`Local`] for a single class, a [`Caught2`](crate::Caught2) for
+    /// two classes, etc.), so the compiler checks that they are all handled:
+    ///
+    /// ```rust,ignore
+    /// match reader.read().catch_declared().execute_with(jvm)? {
+    ///     Ok(ch) => ...,
+    ///     Err(io_exception) => ...,
+    /// }
+    /// ```
+    ///
+    /// Other exceptions are returned as `Err(Error::Thrown(..))`, as usual.
+    fn catch_declared(self) -> TryCatchDeclared<Self>
+    where
+        Self: DeclaredExceptions,
+    {
+        TryCatchDeclared::new(self)
+    }
+
+    /// Given a JVM op that returns an exception, throw it: executing the resulting op returns
+    /// `Err(Error::Thrown(..))`, just as if a Java method had thrown the exception.
+    /// Returning this error from a [`java_function`][crate::java_function] throws the exception
+    /// in the Java caller:
+    ///
+    /// ```rust,ignore
+    /// if options.legacy {
+    ///     return java::lang::UnsupportedOperationException::new("legacy mode").throw().execute();
+    /// }
+    /// ```
+    fn throw<R>(self) -> Throw<Self, R>
+    where
+        for<'jvm> Self::Output<'jvm>: AsJRef<Throwable>,
+    {
+        Throw::new(self)
+    }
+
+    /// Given a JVM op that returns a Java `Iterator`, `Iterable` (such as a `List` or `Set`) or
+    /// `Stream`, produce an [iterator](crate::JavaIterator) that yields its elements lazily, one
+    /// call to `next(jvm)` at a time.
+    fn iter<J>(self) -> IterOp<Self, J>
+    where
+        for<'jvm> Self::Output<'jvm>: TryJDeref<Java = J>,
+        J: JavaIterable,
+    {
+        IterOp::new(self)
+    }
+
     /// Given a JVM op that returns some Java type, convert it to its Rust equivalent
     /// (e.g., from a Java String to a Rust string).
     fn to_rust<R>(self) -> ToRustOp<Self, R>
@@ -110,6 +209,23 @@ pub trait JvmOp: Copy {
         Jvm::with(|jvm| self.execute_with(jvm))
     }
 
+    /// Execute the jvm op on one of duchess's worker threads, returning a future that resolves
+    /// to its result. Unlike [`execute()`][`Self::execute`], this doesn't block (or attach)
+    /// the calling thread, so it can be used from async code without stalling the executor.
+    ///
+    /// The op only starts executing once the future is first polled. The worker threads are
+    /// started on first use and stay attached to the JVM; their number can be configured with
+    /// [`JvmBuilder::worker_threads`]. As with `execute()`, the result type cannot be tied to the
+    /// JVM, and the op itself must be `Send + 'static` (e.g., it may reference [`Global`]s but not `Local`s).
+    fn execute_async<R>(self) -> ExecuteAsync<Self, R>
+    where
+        for<'jvm> Self: JvmOp<Output<'jvm> = R>,
+        Self: Send + 'static,
+        R: Send + 'static,
+    {
+        ExecuteAsync::new(self)
+    }
+
     fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>>;
 }
 
@@ -133,16 +249,17 @@ fn get_or_default_init_jvm() -> crate::GlobalResult<JvmPtr> {
 }
 
 /// Invoked as the body from a JNI native function when it is called by the JVM.
-/// Initializes the environment and invokes `op`. Converts the result into a java
-/// object and returns it. Caller should then return this to the JVM.
+/// Initializes the environment and invokes `op` with a [`Jvm`] for it. Converts
+/// the result into a java object and returns it. Caller should then return this
+/// to the JVM.
 ///
-/// # Safety condition
+/// # Safety
 ///
 /// Must be invoked as the entire body of a JNI native function, with
 /// `env` being the `EnvPtr` argument provided.
-pub unsafe fn native_function_returning_object<J, R>(
-    env: EnvPtr<'_>,
-    op: impl FnOnce() -> R,
+pub unsafe fn native_function_returning_object<'env, J, R>(
+    env: EnvPtr<'env>,
+    op: impl FnOnce(&mut Jvm<'env>) -> R,
 ) -> jni_sys::jobject
 where
     J: Upcast<crate::java::lang::Object> + Upcast<J>,
@@ -151,19 +268,24 @@ where
     init_jvm_from_native_function(env);
     let _callback_guard = thread::attach_from_jni_callback(env);
 
-    let result = match std::panic::catch_unwind(AssertUnwindSafe(|| op())) {
+    let result = match std::panic::catch_unwind(AssertUnwindSafe(|| op(&mut Jvm(env)))) {
         Ok(result) => {
             let mut jvm = Jvm(env);
             let obj = result.to_java().execute_with(&mut jvm);
             match obj {
                 Ok(Some(p)) => p.into_raw().as_ptr(),
                 Ok(None) => std::ptr::null_mut(),
-                Err(e) => todo!("convert from exception `{e:?}` into java exception"),
+                Err(e) => {
+                    if let Err(err) = error_to_java_exception(&mut jvm, e) {
+                        tracing::warn!(?err, "unable to throw exception from native function");
+                    }
+                    std::ptr::null_mut()
+                }
             }
         }
 
         Err(e) => {
-            rust_panic_to_java_exception(e);
+            rust_panic_to_java_exception(&mut Jvm(env), e);
             std::ptr::null_mut()
         }
     };
@@ -172,37 +294,62 @@ where
 }
 
 /// Invoked as the body from a JNI native function when it is called by the JVM.
-/// Initializes the environment and invokes `op`, returning the result, which should
-/// then be returned to the JVM.
+/// Initializes the environment and invokes `op` with a [`Jvm`] for it, converting
+/// the result into the scalar `S` (or `()` for `void` methods), which should then
+/// be returned to the JVM. If `op` returns an error, it is thrown as a Java
+/// exception instead.
 ///
-/// # Safety condition
+/// # Safety
 ///
 /// Must be invoked as the entire body of a JNI native function, with
 /// `env` being the `EnvPtr` argument provided.
-pub unsafe fn native_function_returning_scalar<J, R>(env: EnvPtr<'_>, op: impl FnOnce() -> R) -> R
+pub unsafe fn native_function_returning_scalar<'env, S, R>(
+    env: EnvPtr<'env>,
+    op: impl FnOnce(&mut Jvm<'env>) -> R,
+) -> S
 where
-    J: Upcast<crate::java::lang::Object> + Upcast<J>,
-    R: JavaScalar,
+    S: Default,
+    R: ToJavaScalar<S>,
 {
     init_jvm_from_native_function(env);
     let _callback_guard = thread::attach_from_jni_callback(env);
 
-    let result = match std::panic::catch_unwind(AssertUnwindSafe(|| op())) {
-        Ok(result) => result,
+    let result = match std::panic::catch_unwind(AssertUnwindSafe(|| op(&mut Jvm(env)))) {
+        Ok(result) => {
+            let mut jvm = Jvm(env);
+            match result.to_java_scalar(&mut jvm) {
+                Ok(s) => s,
+                Err(e) => {
+                    if let Err(err) = error_to_java_exception(&mut jvm, e) {
+                        tracing::warn!(?err, "unable to throw exception from native function");
+                    }
+                    S::default()
+                }
+            }
+        }
         Err(e) => {
-            rust_panic_to_java_exception(e);
-            R::default()
+            rust_panic_to_java_exception(&mut Jvm(env), e);
+            S::default()
         }
     };
 
     result
 }
 
+/// Converts an argument of a native function (or a method implemented in Rust) with the [`Jvm`]
+/// that the function was invoked with, rather than attaching again through [`JvmOp::execute`].
+pub fn convert_argument<'jvm, O, R>(jvm: &mut Jvm<'jvm>, op: O) -> crate::GlobalResult<R>
+where
+    O: JvmOp<Output<'jvm> = R>,
+{
+    op.execute_with(jvm).map_err(|err| err.into_global(jvm))
+}
+
 /// Invoked from inside a JNI native function when it is called by the JVM.
 /// If `GLOBAL_JVM` is not yet set, initializes it to use the provided `jvm`.
 /// Otherwise, does nothing.
 ///
-/// # Safety condition
+/// # Safety
 ///
 /// Must be invoked as the first thing from inside a JNI native function.
 unsafe fn init_jvm_from_native_function(env: EnvPtr<'_>) -> Jvm<'_> {
@@ -218,8 +365,143 @@ unsafe fn init_jvm_from_native_function(env: EnvPtr<'_>) -> Jvm<'_> {
     Jvm(env)
 }
 
-fn rust_panic_to_java_exception(_panic: Box<dyn Any + Send + 'static>) {
-    todo!("rust_panic_to_java_exception")
+thread_local! {
+    /// Backtrace of the most recent panic on this thread, recorded by the hook from [`install_panic_hook`] (if
+    /// [`JvmBuilder::capture_panic_backtraces`] installed it).
+    static PANIC_BACKTRACE: Cell<Option<Backtrace>> = const { Cell::new(None) };
+}
+
+/// Installs (once) a panic hook that records the backtrace of each panic so that it can be attached to the
+/// resulting [`RustPanicException`]. Backtraces are only captured if enabled (e.g., with `RUST_BACKTRACE=1`).
+/// The previously installed hook still runs afterwards.
+///
+/// A hook is needed because the stack has been unwound by the time `catch_unwind` returns the panic.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let backtrace = Backtrace::capture();
+            if backtrace.status() == BacktraceStatus::Captured {
+                PANIC_BACKTRACE.with(|b| b.set(Some(backtrace)));
+            }
+            previous_hook(info)
+        }));
+    });
+}
+
+/// Returns the message of a panic payload.
+pub(crate) fn panic_message(panic: &(dyn Any + Send + 'static)) -> String {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+/// Creates a [`RustPanicException`] describing `panic`, which was caught on the current thread.
+pub(crate) fn rust_panic_to_throwable<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    panic: Box<dyn Any + Send + 'static>,
+) -> crate::Result<'jvm, Local<'jvm, Throwable>> {
+    let message = panic_message(&*panic);
+    let backtrace = PANIC_BACKTRACE
+        .with(|b| b.take())
+        .map(|b| b.to_string())
+        .unwrap_or_default();
+
+    crate::bundled::define_bundled_classes(jvm)?;
+    let exception = RustPanicException::new(&message, &backtrace).execute_with(jvm)?;
+    Ok(exception.upcast())
+}
+
+/// Throws a [`RustPanicException`] describing `panic` so that the JVM sees it once the native function returns.
+fn rust_panic_to_java_exception(jvm: &mut Jvm<'_>, panic: Box<dyn Any + Send + 'static>) {
+    let message = panic_message(&*panic);
+    let result = rust_panic_to_throwable(jvm, panic).and_then(|exception| {
+        // SAFETY: `exception` is a `Throwable`
+        unsafe { jvm.env().throw(exception.as_raw()) }
+    });
+
+    // If constructing the exception failed (e.g., with an `OutOfMemoryError`), throw that instead.
+    if let Err(err) = result.or_else(|e| error_to_java_exception(jvm, e)) {
+        tracing::warn!(
+            ?err,
+            "unable to throw RustPanicException: panic was `{message}`"
+        );
+    }
+}
+
+/// Makes `error` the pending Java exception so that the JVM sees it once the native function returns.
+///
+/// [`Error::Thrown`] rethrows the original exception. The remaining variants are reported as a new exception of a
+/// JDK class that matches their meaning, with the error's `Display` output as the message:
+///
+/// * [`Error::NullDeref`] throws `java.lang.NullPointerException`
+/// * [`Error::SliceTooLong`] throws `java.lang.IllegalArgumentException`
+/// * [`Error::JvmAlreadyExists`] throws `java.lang.IllegalStateException`
+/// * anything else throws `java.lang.RuntimeException`
+fn error_to_java_exception<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    error: Error<Local<'jvm, Throwable>>,
+) -> crate::Result<'jvm, ()> {
+    use crate::java::lang::{
+        IllegalArgumentException, IllegalStateException, NullPointerException, RuntimeException,
+    };
+
+    let class = match &error {
+        // SAFETY: `e` is a `Throwable`
+        Error::Thrown(e) => return unsafe { jvm.env().throw(e.as_raw()) },
+        Error::NullDeref => NullPointerException::class(jvm)?,
+        Error::SliceTooLong(_) => IllegalArgumentException::class(jvm)?,
+        Error::JvmAlreadyExists => IllegalStateException::class(jvm)?,
+        _ => RuntimeException::class(jvm)?,
+    };
+
+    // SAFETY: all of the classes above are `Throwable`s with a `(String)` constructor
+    unsafe { jvm.env().throw_new(class.as_raw(), &error.to_string()) }
+}
+
+/// Creates the Java exception that [`error_to_java_exception`] would throw for `error`, without throwing it.
+pub(crate) fn error_to_throwable<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    error: Error<Local<'jvm, Throwable>>,
+) -> crate::Result<'jvm, Local<'jvm, Throwable>> {
+    if let Error::Thrown(exception) = error {
+        return Ok(exception.into_inner());
+    }
+
+    // `ThrowNew` is the only way to construct these exceptions with a message, so throw and immediately catch it
+    error_to_java_exception(jvm, error)?;
+    match jvm.env().check_exception() {
+        Err(Error::Thrown(exception)) => Ok(exception.into_inner()),
+        Err(err) => Err(err),
+        Ok(()) => Err(Error::JvmInternal(
+            "converting an error into an exception did not throw".to_string(),
+        )),
+    }
+}
+
+/// Creates an exception of class `E` with `message`, to be returned as an error.
+pub(crate) fn new_exception<'jvm, E: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    message: &str,
+) -> Error<Local<'jvm, Throwable>> {
+    let class = match E::class(jvm) {
+        Ok(class) => class,
+        Err(err) => return err,
+    };
+    // `ThrowNew` is the only way to construct the exception with a message, so throw and immediately catch it
+    // SAFETY: `E` is a `Throwable` with a `(String)` constructor
+    if let Err(err) = unsafe { jvm.env().throw_new(class.as_raw(), message) } {
+        return err;
+    }
+    match jvm.env().check_exception() {
+        Err(err) => err,
+        Ok(()) => Error::JvmInternal("throwing an exception did not throw".to_string()),
+    }
 }
 
 /// Get the global [`JvmPtr`] assuming that the JVM has already been initialized. Expected to be used with values
@@ -240,8 +522,18 @@ impl<'jvm> Jvm<'jvm> {
     }
 
     pub fn attach_thread_permanently() -> crate::GlobalResult<()> {
-        thread::attach_permanently(get_or_default_init_jvm()?)?;
-        Ok(())
+        thread::attach_permanently(get_or_default_init_jvm()?)
+    }
+
+    /// Sets the executor used to run the futures returned by async native functions (see
+    /// [`java_function`][crate::java_function]): `spawn` is called with each such future and must
+    /// run it to completion, e.g. `|future| { runtime_handle.spawn(future); }` with tokio.
+    ///
+    /// If no executor is set, the futures are driven by duchess's worker threads (see
+    /// [`JvmOp::execute_async`]), so they shouldn't block. Must be called before the first
+    /// async native function is invoked to take effect.
+    pub fn set_executor(spawn: impl Fn(execute_async::BoxFuture) + Send + Sync + 'static) {
+        execute_async::set_executor(Box::new(spawn))
     }
 
     pub fn with<R>(
@@ -252,6 +544,7 @@ impl<'jvm> Jvm<'jvm> {
         let mut guard = unsafe { thread::attach(jvm)? };
 
         let mut jvm = Jvm(guard.env());
+        crate::bundled::define_bundled_classes(&mut jvm).map_err(|e| e.into_global(&mut jvm))?;
         op(&mut jvm).map_err(|e| e.into_global(&mut jvm))
     }
 
@@ -269,13 +562,138 @@ impl<'jvm> Jvm<'jvm> {
         Global::new(self.0, r)
     }
 
+    /// Throws `exception`, returning `Err(Error::Thrown(..))` just as if a Java method had thrown it
+    /// (or `Err(Error::NullDeref)` if `exception` is null).
+    /// Returning this error from a [`java_function`][crate::java_function] throws the exception in
+    /// the Java caller; this can be used to rethrow an exception that was caught earlier.
+    pub fn throw<R>(&mut self, exception: &impl AsJRef<Throwable>) -> crate::Result<'jvm, R> {
+        let exception = exception.as_jref().map_err(|_| Error::NullDeref)?;
+        let snapshot = crate::snapshot::capture(self.0, exception);
+        Err(Error::Thrown(Thrown::with_snapshot(
+            self.local(exception),
+            snapshot,
+        )))
+    }
+
+    /// Throws a new exception of class `E` with the given message (like Java's `throw new E(message)`),
+    /// see [`throw`][Self::throw]. `E` must have a constructor taking a single `String`.
+    ///
+    /// Returns the `Error::Thrown(..)`, to be returned with `Err(..)`:
+    ///
+    /// ```rust,ignore
+    /// return Err(jvm.throw_new::<java::lang::IllegalArgumentException>("negative size"));
+    /// ```
+    pub fn throw_new<E>(&mut self, message: &str) -> Error<Local<'jvm, Throwable>>
+    where
+        E: Upcast<Throwable>,
+    {
+        new_exception::<E>(self, message)
+    }
+
+    /// Ensures that at least `capacity` more local references can be created in the current local frame,
+    /// throwing `OutOfMemoryError` otherwise.
+    pub fn ensure_local_capacity(&mut self, capacity: i32) -> crate::Result<'jvm, ()> {
+        self.0.ensure_local_capacity(capacity)
+    }
+
+    /// Executes `resource`, which produces a `java.lang.AutoCloseable`, runs `body` with it and then calls `close()`
+    /// on it, like Java's try-with-resources (see [`JvmOp::with_resource`] for how exceptions are combined).
+    /// Unlike with the operation, `body` runs right away, so it can capture anything and return `Local`s:
+    ///
+    /// ```rust,ignore
+    /// let line: Option<Local<'_, java::lang::String>> = jvm.with_resource(
+    ///     java::io::BufferedReader::new(reader),
+    ///     |reader, jvm| reader.read_line().execute_with(jvm),
+    /// )?;
+    /// ```
+    pub fn with_resource<R, T>(
+        &mut self,
+        resource: R,
+        body: impl FnOnce(&R::Output<'jvm>, &mut Jvm<'jvm>) -> crate::Result<'jvm, T>,
+    ) -> crate::Result<'jvm, T>
+    where
+        R: JvmOp,
+        R::Output<'jvm>: AsJRef<AutoCloseable>,
+    {
+        crate::with_resource::with_resource(self, resource, body)
+    }
+
+    /// Runs `op` in a new JNI local frame with room for at least `capacity` local references.
+    /// All local references created by `op` are freed when it returns, except for the one it returns
+    /// (or the exception it throws), which is promoted into the current frame.
+    ///
+    /// Use this when creating many local references in a loop, which could otherwise overflow the
+    /// JVM's local reference table:
+    ///
+    /// ```rust,ignore
+    /// for n in 0..1_000_000 {
+    ///     jvm.with_local_frame(4, |jvm| {
+    ///         list.add(n.to_string().to_java()).execute_with(jvm)?;
+    ///         Ok(None::<Local<'_, java::lang::Object>>)
+    ///     })?;
+    /// }
+    /// ```
+    pub fn with_local_frame<T>(
+        &mut self,
+        capacity: i32,
+        op: impl for<'frame> FnOnce(
+            &mut Jvm<'frame>,
+        ) -> crate::Result<'frame, Option<Local<'frame, T>>>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, T>>>
+    where
+        T: JavaObject,
+    {
+        let env = self.0;
+
+        // SAFETY: the frame is popped below, or by `pop_on_unwind` if `op` panics
+        unsafe { env.push_local_frame(capacity)? };
+        let pop_on_unwind = PopLocalFrameGuard(env);
+        let result = op(&mut Jvm(env));
+        std::mem::forget(pop_on_unwind);
+
+        // SAFETY: the `'frame` lifetime ensures no other local reference created by `op` can still be in use, and
+        // the promoted references are instances of `T` (resp. `Throwable`) since they were converted from `Local`s.
+        unsafe {
+            match result {
+                Ok(value) => {
+                    let value = value.map(|v| v.into_raw());
+                    Ok(env
+                        .pop_local_frame(value)
+                        .map(|ptr| Local::from_raw(env, ptr)))
+                }
+                Err(Error::Thrown(exception)) => Err(Error::Thrown(exception.map(|exception| {
+                    let exception = env.pop_local_frame(Some(exception.into_raw())).unwrap();
+                    Local::from_raw(env, exception)
+                }))),
+                Err(err) => {
+                    env.pop_local_frame(None);
+                    Err(match err {
+                        Error::Thrown(_) => unreachable!(),
+                        Error::SliceTooLong(s) => Error::SliceTooLong(s),
+                        Error::NullDeref => Error::NullDeref,
+                        Error::JvmAlreadyExists => Error::JvmAlreadyExists,
+                        #[cfg(feature = "dylibjvm")]
+                        Error::UnableToLoadLibjvm(e) => Error::UnableToLoadLibjvm(e),
+                        Error::JvmInternal(m) => Error::JvmInternal(m),
+                    })
+                }
+            }
+        }
+    }
+
     /// Plumbing method that should only be used by generated and internal code.
     #[doc(hidden)]
     pub fn env(&self) -> EnvPtr<'jvm> {
         self.0
     }
 
-    fn register_native_methods(
+    /// Wraps an environment that internal code only has as an [`EnvPtr`], e.g. to take the snapshot of an
+    /// exception caught by [`EnvPtr::check_exception`].
+    pub(crate) fn from_env(env: EnvPtr<'jvm>) -> Self {
+        Jvm(env)
+    }
+
+    pub(crate) fn register_native_methods(
         &mut self,
         java_functions: &[JavaFunction],
     ) -> crate::Result<'jvm, ()> {
@@ -305,11 +723,24 @@ impl<'jvm> Jvm<'jvm> {
     }
 }
 
+/// Pops the local frame pushed by [`Jvm::with_local_frame`] if its closure panics.
+struct PopLocalFrameGuard<'jvm>(EnvPtr<'jvm>);
+
+impl Drop for PopLocalFrameGuard<'_> {
+    fn drop(&mut self) {
+        // SAFETY: we are unwinding out of the frame, so none of its local references can be used anymore
+        unsafe { self.0.pop_local_frame(None) };
+    }
+}
+
 pub struct JvmBuilder {
     options: Vec<String>,
     #[cfg(feature = "dylibjvm")]
     libjvm_path: Option<std::path::PathBuf>,
     java_functions: Vec<JavaFunction>,
+    worker_threads: Option<usize>,
+    snapshot_exceptions: Option<bool>,
+    capture_panic_backtraces: bool,
 }
 
 impl JvmBuilder {
@@ -319,6 +750,9 @@ impl JvmBuilder {
             #[cfg(feature = "dylibjvm")]
             libjvm_path: None,
             java_functions: vec![],
+            worker_threads: None,
+            snapshot_exceptions: None,
+            capture_panic_backtraces: false,
         };
 
         if cfg!(debug_assertions) {
@@ -351,8 +785,46 @@ impl JvmBuilder {
         self
     }
 
+    /// Sets the number of threads used to execute [`JvmOp::execute_async`][] calls. Defaults to
+    /// the available parallelism. Has no effect once the first `execute_async` has started.
+    pub fn worker_threads(mut self, threads: usize) -> Self {
+        assert!(threads > 0, "duchess needs at least one worker thread");
+        self.worker_threads = Some(threads);
+        self
+    }
+
+    /// Sets whether an [`ExceptionSnapshot`](crate::ExceptionSnapshot) is taken of each exception that duchess
+    /// catches (see [`Thrown`](crate::Thrown)). Defaults to true; disabling it saves the calls into Java that take
+    /// the snapshot, e.g. for code that relies on exceptions for control flow, but formatting an error then only
+    /// says that an exception was thrown.
+    pub fn snapshot_exceptions(mut self, enabled: bool) -> Self {
+        self.snapshot_exceptions = Some(enabled);
+        self
+    }
+
+    /// Attaches the Rust backtrace of a panic in a [`java_function`](crate::java_function) (or closure) to the
+    /// resulting [`RustPanicException`]. Defaults to false, since it requires replacing the process-wide panic
+    /// hook (see [`std::panic::set_hook`]) with one that records the backtrace of every panic, on every thread,
+    /// before calling the previous hook. Backtraces are only captured if enabled (e.g., with `RUST_BACKTRACE=1`).
+    ///
+    /// When Java loads the Rust code, this can be enabled with [`launch_or_use_existing`](Self::launch_or_use_existing).
+    pub fn capture_panic_backtraces(mut self, enabled: bool) -> Self {
+        self.capture_panic_backtraces = enabled;
+        self
+    }
+
     /// Launch a new JVM, returning [`Error::JvmAlreadyExists`] if one already exists.
     pub fn try_launch(self) -> GlobalResult<()> {
+        if let Some(threads) = self.worker_threads {
+            execute_async::set_worker_threads(threads);
+        }
+        if let Some(enabled) = self.snapshot_exceptions {
+            crate::snapshot::set_enabled(enabled);
+        }
+        if self.capture_panic_backtraces {
+            install_panic_hook();
+        }
+
         #[cfg(feature = "dylibjvm")]
         if let Some(path) = self.libjvm_path {
             crate::libjvm::libjvm_or_load_at(&path)?;
@@ -503,10 +975,48 @@ unsafe impl<T: JavaObject> JavaType for T {
     }
 }
 
-pub trait JavaScalar: JavaType + Default {}
+pub trait JavaScalar: JavaType + Default {
+    /// The class whose instances box values of this type (e.g., `java.lang.Integer` for `int`).
+    type Boxed: Upcast<crate::java::lang::Object>;
+
+    fn to_boxed<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Self::Boxed>>;
+
+    fn from_boxed<'jvm>(boxed: &Self::Boxed, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self>;
+}
+
+/// Rust values that can be returned from a native function whose Java return type is the scalar `S`
+/// (or `void`, represented as `()`). Fallible results are converted into a thrown Java exception.
+pub trait ToJavaScalar<S> {
+    fn to_java_scalar<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, S>;
+}
+
+impl<S: JavaScalar> ToJavaScalar<S> for S {
+    fn to_java_scalar<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, S> {
+        Ok(self)
+    }
+}
+
+impl ToJavaScalar<()> for () {
+    fn to_java_scalar<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+        Ok(())
+    }
+}
+
+impl<S, R, E> ToJavaScalar<S> for Result<R, E>
+where
+    R: ToJavaScalar<S>,
+    E: ToJavaThrowable,
+{
+    fn to_java_scalar<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, S> {
+        match self {
+            Ok(r) => r.to_java_scalar(jvm),
+            Err(e) => Err(e.to_java_throwable(jvm)),
+        }
+    }
+}
 
 macro_rules! scalar {
-    ($($rust:ty: $array_class:literal,)*) => {
+    ($($rust:ty: $array_class:literal $boxed:ident::$unbox:ident,)*) => {
         $(
             unsafe impl JavaType for $rust {
                 fn array_class<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Class>> {
@@ -522,20 +1032,45 @@ macro_rules! scalar {
                 }
             }
 
-            impl JavaScalar for $rust {}
+            impl JavaScalar for $rust {
+                type Boxed = crate::java::lang::$boxed;
+
+                fn to_boxed<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Self::Boxed>> {
+                    crate::java::lang::$boxed::value_of(self).assert_not_null().execute_with(jvm)
+                }
+
+                fn from_boxed<'jvm>(boxed: &Self::Boxed, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self> {
+                    boxed.$unbox().execute_with(jvm)
+                }
+            }
+
+            impl IntoRust<$rust> for &crate::java::lang::$boxed {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $rust> {
+                    <$rust>::from_boxed(self, jvm)
+                }
+            }
+
+            impl ToJavaImpl<crate::java::lang::$boxed> for $rust {
+                fn to_java_impl<'jvm>(
+                    rust: &Self,
+                    jvm: &mut Jvm<'jvm>,
+                ) -> crate::Result<'jvm, Option<Local<'jvm, crate::java::lang::$boxed>>> {
+                    Ok(Some(rust.to_boxed(jvm)?))
+                }
+            }
         )*
     };
 }
 
 scalar! {
-    bool: b"[Z\0",
-    i8:   b"[B\0",
-    i16:  b"[S\0",
-    u16:  b"[C\0",
-    i32:  b"[I\0",
-    i64:  b"[J\0",
-    f32:  b"[F\0",
-    f64:  b"[D\0",
+    bool: b"[Z\0" Boolean::boolean_value,
+    i8:   b"[B\0" Byte::byte_value,
+    i16:  b"[S\0" Short::short_value,
+    u16:  b"[C\0" Character::char_value,
+    i32:  b"[I\0" Integer::int_value,
+    i64:  b"[J\0" Long::long_value,
+    f32:  b"[F\0" Float::float_value,
+    f64:  b"[D\0" Double::double_value,
 }
 
 pub trait CloneIn<'jvm> {
@@ -1,38 +1,71 @@
 //! Experiments with Java-Rust interop.
 
 mod array;
+mod bundled;
+mod byte_buffer;
 mod cast;
+mod class_file;
+mod closure;
 mod error;
+mod execute_async;
 mod find;
 mod from_ref;
+mod future;
 mod global;
 mod into_rust;
+mod iter;
 mod jvm;
 mod libjvm;
 mod link;
 mod not_null;
+mod oneshot;
 mod ops;
+mod proxy;
 mod raw;
 mod ref_;
 mod refs;
+mod rust_object;
+mod snapshot;
 mod str;
+mod subclass;
 mod thread;
+mod throw;
 mod to_java;
 mod try_catch;
+mod view;
+mod with_resource;
 
 /// Contains reusable declarations for classes distributed by the JDK under the `java.*` packages.
 pub mod java;
 
-pub use duchess_macro::{java_function, java_package, ToJava, ToRust};
-pub use error::{Error, GlobalResult, Result};
+pub use array::{ArrayAccess, ArrayChunks, ArrayElement, ArrayElements, IntoArrayElement};
+pub use bundled::RustPanicException;
+pub use closure::{Closure, RustFn};
+pub use duchess_macro::{
+    java_function, java_interface, java_package, java_peer, java_subclass, ToJava, ToRust,
+};
+pub use error::{Error, GlobalResult, Result, Thrown};
+pub use execute_async::{BoxFuture, ExecuteAsync};
+pub use future::JavaFuture;
 pub use into_rust::IntoRust;
+pub use iter::{JavaIterable, JavaIterator, ToRustIterator};
 pub use jvm::JavaObject;
 pub use jvm::JavaType;
 pub use jvm::Jvm;
 pub use link::JavaFunction;
+pub use proxy::{JavaInterface, Proxy};
 pub use ref_::{Global, Local};
 pub use refs::{AsJRef, JDeref, NullJRef, Nullable, TryJDeref};
-pub use try_catch::TryCatch;
+pub use rust_object::{JavaPeer, RustObject};
+pub use snapshot::ExceptionSnapshot;
+pub use subclass::{JavaSubclass, Protected, Subclass};
+pub use throw::Throw;
+pub use to_java::ToJavaThrowable;
+pub use try_catch::{
+    Catchable, Caught2, Caught3, Caught4, Finally, TryCatch, TryCatchAny, TryCatchDeclared,
+};
+pub use view::{IteratorView, ListView, MapView};
+pub use with_resource::WithResource;
 
 pub use prelude::*;
 
@@ -55,16 +88,27 @@ pub mod plumbing {
     pub use crate::cast::Upcast;
     pub use crate::find::{find_class, find_constructor, find_field, find_method};
     pub use crate::from_ref::FromRef;
+    pub use crate::future::native_function_returning_future;
     pub use crate::global::GlobalOp;
+    pub use crate::jvm::convert_argument;
     pub use crate::jvm::native_function_returning_object;
     pub use crate::jvm::native_function_returning_scalar;
     pub use crate::jvm::JavaObjectExt;
+    pub use crate::jvm::JavaScalar;
     pub use crate::jvm::JavaView;
+    pub use crate::jvm::ToJavaScalar;
     pub use crate::link::JavaFn;
     pub use crate::link::JavaFunction;
+    pub use crate::proxy::{
+        object_argument, object_result, scalar_argument, scalar_result, unsupported_method,
+        void_result,
+    };
     pub use crate::raw::{EnvPtr, FieldPtr, FromJniValue, IntoJniValue, MethodPtr, ObjectPtr};
     pub use crate::refs::NullJRef;
+    pub use crate::rust_object::{define_peer_class, PeerClass};
+    pub use crate::subclass::{define_subclass, subclass_value, ProtectedView, SubclassInfo};
     pub use crate::to_java::ToJavaImpl;
+    pub use crate::try_catch::{catch_as, catch_named, DeclaredExceptions};
     pub use jni_sys;
     pub use once_cell;
 }
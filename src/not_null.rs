@@ -1,4 +1,6 @@
-use crate::{Error, JavaObject, JvmOp, Local};
+use crate::{
+    java::lang::Throwable, try_catch::DeclaredExceptions, Error, JavaObject, Jvm, JvmOp, Local,
+};
 
 #[derive_where::derive_where(Copy, Clone)]
 pub struct NotNull<J: JvmOp> {
@@ -30,3 +32,17 @@ where
         j.ok_or(Error::NullDeref)
     }
 }
+
+impl<J> DeclaredExceptions for NotNull<J>
+where
+    J: JvmOp + DeclaredExceptions,
+{
+    type Caught<'jvm> = J::Caught<'jvm>;
+
+    fn try_catch<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        exception: &Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Option<Self::Caught<'jvm>>> {
+        J::try_catch(jvm, exception)
+    }
+}
@@ -1,3 +1,5 @@
+use crate::java::lang::IllegalArgumentException;
+use crate::jvm::new_exception;
 use crate::jvm::JavaScalar;
 use crate::jvm::Jvm;
 use crate::jvm::JvmOp;
@@ -26,8 +28,6 @@ identity_jvm_op! {
     [] i16,  // short
     [] i32,  // int
     [] i64,  // long
-
-    [] char, // long (Java char is 2 bytes / UTF-16)
     [] u16,  // char
 
     [] (),  // void
@@ -42,6 +42,24 @@ identity_jvm_op! {
     [R: JavaObject] &Option<Global<R>>,
 }
 
+/// A Rust `char` can be passed wherever a Java `char` is expected. Java `char`s are UTF-16 code
+/// units, so this throws an `IllegalArgumentException` for characters outside of the Basic
+/// Multilingual Plane (which Java represents as a pair of surrogate `char`s).
+impl JvmOp for char {
+    type Output<'jvm> = u16;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let mut units = [0; 2];
+        match *self.encode_utf16(&mut units) {
+            [unit] => Ok(unit),
+            _ => Err(new_exception::<IllegalArgumentException>(
+                jvm,
+                &format!("`{self}` does not fit in a single Java `char`"),
+            )),
+        }
+    }
+}
+
 /// Types that are able to be used as a Java `T`, either because they will produce a Java `T` (e.g. [`JvmOp`]s that
 /// produce a `T`) or because we can convert into them via a JNI call.
 ///
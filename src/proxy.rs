@@ -0,0 +1,231 @@
+//! Implements Java interfaces with Rust values, using `java.lang.reflect.Proxy`.
+
+use std::sync::Arc;
+
+// Make current crate available as `duchess` for use by the generated code.
+// NB. in doctests, the current crate is already available as duchess.
+#[cfg(not(doctest))]
+use crate as duchess;
+
+use crate::{
+    bundled::{define_bundled_classes, duchess_rs},
+    cast::Upcast,
+    java,
+    java::lang::Object,
+    java_function,
+    jvm::{JavaObjectExt, JavaScalar, ToJavaScalar},
+    link::JavaFn,
+    plumbing::{JavaFunction, ToJavaImpl},
+    Error, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// A Rust value that can be converted (with [`to_java`](crate::ToJava::to_java)) into a Java
+/// object implementing the interface `I`, for any `I` such that the value implements
+/// [`JavaInterface<I>`]. Calls to the methods of the Java object are forwarded to the Rust value.
+///
+/// The value is dropped once the Java object has been garbage collected.
+///
+/// ```rust,ignore
+/// #[duchess::java_interface(java.util.Comparator)]
+/// impl java::util::Comparator<java::lang::String> for ByLength {
+///     fn compare(&self, a: String, b: String) -> i32 {
+///         a.len().cmp(&b.len()) as i32
+///     }
+/// }
+///
+/// let comparator = Proxy::new(ByLength).to_java::<java::util::Comparator<java::lang::String>>();
+/// ```
+#[derive_where::derive_where(Clone)]
+pub struct Proxy<T> {
+    value: Arc<T>,
+}
+
+impl<T> Proxy<T> {
+    pub fn new(value: T) -> Self {
+        Proxy {
+            value: Arc::new(value),
+        }
+    }
+}
+
+/// Rust types that implement the Java interface `I`. Usually implemented with the
+/// [`java_interface`](crate::java_interface) attribute.
+pub trait JavaInterface<I>: Send + Sync + 'static {
+    /// The methods of `I` that the Rust value implements, each given by its name followed by its descriptor
+    /// (e.g., `compare(Ljava/lang/Object;Ljava/lang/Object;)I`). The other default methods of `I` run their
+    /// default body.
+    const METHODS: &'static [&'static str];
+
+    /// Invokes `method`, given by its name and descriptor, with the arguments passed by Java. Scalar arguments and
+    /// results are boxed (e.g., an `int` is passed as a `java.lang.Integer`); `void` methods
+    /// return `None`.
+    fn invoke<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        method: &str,
+        args: &java::Array<Object>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>>;
+}
+
+/// The value that a `duchess_rs.RustInvocationHandler` points to.
+struct Handler {
+    /// The [`JavaInterface::METHODS`] of the Rust value.
+    methods: &'static [&'static str],
+    invoke: Box<Invoke>,
+}
+
+/// The type-erased [`JavaInterface::invoke`] of the Rust value.
+type Invoke = dyn for<'jvm> Fn(
+        &mut Jvm<'jvm>,
+        &str,
+        &java::Array<Object>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>>
+    + Send
+    + Sync;
+
+impl<I, T> ToJavaImpl<I> for Proxy<T>
+where
+    I: Upcast<Object>,
+    T: JavaInterface<I>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, I>>> {
+        define_bundled_classes(jvm)?;
+
+        let class = I::class(jvm)?;
+        let value = rust.value.clone();
+        let handler = Handler {
+            methods: T::METHODS,
+            invoke: Box::new(move |jvm, method, args| value.invoke(jvm, method, args)),
+        };
+        let handler: *mut Handler = Box::into_raw(Box::new(handler));
+        match duchess_rs::RustInvocationHandler::new_proxy(&class, handler as i64)
+            .assert_not_null()
+            .execute_with(jvm)
+        {
+            // SAFETY: the proxy implements `I`
+            Ok(object) => Ok(Some(unsafe { object.cast::<I>() })),
+            Err(err) => {
+                // SAFETY: the handler wasn't created, so we still own `handler`
+                drop(unsafe { Box::from_raw(handler) });
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Returns the argument at `index`, an object of type `J` (or `null`).
+///
+/// # Safety
+///
+/// `J` must be the type that the method being invoked declares for this argument.
+pub unsafe fn object_argument<'jvm, J: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    args: &java::Array<Object>,
+    index: i32,
+) -> crate::Result<'jvm, Option<Local<'jvm, J>>> {
+    let args = args.as_raw();
+    let arg: Option<Local<'jvm, Object>> = jvm.env().invoke(
+        |env| env.GetObjectArrayElement,
+        |env, f| f(env, args.as_ptr(), index),
+    )?;
+    Ok(arg.map(|arg| arg.cast::<J>()))
+}
+
+/// Returns the argument at `index`, a boxed `S`. Passing `null` is an error.
+///
+/// # Safety
+///
+/// `S` must be the type that the method being invoked declares for this argument.
+pub unsafe fn scalar_argument<'jvm, S: JavaScalar>(
+    jvm: &mut Jvm<'jvm>,
+    args: &java::Array<Object>,
+    index: i32,
+) -> crate::Result<'jvm, S> {
+    let arg = object_argument::<S::Boxed>(jvm, args, index)?.ok_or(Error::NullDeref)?;
+    S::from_boxed(&arg, jvm)
+}
+
+/// Converts the result of a Rust method implementing a Java method that returns a `U` object.
+pub fn object_result<'jvm, U, R>(
+    jvm: &mut Jvm<'jvm>,
+    result: R,
+) -> crate::Result<'jvm, Option<Local<'jvm, Object>>>
+where
+    U: Upcast<Object>,
+    R: ToJavaImpl<U>,
+{
+    Ok(R::to_java_impl(&result, jvm)?.map(|object| object.upcast()))
+}
+
+/// Converts (and boxes) the result of a Rust method implementing a Java method that returns the scalar `S`.
+pub fn scalar_result<'jvm, S, R>(
+    jvm: &mut Jvm<'jvm>,
+    result: R,
+) -> crate::Result<'jvm, Option<Local<'jvm, Object>>>
+where
+    S: JavaScalar,
+    R: ToJavaScalar<S>,
+{
+    let result = result.to_java_scalar(jvm)?.to_boxed(jvm)?;
+    Ok(Some(result.upcast()))
+}
+
+/// Converts the result of a Rust method implementing a `void` Java method.
+pub fn void_result<'jvm, R>(
+    jvm: &mut Jvm<'jvm>,
+    result: R,
+) -> crate::Result<'jvm, Option<Local<'jvm, Object>>>
+where
+    R: ToJavaScalar<()>,
+{
+    result.to_java_scalar(jvm)?;
+    Ok(None)
+}
+
+/// Throws an `UnsupportedOperationException` for a method that the Rust value does not implement.
+pub fn unsupported_method<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    method: &str,
+) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+    let message = format!("`{method}` is not implemented in Rust");
+    let exception = java::lang::UnsupportedOperationException::new(&message).execute_with(jvm)?;
+    jvm.throw(&exception)
+}
+
+#[java_function(duchess_rs.RustInvocationHandler::implemented)]
+fn implemented(handler: i64, method: String) -> bool {
+    // SAFETY: as for `call`
+    let handler = unsafe { &*(handler as *const Handler) };
+    handler.methods.contains(&method.as_str())
+}
+
+#[java_function(duchess_rs.RustInvocationHandler::call)]
+fn call<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    handler: i64,
+    method: String,
+    args: &java::Array<Object>,
+) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+    // SAFETY: `handler` was created by `to_java_impl` and is only freed once the Java handler
+    // has been garbage collected, which cannot happen while it is invoking the Rust value.
+    let handler = unsafe { &*(handler as *const Handler) };
+    (handler.invoke)(jvm, &method, args)
+}
+
+#[java_function(duchess_rs.RustInvocationHandler::drop)]
+fn drop_handler(handler: i64) {
+    // SAFETY: `handler` was created by `to_java_impl`, and Java drops it exactly once
+    drop(unsafe { Box::from_raw(handler as *mut Handler) });
+}
+
+/// The native methods of `duchess_rs.RustInvocationHandler`, registered when the bundled classes are defined.
+pub(crate) fn native_methods() -> Vec<JavaFunction> {
+    vec![
+        implemented::java_fn(),
+        call::java_fn(),
+        drop_handler::java_fn(),
+    ]
+}
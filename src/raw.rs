@@ -5,7 +5,7 @@
 //! The `'static` pointers generally rely on duchess not deinitializing a JVM after it's already initialized.
 
 use std::{
-    ffi::{self},
+    ffi::{self, CStr, CString},
     marker::PhantomData,
     mem::MaybeUninit,
     ptr::{self, NonNull},
@@ -13,7 +13,7 @@ use std::{
 
 use jni_sys::jvalue;
 
-use crate::{jvm::JavaObjectExt, Error, GlobalResult, JavaObject, Local};
+use crate::{java, jvm::JavaObjectExt, Error, GlobalResult, JavaObject, Local, Thrown};
 
 const VERSION: jni_sys::jint = jni_sys::JNI_VERSION_1_8;
 
@@ -314,13 +314,141 @@ impl<'jvm> EnvPtr<'jvm> {
         }
     }
 
+    /// Defines a class from raw class file bytes using `loader`, or else the bootstrap class loader (which makes it
+    /// visible to every other class loader).
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must contain a valid class file for the class named `jni_name`.
+    pub(crate) unsafe fn define_class(
+        self,
+        jni_name: &CStr,
+        loader: Option<&java::lang::ClassLoader>,
+        bytes: &[u8],
+    ) -> crate::Result<'jvm, Local<'jvm, java::lang::Class>> {
+        let class: Option<Local<'jvm, java::lang::Class>> = self.invoke(
+            |env| env.DefineClass,
+            |env, f| {
+                f(
+                    env,
+                    jni_name.as_ptr(),
+                    // `null` is the bootstrap class loader
+                    loader.map_or(ptr::null_mut(), |loader| loader.as_raw().as_ptr()),
+                    bytes.as_ptr() as *const jni_sys::jbyte,
+                    bytes.len() as jni_sys::jsize,
+                )
+            },
+        )?;
+        class.ok_or_else(|| {
+            Error::JvmInternal(format!(
+                "failed to define class `{}`",
+                jni_name.to_string_lossy()
+            ))
+        })
+    }
+
+    /// Makes `exception` the pending exception of this thread, to be thrown once control returns to the JVM.
+    ///
+    /// # Safety
+    ///
+    /// `exception` must point to an instance of `java.lang.Throwable`.
+    pub(crate) unsafe fn throw(self, exception: ObjectPtr) -> crate::Result<'jvm, ()> {
+        let result = self.invoke_unchecked(|env| env.Throw, |env, f| f(env, exception.as_ptr()));
+        if result == jni_sys::JNI_OK {
+            Ok(())
+        } else {
+            Err(Error::JvmInternal(format!(
+                "Throw failed with code `{result}`"
+            )))
+        }
+    }
+
+    /// Constructs a new instance of the exception class `class` with the given message and makes it the pending
+    /// exception of this thread, to be thrown once control returns to the JVM.
+    ///
+    /// # Safety
+    ///
+    /// `class` must point to a subclass of `java.lang.Throwable` that has a constructor taking a single `String`.
+    pub(crate) unsafe fn throw_new(
+        self,
+        class: ObjectPtr,
+        message: &str,
+    ) -> crate::Result<'jvm, ()> {
+        let encoded = cesu8::to_java_cesu8(message);
+        // SAFETY: cesu8 encodes interior nul bytes as 0xC080
+        let message = CString::from_vec_unchecked(encoded.into_owned());
+        let result = self.invoke_unchecked(
+            |env| env.ThrowNew,
+            |env, f| f(env, class.as_ptr(), message.as_ptr()),
+        );
+        if result == jni_sys::JNI_OK {
+            Ok(())
+        } else {
+            // The constructor itself may have thrown (e.g., `OutOfMemoryError`)
+            self.check_exception()?;
+            Err(Error::JvmInternal(format!(
+                "ThrowNew failed with code `{result}`"
+            )))
+        }
+    }
+
+    /// Ensures that at least `capacity` more local references can be created in the current local frame.
+    pub(crate) fn ensure_local_capacity(self, capacity: i32) -> crate::Result<'jvm, ()> {
+        // SAFETY: we don't hold on to the env ptr
+        let result =
+            unsafe { self.invoke_unchecked(|env| env.EnsureLocalCapacity, |env, f| f(env, capacity)) };
+        if result == jni_sys::JNI_OK {
+            Ok(())
+        } else {
+            // Throws `OutOfMemoryError` on failure
+            self.check_exception()?;
+            Err(Error::JvmInternal(format!(
+                "EnsureLocalCapacity failed with code `{result}`"
+            )))
+        }
+    }
+
+    /// Creates a new local frame with room for at least `capacity` local references.
+    ///
+    /// # Safety
+    ///
+    /// The frame must be popped by a matching call to [`Self::pop_local_frame()`][] once all local references created
+    /// in it are no longer used.
+    pub(crate) unsafe fn push_local_frame(self, capacity: i32) -> crate::Result<'jvm, ()> {
+        let result = self.invoke_unchecked(|env| env.PushLocalFrame, |env, f| f(env, capacity));
+        if result == jni_sys::JNI_OK {
+            Ok(())
+        } else {
+            // Throws `OutOfMemoryError` on failure
+            self.check_exception()?;
+            Err(Error::JvmInternal(format!(
+                "PushLocalFrame failed with code `{result}`"
+            )))
+        }
+    }
+
+    /// Pops the innermost local frame, freeing all local references created in it. If `result` is given, a new local
+    /// reference to it is created in the enclosing frame and returned.
+    ///
+    /// # Safety
+    ///
+    /// Must be paired with a prior call to [`Self::push_local_frame()`][]. No local reference created in the popped
+    /// frame (other than `result`, which is consumed) may be used afterwards.
+    pub(crate) unsafe fn pop_local_frame(self, result: Option<ObjectPtr>) -> Option<ObjectPtr> {
+        let result = result.map_or(ptr::null_mut(), ObjectPtr::as_ptr);
+        ObjectPtr::new(self.invoke_unchecked(|env| env.PopLocalFrame, |env, f| f(env, result)))
+    }
+
     pub fn check_exception(self) -> crate::Result<'jvm, ()> {
         // SAFETY: we don't hold on to the return env ptr
         let thrown = unsafe { self.invoke_unchecked(|env| env.ExceptionOccurred, |env, f| f(env)) };
         if let Some(thrown) = ObjectPtr::new(thrown) {
             unsafe { self.invoke_unchecked(|env| env.ExceptionClear, |env, f| f(env)) };
             // SAFETY: the ptr returned by ExceptionOccurred is already a local ref and must be an instance of Throwable
-            Err(Error::Thrown(unsafe { Local::from_raw(self, thrown) }))
+            let exception: Local<'jvm, java::lang::Throwable> =
+                unsafe { Local::from_raw(self, thrown) };
+            let snapshot = crate::snapshot::capture(self, &exception);
+            Err(Error::Thrown(Thrown::with_snapshot(exception, snapshot)))
         } else {
             Ok(())
         }
@@ -54,6 +54,16 @@ impl<'jvm, T: JavaObject> Local<'jvm, T> {
         std::mem::forget(self);
         p
     }
+
+    /// Reinterprets this reference as a reference to a `U`, without creating a new local reference.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the referenced object is an instance of `U` (or its subclasses).
+    pub(crate) unsafe fn cast<U: JavaObject>(self) -> Local<'jvm, U> {
+        let env = self.env;
+        Local::from_raw(env, self.into_raw())
+    }
 }
 
 impl<T: JavaObject> Drop for Local<'_, T> {
@@ -97,6 +107,17 @@ impl<T: JavaObject> Global<T> {
         }
     }
 
+    /// Reinterprets this reference as a reference to a `U`, without creating a new global reference.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the referenced object is an instance of `U` (or its subclasses).
+    pub(crate) unsafe fn cast<U: JavaObject>(self) -> Global<U> {
+        let obj = self.obj;
+        std::mem::forget(self);
+        Global::from_raw(obj)
+    }
+
     /// Creates a *new* global reference to `obj` in the current frame via a `NewGlobalRef` JNI call.
     pub(crate) fn new(env: EnvPtr<'_>, obj: &T) -> Self {
         // SAFETY: The JavaObject trait contract ensures that &T points to a Java object that is an instance of T.
@@ -171,6 +192,8 @@ impl<'a, R: JavaObject> Local<'a, R> {
     {
         // SAFETY: From the Upcast trait contract, we know R is also an instance of S
         let upcast = unsafe { Local::<S>::from_raw(self.env, self.obj) };
+        // The reference is now owned by `upcast`
+        std::mem::forget(self);
         upcast
     }
 }
@@ -194,6 +217,8 @@ impl<R: JavaObject> Global<R> {
     {
         // SAFETY: From the Upcast trait contract, we know R is also an instance of S
         let upcast = unsafe { Global::<S>::from_raw(self.obj) };
+        // The reference is now owned by `upcast`
+        std::mem::forget(self);
         upcast
     }
 }
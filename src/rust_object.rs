@@ -0,0 +1,444 @@
+//! Hands Rust values to Java as instances of peer classes, which are generated at runtime and extend
+//! `duchess_rs.RustObject`.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    sync::{Arc, RwLock},
+};
+
+use jni_sys::jvalue;
+
+// Make current crate available as `duchess` for use by the generated code.
+// NB. in doctests, the current crate is already available as duchess.
+#[cfg(not(doctest))]
+use crate as duchess;
+
+use crate::{
+    bundled::{define_bundled_classes, duchess_rs},
+    cast::Upcast,
+    class_file::{opcodes::*, ClassWriter, Code, ACC_FINAL, ACC_PUBLIC, ACC_SUPER, ACC_SYNTHETIC},
+    find::{find_constructor, find_field},
+    java::{
+        self,
+        lang::{Class, IllegalArgumentException, IllegalStateException},
+    },
+    java_function,
+    jvm::{new_exception, JavaObjectExt},
+    link::{JavaFn, JavaFunction},
+    plumbing::{FieldPtr, MethodPtr, ToJavaImpl},
+    subclass::{define_native_class, generated_class_name, jni_name_and_loader},
+    Error, Global, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// A Rust value that can be converted (with [`to_java`](crate::ToJava::to_java)) into a Java object
+/// implementing the interface `I`, for any `I` such that the value implements [`JavaPeer<I>`].
+/// The Java object is an instance of a peer class whose `native` methods call the methods of the
+/// Rust value, which may take `&self` or `&mut self`.
+///
+/// The Java object also implements `java.lang.AutoCloseable`: its `close` method drops the Rust value,
+/// after which calls to its methods throw an `IllegalStateException`. Otherwise, the value is dropped
+/// once the Java object has been garbage collected.
+///
+/// ```rust,ignore
+/// #[duchess::java_peer(java.util.Iterator)]
+/// impl java::util::Iterator<java::lang::String> for Countdown {
+///     fn has_next(&self) -> bool {
+///         self.0 > 0
+///     }
+///
+///     fn next(&mut self) -> String {
+///         self.0 -= 1;
+///         self.0.to_string()
+///     }
+/// }
+///
+/// let iterator = RustObject::new(Countdown(3)).to_java::<java::util::Iterator<java::lang::String>>();
+/// ```
+#[derive_where::derive_where(Clone)]
+pub struct RustObject<T> {
+    value: Arc<Cell<T>>,
+}
+
+/// `None` once the Java object has been closed.
+type Cell<T> = RwLock<Option<T>>;
+
+impl<T> RustObject<T> {
+    pub fn new(value: T) -> Self {
+        RustObject {
+            value: Arc::new(RwLock::new(Some(value))),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> RustObject<T> {
+    /// Invokes `op` with the Rust value of `object`, a peer created by converting a `RustObject<T>` to Java.
+    ///
+    /// Waits while a `&mut self` method of the object runs on another thread. Returns an error (thrown as an
+    /// `IllegalStateException`) if the object has been closed or a `&mut self` method of it is running on the
+    /// current thread, and one thrown as an `IllegalArgumentException` if `object` is not a peer of `T`.
+    pub fn with<'jvm, I, R>(
+        jvm: &mut Jvm<'jvm>,
+        object: &I,
+        op: impl FnOnce(&mut Jvm<'jvm>, &T) -> R,
+    ) -> crate::Result<'jvm, R>
+    where
+        T: JavaPeer<I>,
+        I: JavaObject,
+    {
+        let cell = Self::cell(jvm, object)?;
+        match borrow_on_current_thread(&cell) {
+            // The enclosing call holds the read lock, which cannot be taken again on this thread without
+            // risking a deadlock, but which keeps the value alive and unchanged until this call returns
+            // SAFETY: the pointer was taken from the value of `cell`, a `T`
+            Some(Some(value)) => return Ok(op(jvm, unsafe { &*value.cast::<T>() })),
+            Some(None) => {
+                return Err(new_exception::<IllegalStateException>(
+                    jvm,
+                    "Rust object is being modified",
+                ))
+            }
+            None => {}
+        }
+        // A panic in a `&mut self` method does not make the value unusable
+        let guard = cell.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match &*guard {
+            Some(value) => {
+                let _borrow = Borrow::new(&cell, Some(value as *const T as *const ()));
+                Ok(op(jvm, value))
+            }
+            None => Err(new_exception::<IllegalStateException>(
+                jvm,
+                "Rust object has been closed",
+            )),
+        }
+    }
+
+    /// Like [`with`](RustObject::with), but gives `op` a mutable reference to the Rust value. Waits while any
+    /// other method of the object runs on another thread, and returns an error if one is running on the current
+    /// thread.
+    pub fn with_mut<'jvm, I, R>(
+        jvm: &mut Jvm<'jvm>,
+        object: &I,
+        op: impl FnOnce(&mut Jvm<'jvm>, &mut T) -> R,
+    ) -> crate::Result<'jvm, R>
+    where
+        T: JavaPeer<I>,
+        I: JavaObject,
+    {
+        let cell = Self::cell(jvm, object)?;
+        if borrow_on_current_thread(&cell).is_some() {
+            return Err(new_exception::<IllegalStateException>(
+                jvm,
+                "Rust object is already in use",
+            ));
+        }
+        let mut guard = cell
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match &mut *guard {
+            Some(value) => {
+                let _borrow = Borrow::new(&cell, None);
+                Ok(op(jvm, value))
+            }
+            None => Err(new_exception::<IllegalStateException>(
+                jvm,
+                "Rust object has been closed",
+            )),
+        }
+    }
+
+    fn cell<'jvm, I>(jvm: &mut Jvm<'jvm>, object: &I) -> crate::Result<'jvm, Arc<Cell<T>>>
+    where
+        T: JavaPeer<I>,
+        I: JavaObject,
+    {
+        let peer = T::peer_class(jvm)?;
+        let env = jvm.env();
+        // SAFETY: both are valid references
+        let is_peer = unsafe {
+            env.invoke_unchecked(
+                |env| env.IsInstanceOf,
+                |env, f| f(env, object.as_raw().as_ptr(), peer.class.as_raw().as_ptr()),
+            )
+        } == jni_sys::JNI_TRUE;
+        if !is_peer {
+            return Err(new_exception::<IllegalArgumentException>(
+                jvm,
+                &format!("not a Java peer of `{}`", std::any::type_name::<T>()),
+            ));
+        }
+
+        // SAFETY: `object` is a peer, so `handle` is a `long` field
+        let handle = unsafe {
+            env.invoke_unchecked(
+                |env| env.GetLongField,
+                |env, f| f(env, object.as_raw().as_ptr(), peer.handle.as_ptr()),
+            )
+        };
+        // SAFETY: the handle of a peer of `T` points to the `Cell<T>` it was created with. It is only freed once
+        // the peer is unreachable, which it is not while we hold a reference to it.
+        let handle = unsafe { &*(handle as *const Handle) };
+        Ok(handle
+            .clone()
+            .into_any()
+            .downcast::<Cell<T>>()
+            .expect("peer class of `T` holds a different type"))
+    }
+}
+
+/// Rust types whose values are handed to Java as instances of a peer class implementing `I`. Usually
+/// implemented with the [`java_peer`](crate::java_peer) attribute.
+///
+/// # Safety
+///
+/// The peer class must be defined with [`define_peer_class::<I>`](define_peer_class), and its native methods must
+/// expect a peer of `Self` as `this`.
+pub unsafe trait JavaPeer<I>: Send + Sync + 'static {
+    /// Returns the peer class, defining it the first time it is needed.
+    fn peer_class<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, &'static PeerClass>;
+}
+
+thread_local! {
+    /// The peers whose methods are running on the current thread, innermost last: the address of each cell,
+    /// with that of its value if the method takes `&self` (or `None` if it takes `&mut self`). A nested call on
+    /// the same object must not wait for the lock, since it would wait for the current thread.
+    static BORROWS: RefCell<Vec<(*const (), Option<*const ()>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns how `cell` is borrowed by a method running on the current thread, if it is.
+fn borrow_on_current_thread<T>(cell: &Cell<T>) -> Option<Option<*const ()>> {
+    let address = cell as *const Cell<T> as *const ();
+    BORROWS.with(|borrows| {
+        borrows
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(cell, _)| *cell == address)
+            .map(|&(_, value)| value)
+    })
+}
+
+/// Records a borrow of a cell by the current thread until dropped.
+struct Borrow {
+    cell: *const (),
+}
+
+impl Borrow {
+    fn new<T>(cell: &Cell<T>, value: Option<*const ()>) -> Self {
+        let cell = cell as *const Cell<T> as *const ();
+        BORROWS.with(|borrows| borrows.borrow_mut().push((cell, value)));
+        Borrow { cell }
+    }
+}
+
+impl Drop for Borrow {
+    fn drop(&mut self) {
+        BORROWS.with(|borrows| {
+            let mut borrows = borrows.borrow_mut();
+            let index = borrows
+                .iter()
+                .rposition(|(cell, _)| *cell == self.cell)
+                .expect("borrow was recorded");
+            borrows.remove(index);
+        });
+    }
+}
+
+/// A peer class that has been defined into the JVM.
+pub struct PeerClass {
+    class: Global<Class>,
+    constructor: MethodPtr,
+    handle: FieldPtr,
+}
+
+/// The type-erased Rust value that the `handle` of a `duchess_rs.RustObject` points to.
+type Handle = Arc<dyn Peer>;
+
+trait Peer: Send + Sync {
+    /// Drops the Rust value once the methods running on other threads have returned, unless a method is running
+    /// on the current thread; returns `false` in that case.
+    fn close(&self) -> bool;
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl<T: Send + Sync + 'static> Peer for Cell<T> {
+    fn close(&self) -> bool {
+        if borrow_on_current_thread(self).is_some() {
+            return false;
+        }
+        let mut guard = self
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *guard = None;
+        true
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+impl<I, T> ToJavaImpl<I> for RustObject<T>
+where
+    I: Upcast<java::lang::Object>,
+    T: JavaPeer<I>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, I>>> {
+        let peer = T::peer_class(jvm)?;
+        let handle: *mut Handle = Box::into_raw(Box::new(rust.value.clone()));
+        let object: crate::Result<'jvm, Option<Local<'jvm, I>>> = unsafe {
+            jvm.env().invoke(
+                |env| env.NewObjectA,
+                |env, f| {
+                    f(
+                        env,
+                        peer.class.as_raw().as_ptr(),
+                        peer.constructor.as_ptr(),
+                        [jvalue { j: handle as i64 }].as_ptr(),
+                    )
+                },
+            )
+        };
+        match object {
+            Ok(Some(object)) => Ok(Some(object)),
+            result => {
+                // SAFETY: the Java object wasn't created, so we still own `handle`
+                drop(unsafe { Box::from_raw(handle) });
+                match result {
+                    Err(err) => Err(err),
+                    _ => Err(Error::JvmInternal(
+                        "failed to instantiate Rust object".to_string(),
+                    )),
+                }
+            }
+        }
+    }
+}
+
+/// Defines a peer class that extends `duchess_rs.RustObject` and implements `I` with the native `methods`.
+/// The class and native methods of `methods` are ignored; instead, they are registered on the peer class.
+pub fn define_peer_class<'jvm, I: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    methods: Vec<JavaFunction>,
+) -> crate::Result<'jvm, PeerClass> {
+    define_bundled_classes(jvm)?;
+
+    const SUPER_NAME: &str = "duchess_rs/RustObject";
+    let name = generated_class_name("RustObject");
+    let mut writer = ClassWriter::new(
+        ACC_PUBLIC | ACC_FINAL | ACC_SUPER | ACC_SYNTHETIC,
+        &name,
+        SUPER_NAME,
+    );
+
+    // The peer class must see both `I` and `duchess_rs.RustObject`. If `I` comes from the bootstrap class loader
+    // (e.g., `java.util.Iterator`), it is visible from any loader, but the bundled classes may not be (e.g., if they
+    // are on the classpath).
+    let interface = I::class(jvm)?;
+    let rust_object = duchess_rs::RustObject::class(jvm)?;
+    let (interface_name, loader) = match jni_name_and_loader(jvm, &interface)? {
+        (name, None) => (name, rust_object.get_class_loader().execute_with(jvm)?),
+        name_and_loader => name_and_loader,
+    };
+
+    // `I` is either an interface or a superclass of `duchess_rs.RustObject` (e.g., `java.lang.Object`)
+    if interface.is_interface().execute_with(jvm)? {
+        writer.add_interface(&interface_name);
+    } else {
+        // SAFETY: both are valid references to classes
+        let is_superclass = unsafe {
+            jvm.env().invoke_unchecked(
+                |env| env.IsAssignableFrom,
+                |env, f| {
+                    f(
+                        env,
+                        rust_object.as_raw().as_ptr(),
+                        interface.as_raw().as_ptr(),
+                    )
+                },
+            )
+        } == jni_sys::JNI_TRUE;
+        if !is_superclass {
+            return Err(Error::JvmInternal(format!(
+                "Rust objects cannot extend `{}`",
+                interface_name.replace('/', ".")
+            )));
+        }
+    }
+
+    // public RustObjectN(long handle) { super(handle); }
+    let super_init = writer.method_ref(SUPER_NAME, "<init>", "(J)V");
+    let [super_init_hi, super_init_lo] = super_init.to_be_bytes();
+    writer.add_method(
+        ACC_PUBLIC,
+        "<init>",
+        "(J)V",
+        Some(Code {
+            max_stack: 3,
+            max_locals: 3,
+            bytecode: vec![
+                ALOAD_0,
+                LLOAD_1,
+                INVOKESPECIAL,
+                super_init_hi,
+                super_init_lo,
+                RETURN,
+            ],
+        }),
+    );
+
+    let natives: Vec<_> = methods.iter().map(|method| (ACC_PUBLIC, method)).collect();
+    // SAFETY: the native functions expect instances of the peer class
+    let class = unsafe { define_native_class(jvm, &name, writer, loader.as_deref(), &natives) }?;
+
+    let constructor = find_constructor(jvm, &class, c"(J)V")?;
+    let handle = find_field(jvm, &class, c"handle", c"J", false)?;
+    Ok(PeerClass {
+        class: jvm.global(&class),
+        constructor,
+        handle,
+    })
+}
+
+/// Drops `value` once `object` has been garbage collected, like the value of a peer. This is for Java objects
+/// that depend on Rust values but are not peers (e.g., a direct `java.nio.ByteBuffer` viewing Rust memory). If that
+/// cannot be arranged, `value` is leaked, since `object` may still depend on it.
+pub(crate) fn drop_when_collected<'jvm, T: Send + Sync + 'static>(
+    jvm: &mut Jvm<'jvm>,
+    object: &java::lang::Object,
+    value: T,
+) -> crate::Result<'jvm, ()> {
+    let value: Handle = Arc::new(RwLock::new(Some(value)));
+    let handle: *mut Handle = Box::into_raw(Box::new(value));
+    duchess_rs::RustObjectCleaner::register(object, handle as i64).execute_with(jvm)
+}
+
+#[java_function(duchess_rs.RustObject::release)]
+fn release<'jvm>(jvm: &mut Jvm<'jvm>, handle: i64) -> crate::Result<'jvm, ()> {
+    // SAFETY: `handle` was created by `to_java_impl` and is only freed once the Java object is unreachable
+    let handle = unsafe { &*(handle as *const Handle) };
+    if handle.close() {
+        Ok(())
+    } else {
+        Err(new_exception::<IllegalStateException>(
+            jvm,
+            "cannot close a Rust object while it is in use",
+        ))
+    }
+}
+
+#[java_function(duchess_rs.RustObject::drop)]
+fn drop_handle(handle: i64) {
+    // SAFETY: `handle` was created by `to_java_impl`, and Java drops it exactly once
+    drop(unsafe { Box::from_raw(handle as *mut Handle) });
+}
+
+/// The native methods of `duchess_rs.RustObject`, registered when the bundled classes are defined.
+pub(crate) fn native_methods() -> Vec<JavaFunction> {
+    vec![release::java_fn(), drop_handle::java_fn()]
+}
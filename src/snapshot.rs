@@ -0,0 +1,245 @@
+//! Snapshots of Java exceptions, taken when duchess catches them, so that they can be reported (e.g. by formatting an
+//! [`Error`](crate::Error)) without calling back into the JVM.
+
+use std::{
+    cell::Cell,
+    fmt::{self, Display},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    java::{lang::Throwable, ArrayExt},
+    jvm::JavaObjectExt,
+    raw::{EnvPtr, ObjectPtr},
+    to_java::in_element_frame,
+    Error, Jvm, JvmOp, Local,
+};
+
+/// Whether exceptions are snapshotted when they are caught, see [`JvmBuilder::snapshot_exceptions`](crate::jvm::JvmBuilder::snapshot_exceptions).
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+thread_local! {
+    /// Set while a snapshot is being taken on this thread. Taking a snapshot calls Java methods, which may throw
+    /// exceptions of their own; those are caught without a snapshot.
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+}
+
+pub(crate) fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// The details of a Java exception, copied out of the JVM when duchess caught it:
+/// its class, message and stack trace, along with those of its suppressed exceptions and its causes.
+///
+/// `Display` prints the exception like Java's `Throwable.toString()` (e.g. `java.io.IOException: disk full`);
+/// the alternate form (`{:#}`) prints the whole stack trace like `Throwable.printStackTrace()`.
+/// [`source`](std::error::Error::source) is the snapshot of the cause (`getCause()`), so that error reporters such as
+/// `anyhow` print the chain of Java causes.
+#[derive(Clone, Debug)]
+pub struct ExceptionSnapshot {
+    class_name: String,
+    message: Option<String>,
+    description: String,
+    stack_trace: Vec<String>,
+    suppressed: Vec<ExceptionSnapshot>,
+    cause: Option<Box<ExceptionSnapshot>>,
+}
+
+impl ExceptionSnapshot {
+    /// The binary name of the exception's class, e.g. `java.lang.IllegalStateException`.
+    pub fn class_name(&self) -> &str {
+        &self.class_name
+    }
+
+    /// The result of `getMessage()`.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// The frames of the stack trace (from `getStackTrace()`), innermost first, each formatted like
+    /// `StackTraceElement.toString()`.
+    pub fn stack_trace(&self) -> &[String] {
+        &self.stack_trace
+    }
+
+    /// The exceptions that were suppressed in order to deliver this one (from `getSuppressed()`).
+    pub fn suppressed(&self) -> &[ExceptionSnapshot] {
+        &self.suppressed
+    }
+
+    /// The cause of the exception (from `getCause()`). A cause that is already part of the chain (which Java permits)
+    /// ends the chain.
+    pub fn cause(&self) -> Option<&ExceptionSnapshot> {
+        self.cause.as_deref()
+    }
+
+    /// Writes the stack trace in the format of `Throwable.printStackTrace()`, with every line after the first
+    /// prefixed by `indent`.
+    fn fmt_stack_trace(&self, f: &mut fmt::Formatter<'_>, indent: &str) -> fmt::Result {
+        write!(f, "{}", self.description)?;
+        for frame in &self.stack_trace {
+            write!(f, "\n{indent}\tat {frame}")?;
+        }
+        for suppressed in &self.suppressed {
+            write!(f, "\n{indent}\tSuppressed: ")?;
+            suppressed.fmt_stack_trace(f, &format!("{indent}\t"))?;
+        }
+        if let Some(cause) = &self.cause {
+            write!(f, "\n{indent}Caused by: ")?;
+            cause.fmt_stack_trace(f, indent)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ExceptionSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            self.fmt_stack_trace(f, "")
+        } else {
+            write!(f, "{}", self.description)
+        }
+    }
+}
+
+impl std::error::Error for ExceptionSnapshot {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause().map(|cause| cause as _)
+    }
+}
+
+/// Takes a snapshot of `exception`, which has just been caught, unless snapshots are disabled or one is already being
+/// taken on this thread. Returns `None` if any of the Java methods involved throws.
+pub(crate) fn capture(env: EnvPtr<'_>, exception: &Throwable) -> Option<Arc<ExceptionSnapshot>> {
+    if !ENABLED.load(Ordering::Relaxed) || CAPTURING.with(|capturing| capturing.replace(true)) {
+        return None;
+    }
+
+    struct ResetOnDrop;
+    impl Drop for ResetOnDrop {
+        fn drop(&mut self) {
+            CAPTURING.with(|capturing| capturing.set(false));
+        }
+    }
+    let _reset = ResetOnDrop;
+
+    snapshot(&mut Jvm::from_env(env), exception, &mut vec![])
+        .ok()
+        .map(Arc::new)
+}
+
+/// Snapshots `exception` (in its own local frame). `chain` holds the exceptions whose snapshots are being taken by
+/// the callers, which are skipped to cut cycles.
+fn snapshot<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    exception: &Throwable,
+    chain: &mut Vec<ObjectPtr>,
+) -> crate::Result<'jvm, ExceptionSnapshot> {
+    let mut snapshot = None;
+    in_element_frame(jvm, |jvm| {
+        let class_name = class_name(jvm, exception)?;
+        let message: Option<String> = exception.get_message().to_rust().execute_with(jvm)?;
+        let description: String = exception
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let stack_trace = stack_trace(jvm, exception)?;
+
+        chain.push(exception.as_raw());
+        let suppressed_exceptions = exception
+            .get_suppressed()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let mut suppressed = vec![];
+        for index in 0..(&suppressed_exceptions).length().execute_with(jvm)? {
+            let exception = (&suppressed_exceptions)
+                .get(index)
+                .assert_not_null()
+                .execute_with(jvm)?;
+            if !in_chain(jvm, &exception, chain) {
+                suppressed.push(self::snapshot(jvm, &exception, chain)?);
+            }
+        }
+        let cause = match exception.get_cause().execute_with(jvm)? {
+            Some(cause) if !in_chain(jvm, &cause, chain) => {
+                Some(Box::new(self::snapshot(jvm, &cause, chain)?))
+            }
+            _ => None,
+        };
+        chain.pop();
+
+        snapshot = Some(ExceptionSnapshot {
+            class_name,
+            message,
+            description,
+            stack_trace,
+            suppressed,
+            cause,
+        });
+        Ok(())
+    })?;
+    Ok(snapshot.expect("exception was snapshotted"))
+}
+
+/// Returns the name of the class of `exception`.
+fn class_name<'jvm>(jvm: &mut Jvm<'jvm>, exception: &Throwable) -> crate::Result<'jvm, String> {
+    let env = jvm.env();
+    // SAFETY: `GetObjectClass` returns a new local reference to the object's class and cannot throw
+    let class = unsafe {
+        let class = env.invoke_unchecked(
+            |env| env.GetObjectClass,
+            |env, f| f(env, exception.as_raw().as_ptr()),
+        );
+        let class = ObjectPtr::new(class).ok_or(Error::NullDeref)?;
+        Local::<crate::java::lang::Class>::from_raw(env, class)
+    };
+    class
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)
+}
+
+/// Returns the frames of the stack trace of `exception`, each converted in its own local frame.
+fn stack_trace<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    exception: &Throwable,
+) -> crate::Result<'jvm, Vec<String>> {
+    let elements = exception
+        .get_stack_trace()
+        .assert_not_null()
+        .execute_with(jvm)?;
+    let length = (&elements).length().execute_with(jvm)?;
+    let mut frames = Vec::with_capacity(length as usize);
+    for index in 0..length {
+        in_element_frame(jvm, |jvm| {
+            let element = (&elements).get(index).assert_not_null().execute_with(jvm)?;
+            let frame: String = element
+                .to_string()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            frames.push(frame);
+            Ok(())
+        })?;
+    }
+    Ok(frames)
+}
+
+/// Tests whether `exception` is the same object as one of the exceptions in `chain`.
+fn in_chain(jvm: &mut Jvm<'_>, exception: &Throwable, chain: &[ObjectPtr]) -> bool {
+    let env = jvm.env();
+    chain.iter().any(|&other| {
+        // SAFETY: `IsSameObject` cannot throw
+        unsafe {
+            env.invoke_unchecked(
+                |env| env.IsSameObject,
+                |env, f| f(env, exception.as_raw().as_ptr(), other.as_ptr()),
+            ) == jni_sys::JNI_TRUE
+        }
+    })
+}
@@ -0,0 +1,289 @@
+//! Extends Java classes with Rust types, by generating subclasses at runtime whose methods are `native`.
+
+use std::{
+    ffi::{c_char, c_void, CString},
+    ops::Deref,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use jni_sys::jvalue;
+
+use crate::{
+    cast::Upcast,
+    class_file::{
+        opcodes::*, ClassWriter, Code, ACC_FINAL, ACC_NATIVE, ACC_PRIVATE, ACC_PUBLIC, ACC_SUPER,
+        ACC_SYNTHETIC,
+    },
+    find::{find_constructor, find_field},
+    java::{self, lang::Class},
+    jvm::JavaObjectExt,
+    link::JavaFunction,
+    plumbing::{FieldPtr, FromRef, MethodPtr, ToJavaImpl},
+    rust_object::drop_when_collected,
+    Error, Global, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// A Rust value that can be converted (with [`to_java`](crate::ToJava::to_java)) into an instance of
+/// a subclass of the Java class `C`, for any `C` such that the value implements [`JavaSubclass<C>`].
+/// Calls to the overridden methods of the Java object are forwarded to the Rust value.
+///
+/// The value is dropped once the Java object has been garbage collected.
+///
+/// ```rust,ignore
+/// #[duchess::java_subclass(java.io.InputStream)]
+/// impl java::io::InputStream for Zeroes {
+///     fn read(&self, _this: &java::io::InputStream) -> i32 {
+///         0
+///     }
+/// }
+///
+/// let zeroes = Subclass::new(Zeroes).to_java::<java::io::InputStream>();
+/// ```
+#[derive_where::derive_where(Clone)]
+pub struct Subclass<T> {
+    value: Arc<T>,
+}
+
+impl<T> Subclass<T> {
+    pub fn new(value: T) -> Self {
+        Subclass {
+            value: Arc::new(value),
+        }
+    }
+}
+
+/// Rust types that extend the Java class `C`. Implemented with the
+/// [`java_subclass`](crate::java_subclass) attribute.
+///
+/// # Safety
+///
+/// The native methods of the subclass must expect an instance of it as `this`, and find the Rust value with
+/// [`subclass_value::<Self, C>`](subclass_value).
+pub unsafe trait JavaSubclass<C>: Send + Sync + 'static {
+    /// Returns the subclass, defining it the first time it is needed.
+    fn subclass<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, &'static SubclassInfo>;
+}
+
+/// The view of an instance of the Java class `C` from within a Rust subclass, which can also call the
+/// protected methods of `C` that are listed in its [`java_package`](crate::java_package) declaration.
+/// Methods declared with the [`java_subclass`](crate::java_subclass) attribute receive it as `this`
+/// when they declare `this: &Protected<C>`; it dereferences to `C` for everything else.
+///
+/// ```rust,ignore
+/// #[duchess::java_subclass(native_subclass.Shape)]
+/// impl native_subclass::Shape for Square {
+///     fn kind(&self, this: &Protected<native_subclass::Shape>) -> duchess::GlobalResult<String> {
+///         let prefix: String = this.prefix().assert_not_null().to_rust().execute()?;
+///         Ok(format!("{prefix}square"))
+///     }
+/// }
+/// ```
+pub type Protected<C> = <C as ProtectedView>::Protected;
+
+/// Classes with a [`Protected`] view, i.e. those that Rust types can extend.
+/// Implemented by the [`java_package`](crate::java_package) macro for classes that are not `final`.
+pub trait ProtectedView: JavaObject {
+    type Protected: FromRef<Self> + Deref<Target = Self>;
+}
+
+/// A subclass that has been defined into the JVM.
+pub struct SubclassInfo {
+    class: Global<Class>,
+    constructor: MethodPtr,
+    field: FieldPtr,
+}
+
+impl<C, T> ToJavaImpl<C> for Subclass<T>
+where
+    C: Upcast<java::lang::Object>,
+    T: JavaSubclass<C>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, C>>> {
+        let subclass = T::subclass(jvm)?;
+        // The Java object borrows the Rust value, which is kept alive (by the `Arc` handed to
+        // `drop_when_collected`) until the object has been garbage collected
+        let value = Arc::as_ptr(&rust.value);
+        let object: Option<Local<'jvm, java::lang::Object>> = unsafe {
+            jvm.env().invoke(
+                |env| env.NewObjectA,
+                |env, f| {
+                    f(
+                        env,
+                        subclass.class.as_raw().as_ptr(),
+                        subclass.constructor.as_ptr(),
+                        [jvalue { j: value as i64 }].as_ptr(),
+                    )
+                },
+            )
+        }?;
+        let Some(object) = object else {
+            return Err(Error::JvmInternal(
+                "failed to instantiate Rust subclass".to_string(),
+            ));
+        };
+        drop_when_collected(jvm, &object, rust.value.clone())?;
+        // SAFETY: the object is an instance of the subclass of `C`
+        Ok(Some(unsafe { object.cast::<C>() }))
+    }
+}
+
+/// Defines a subclass of `C` whose `methods` are `native`. The class and native methods of `methods` are ignored;
+/// instead, they are registered on the subclass.
+pub fn define_subclass<'jvm, C: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    methods: Vec<JavaFunction>,
+) -> crate::Result<'jvm, SubclassInfo> {
+    let super_class = C::class(jvm)?;
+    let (super_name, loader) = jni_name_and_loader(jvm, &super_class)?;
+    let name = generated_class_name("Subclass");
+
+    let mut writer = ClassWriter::new(
+        ACC_PUBLIC | ACC_FINAL | ACC_SUPER | ACC_SYNTHETIC,
+        &name,
+        &super_name,
+    );
+    writer.add_field(ACC_PRIVATE, "rust", "J");
+
+    // public Subclass(long rust) { super(); this.rust = rust; }
+    let super_init = writer.method_ref(&super_name, "<init>", "()V");
+    let rust_field = writer.field_ref(&name, "rust", "J");
+    let [super_init_hi, super_init_lo] = super_init.to_be_bytes();
+    let [rust_field_hi, rust_field_lo] = rust_field.to_be_bytes();
+    writer.add_method(
+        ACC_PUBLIC,
+        "<init>",
+        "(J)V",
+        Some(Code {
+            max_stack: 3,
+            max_locals: 3,
+            bytecode: vec![
+                ALOAD_0,
+                INVOKESPECIAL,
+                super_init_hi,
+                super_init_lo,
+                ALOAD_0,
+                LLOAD_1,
+                PUTFIELD,
+                rust_field_hi,
+                rust_field_lo,
+                RETURN,
+            ],
+        }),
+    );
+
+    let natives: Vec<_> = methods.iter().map(|method| (ACC_PUBLIC, method)).collect();
+    // SAFETY: the native functions expect instances of the subclass
+    let class = unsafe { define_native_class(jvm, &name, writer, loader.as_deref(), &natives) }?;
+
+    let constructor = find_constructor(jvm, &class, c"(J)V")?;
+    let field = find_field(jvm, &class, c"rust", c"J", false)?;
+    Ok(SubclassInfo {
+        class: jvm.global(&class),
+        constructor,
+        field,
+    })
+}
+
+/// Returns the JNI name (e.g., `java/lang/Object`) and the class loader of `class`. Generated classes that extend
+/// or implement it are defined with the same loader, so that they can see the same classes.
+pub(crate) fn jni_name_and_loader<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    class: &Class,
+) -> crate::Result<'jvm, (String, Option<Local<'jvm, java::lang::ClassLoader>>)> {
+    let name: String = class
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+    let loader = class.get_class_loader().execute_with(jvm)?;
+    Ok((name.replace('.', "/"), loader))
+}
+
+/// Returns a fresh JNI name for a generated class.
+pub(crate) fn generated_class_name(prefix: &str) -> String {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    // Each definition gets a fresh name, so that several copies of duchess can share a JVM.
+    format!(
+        "duchess_rs/generated/{prefix}{}",
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Declares `natives` (with the given access flags) as the `native` methods of the class that `writer` describes,
+/// then defines the class with `loader` and registers the native functions. The class recorded in each
+/// [`JavaFunction`] is ignored.
+///
+/// # Safety
+///
+/// The native functions must expect an instance of the class as `this`, and match the signatures they declare.
+pub(crate) unsafe fn define_native_class<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    name: &str,
+    mut writer: ClassWriter,
+    loader: Option<&java::lang::ClassLoader>,
+    natives: &[(u16, &JavaFunction)],
+) -> crate::Result<'jvm, Local<'jvm, Class>> {
+    for (access_flags, method) in natives {
+        writer.add_method(
+            access_flags | ACC_NATIVE,
+            method.name.to_str().unwrap(),
+            method.signature.to_str().unwrap(),
+            None,
+        );
+    }
+
+    let jni_name = CString::new(name).unwrap();
+    // SAFETY: `ClassWriter` produces a valid class file
+    let class = jvm
+        .env()
+        .define_class(&jni_name, loader, &writer.into_bytes())?;
+
+    let native_methods: Vec<_> = natives
+        .iter()
+        .map(|(_, method)| jni_sys::JNINativeMethod {
+            name: method.name.as_ptr() as *mut c_char,
+            signature: method.signature.as_ptr() as *mut c_char,
+            fnPtr: method.pointer.as_ptr() as *mut c_void,
+        })
+        .collect();
+    // SAFETY: the native methods were declared above, with the same signatures
+    jvm.env()
+        .register_native_methods(class.as_raw(), &native_methods)?;
+
+    Ok(class)
+}
+
+/// Returns the Rust value that the instance `this` of the subclass `T::subclass` was created with.
+///
+/// # Safety
+///
+/// `this` must be an instance of `T::subclass`.
+pub unsafe fn subclass_value<'a, 'jvm, T, C>(
+    jvm: &mut Jvm<'jvm>,
+    this: &'a C,
+) -> crate::Result<'jvm, &'a T>
+where
+    T: JavaSubclass<C>,
+    C: JavaObject,
+{
+    let subclass = T::subclass(jvm)?;
+    let value = jvm.env().invoke_unchecked(
+        |env| env.GetLongField,
+        |env, f| f(env, this.as_raw().as_ptr(), subclass.field.as_ptr()),
+    );
+    if value == 0 {
+        // e.g., the constructor of the superclass called an overridden method
+        return Err(Error::JvmInternal(
+            "Rust subclass used before it was constructed".to_string(),
+        ));
+    }
+    // SAFETY: the Rust value is only dropped once `this` has been garbage collected
+    Ok(&*(value as *const T))
+}
@@ -1,51 +1,72 @@
-use std::cell::Cell;
+use std::cell::RefCell;
 
 use crate::{
     raw::{EnvPtr, JvmPtr},
-    Error, GlobalResult,
+    GlobalResult,
 };
 
-// XX: The current thread-local state will prevent duchess => java => duchess call stacks. We may want to relax this in
-// the future!
+// Each thread tracks a stack of the duchess frames that are currently active on it. A frame is pushed each time we
+// enter duchess code that needs the JNI environment -- a call to `Jvm::with` or a native function invoked by the JVM --
+// and popped when that code returns. This permits arbitrary duchess => java => duchess call stacks, as well as nested
+// `Jvm::with` calls: every frame on the stack shares the same (per-thread) JNI environment, and only the outermost
+// frame that attached the thread may detach it again.
 thread_local! {
-    static STATE: Cell<State> = Cell::new(State::Detached);
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Set if the JVM is attached to the current thread and duchess knows about it, either because the thread was
+    /// permanently attached or because there is at least one frame on the stack.
+    env: Option<EnvPtr<'static>>,
+
+    /// True if the thread should stay attached once the stack of frames becomes empty.
+    permanent: bool,
+
+    /// The active duchess frames, innermost last.
+    frames: Vec<Frame>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub enum State {
-    /// The JVM is attached to the current thread, but we're already inside a duchess frame.
-    InUse,
-    /// The JVM is permanently attached to the current thread, but we're not inside a duchess frame.
-    AttachedPermanently(EnvPtr<'static>),
-    /// Duchess thinks the JVM is detached, though JNI calls through other means could change this.
-    Detached,
+enum Frame {
+    /// Entered via `Jvm::with` (or another internal attach). If `detach` is true, this frame attached the thread and
+    /// must detach it when popped (unless the thread has since been permanently attached).
+    Attached { detach: bool },
+    /// Entered from the JVM invoking a native function.
+    JniCallback,
 }
 
-fn attached_or(
-    jvm: JvmPtr,
-    f: impl FnOnce() -> GlobalResult<AttachGuard>,
-) -> GlobalResult<AttachGuard> {
-    STATE.with(|state| match state.replace(State::InUse) {
-        State::AttachedPermanently(env) => Ok(AttachGuard {
-            jvm,
-            env,
-            permanent: true,
-        }),
-        State::InUse => Err(Error::NestedUsage),
-        State::Detached => {
-            let result = f();
-            if result.is_err() {
-                state.set(State::Detached);
-            }
-            result
+impl State {
+    fn push(&mut self, env: EnvPtr<'static>, frame: Frame) {
+        if let Some(old_env) = self.env {
+            assert_eq!(
+                old_env, env,
+                "JNI environment changed while thread was attached"
+            );
         }
-    })
+        self.env = Some(env);
+        self.frames.push(frame);
+    }
+
+    /// Pops the innermost frame, returning true if the thread should now be detached from the JVM.
+    fn pop(&mut self, expected: Frame) -> bool {
+        let frame = self.frames.pop();
+        assert_eq!(
+            frame.as_ref(),
+            Some(&expected),
+            "duchess frames popped out of order"
+        );
+
+        let detach = matches!(frame, Some(Frame::Attached { detach: true })) && !self.permanent;
+        if self.frames.is_empty() && !self.permanent {
+            self.env = None;
+        }
+        detach
+    }
 }
 
-/// Marks the current thread as attached until `detach_from_jni_callback` is called.
+/// Marks the current thread as attached until the returned guard is dropped.
 /// Intended for use within JNI calls of native functions.
-/// Returns the previous thread state, which should be given to `detach_from_jni_callback`
-/// as a parameter.
 ///
 /// # Safety condition
 ///
@@ -53,83 +74,91 @@ fn attached_or(
 /// which guarantees that the current thread is attached and will stay that way.
 ///
 /// Caller must drop the guard object that is returned before returning control to the JVM.
-#[must_use = "remember to call `detach_from_jni_callback`"]
+#[must_use = "the thread is only marked as attached while the guard is live"]
 pub unsafe fn attach_from_jni_callback(env: EnvPtr<'_>) -> JniCallbackGuard<'_> {
-    let old_state = STATE.with(|state| {
-        // Unsafe condition: `env` pointer returned from transmute will not
-        // live past the drop of the guard object that we return,
-        // and that guard object is contained in in its original lifetime.
-        let env: EnvPtr<'static> = unsafe { std::mem::transmute(env) };
-        state.replace(State::AttachedPermanently(env))
-    });
-    JniCallbackGuard { env, old_state }
+    // Unsafe condition: `env` pointer returned from transmute will not
+    // live past the drop of the guard object that we return,
+    // and that guard object is contained in in its original lifetime.
+    let static_env: EnvPtr<'static> = unsafe { std::mem::transmute(env) };
+    STATE.with(|state| state.borrow_mut().push(static_env, Frame::JniCallback));
+    JniCallbackGuard { env }
 }
 
-/// A guard object whose destructor restores the thread attachment state
-/// to whatever it was before the JNI invocation began.
-/// See [`attach_from_jni_callback`][] for more details.
+/// A guard object whose destructor pops the frame pushed by [`attach_from_jni_callback`][].
 pub struct JniCallbackGuard<'env> {
     env: EnvPtr<'env>,
-    old_state: State,
 }
 
 impl Drop for JniCallbackGuard<'_> {
     fn drop(&mut self) {
         STATE.with(|state| {
-            let old_state = std::mem::replace(&mut self.old_state, State::InUse);
-            let jni_state = state.replace(old_state);
-
+            let mut state = state.borrow_mut();
             // Unsafe condition: this pointer will not actually live past end of this block
             // so it remains inside its original lifetime.
             let env: EnvPtr<'static> = unsafe { std::mem::transmute(self.env) };
-            assert!(
-                jni_state == State::AttachedPermanently(env),
-                "invalid prior state `{jni_state:?}`"
-            );
+            assert_eq!(state.env, Some(env), "invalid JNI environment");
+            let detach = state.pop(Frame::JniCallback);
+            debug_assert!(!detach);
         });
     }
 }
 
-pub fn attach_permanently(jvm: JvmPtr) -> GlobalResult<AttachGuard> {
-    attached_or(jvm, || {
-        Ok(AttachGuard {
-            jvm,
+/// Attaches the current thread to the JVM (if it isn't already) and ensures that it stays attached, even after all
+/// duchess frames on the current thread have returned.
+pub fn attach_permanently(jvm: JvmPtr) -> GlobalResult<()> {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.env.is_none() {
             // no-op if already attached outside of duchess
-            env: unsafe { jvm.attach_thread()? },
-            permanent: true,
-        })
+            state.env = Some(unsafe { jvm.attach_thread()? });
+        }
+        state.permanent = true;
+        Ok(())
     })
 }
 
-pub unsafe fn attach<'jvm>(jvm: JvmPtr) -> GlobalResult<AttachGuard> {
-    attached_or(jvm, || {
-        Ok(AttachGuard {
-            jvm,
-            // no-op if already attached outside of duchess
-            env: unsafe { jvm.attach_thread()? },
-            permanent: false,
-        })
+/// Pushes a new duchess frame onto the current thread, attaching it to the JVM if necessary.
+/// The frame is popped when the returned guard is dropped.
+///
+/// # Safety condition
+///
+/// The JVM must not be deinitialized while the guard is live.
+pub unsafe fn attach(jvm: JvmPtr) -> GlobalResult<AttachGuard> {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let (env, frame) = match state.env {
+            // Already attached (permanently, or by an enclosing frame): share its environment
+            Some(env) => (env, Frame::Attached { detach: false }),
+            None => {
+                // no-op if already attached outside of duchess
+                let env = unsafe { jvm.attach_thread()? };
+                (env, Frame::Attached { detach: true })
+            }
+        };
+        let detach = matches!(frame, Frame::Attached { detach: true });
+        state.push(env, frame);
+        Ok(AttachGuard { jvm, env, detach })
     })
 }
 
-/// When dropped, will detach the current thread from the JVM unless it was permanently attached.
+/// When dropped, pops the frame pushed by [`attach`][] and detaches the current thread from the JVM if that frame
+/// attached it (and it was not permanently attached since).
 pub struct AttachGuard {
     jvm: JvmPtr,
     env: EnvPtr<'static>, // not send!
-    permanent: bool,
+    detach: bool,
 }
 
 impl Drop for AttachGuard {
     fn drop(&mut self) {
-        if self.permanent {
-            STATE.with(|state| {
-                let old_state = state.replace(State::AttachedPermanently(self.env));
-                debug_assert!(matches!(old_state, State::InUse))
-            });
-        } else {
-            match unsafe { self.jvm.detach_thread() } {
-                Ok(()) => STATE.with(|state| state.set(State::Detached)),
-                Err(err) => tracing::warn!(?err, "couldn't detach thread from JVM"),
+        let detach = STATE.with(|state| {
+            state.borrow_mut().pop(Frame::Attached {
+                detach: self.detach,
+            })
+        });
+        if detach {
+            if let Err(err) = unsafe { self.jvm.detach_thread() } {
+                tracing::warn!(?err, "couldn't detach thread from JVM");
             }
         }
     }
@@ -0,0 +1,39 @@
+use std::marker::PhantomData;
+
+use crate::{java::lang::Throwable, AsJRef, Jvm, JvmOp};
+
+/// Operation returned by [`JvmOp::throw`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct Throw<This, R>
+where
+    This: JvmOp,
+{
+    this: This,
+    phantom: PhantomData<fn() -> R>,
+}
+
+impl<This, R> Throw<This, R>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<Throwable>,
+{
+    pub(crate) fn new(this: This) -> Self {
+        Self {
+            this,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<This, R> JvmOp for Throw<This, R>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<Throwable>,
+{
+    type Output<'jvm> = R;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, R> {
+        let exception = self.this.execute_with(jvm)?;
+        jvm.throw(&exception)
+    }
+}
@@ -1,7 +1,11 @@
-use std::{collections::HashMap, marker::PhantomData};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    marker::PhantomData,
+};
 
 use crate::{
-    cast::Upcast, from_ref::FromRef, java, jvm::JavaView, Error, Global, Jvm, JvmOp, Local,
+    cast::Upcast, from_ref::FromRef, java, jvm::JavaView, prelude::IntoJava, AsJRef, Error, Global,
+    Jvm, JvmOp, Local, NullJRef,
 };
 
 pub trait ToJava {
@@ -77,6 +81,23 @@ where
     }
 }
 
+/// Number of local references reserved for converting a single element of a collection. Nested
+/// collections push frames of their own, so this only has to cover the element's own references.
+const ELEMENT_FRAME_CAPACITY: i32 = 8;
+
+/// Runs `op` (which converts one element of a collection) in its own local frame, so that
+/// converting a large collection doesn't overflow the local reference table.
+pub(crate) fn in_element_frame<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    op: impl for<'frame> FnOnce(&mut Jvm<'frame>) -> crate::Result<'frame, ()>,
+) -> crate::Result<'jvm, ()> {
+    jvm.with_local_frame(ELEMENT_FRAME_CAPACITY, |jvm| {
+        op(jvm)?;
+        Ok(None::<Local<'_, java::lang::Object>>)
+    })?;
+    Ok(())
+}
+
 impl<K, V, JK, JV, S> ToJavaImpl<java::util::HashMap<JK, JV>> for HashMap<K, V, S>
 where
     K: ToJavaImpl<JK>,
@@ -91,7 +112,10 @@ where
         let jmap: Local<'jvm, java::util::HashMap<JK, JV>> =
             java::util::HashMap::new().execute_with(jvm)?;
         for (key, value) in rust {
-            jmap.put(key.to_java(), value.to_java()).execute_with(jvm)?;
+            in_element_frame(jvm, |jvm| {
+                jmap.put(key.to_java(), value.to_java()).execute_with(jvm)?;
+                Ok(())
+            })?;
         }
         Ok(Some(jmap))
     }
@@ -129,7 +153,10 @@ where
         let jvec: Local<'jvm, java::util::ArrayList<JE>> =
             java::util::ArrayList::new().execute_with(jvm)?;
         for element in rust {
-            jvec.add(element.to_java()).execute_with(jvm)?;
+            in_element_frame(jvm, |jvm| {
+                jvec.add(element.to_java()).execute_with(jvm)?;
+                Ok(())
+            })?;
         }
         Ok(Some(jvec))
     }
@@ -153,6 +180,172 @@ where
     }
 }
 
+/// Adds each of `elements` to `collection`, each in its own local frame.
+fn add_elements<'jvm, 'r, E, JE>(
+    jvm: &mut Jvm<'jvm>,
+    collection: &java::util::Collection<JE>,
+    elements: impl IntoIterator<Item = &'r E>,
+) -> crate::Result<'jvm, ()>
+where
+    E: ToJavaImpl<JE> + 'r,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    for element in elements {
+        in_element_frame(jvm, |jvm| {
+            collection.add(element.to_java()).execute_with(jvm)?;
+            Ok(())
+        })?;
+    }
+    Ok(())
+}
+
+impl<E, JE> ToJavaImpl<java::util::ArrayList<JE>> for VecDeque<E>
+where
+    E: ToJavaImpl<JE>,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::ArrayList<JE>>>> {
+        let jvec: Local<'jvm, java::util::ArrayList<JE>> =
+            java::util::ArrayList::new().execute_with(jvm)?;
+        add_elements(jvm, jvec.as_jref()?, rust)?;
+        Ok(Some(jvec))
+    }
+}
+
+impl<E, JE> ToJavaImpl<java::util::List<JE>> for VecDeque<E>
+where
+    E: ToJavaImpl<JE>,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::List<JE>>>> {
+        Ok(Some(
+            rust.to_java::<java::util::ArrayList<JE>>()
+                .assert_not_null()
+                .upcast()
+                .execute_with(jvm)?,
+        ))
+    }
+}
+
+impl<E, JE, S> ToJavaImpl<java::util::HashSet<JE>> for HashSet<E, S>
+where
+    E: ToJavaImpl<JE>,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::HashSet<JE>>>> {
+        let jset: Local<'jvm, java::util::HashSet<JE>> =
+            java::util::HashSet::new().execute_with(jvm)?;
+        add_elements(jvm, jset.as_jref()?, rust)?;
+        Ok(Some(jset))
+    }
+}
+
+impl<E, JE, S> ToJavaImpl<java::util::Set<JE>> for HashSet<E, S>
+where
+    E: ToJavaImpl<JE>,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Set<JE>>>> {
+        Ok(Some(
+            rust.to_java::<java::util::HashSet<JE>>()
+                .assert_not_null()
+                .upcast()
+                .execute_with(jvm)?,
+        ))
+    }
+}
+
+/// The elements must implement `java.lang.Comparable`, and Java's ordering should agree with Rust's.
+impl<E, JE> ToJavaImpl<java::util::TreeSet<JE>> for BTreeSet<E>
+where
+    E: ToJavaImpl<JE>,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::TreeSet<JE>>>> {
+        let jset: Local<'jvm, java::util::TreeSet<JE>> =
+            java::util::TreeSet::new().execute_with(jvm)?;
+        add_elements(jvm, jset.as_jref()?, rust)?;
+        Ok(Some(jset))
+    }
+}
+
+impl<E, JE> ToJavaImpl<java::util::Set<JE>> for BTreeSet<E>
+where
+    E: ToJavaImpl<JE>,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Set<JE>>>> {
+        Ok(Some(
+            rust.to_java::<java::util::TreeSet<JE>>()
+                .assert_not_null()
+                .upcast()
+                .execute_with(jvm)?,
+        ))
+    }
+}
+
+/// The keys must implement `java.lang.Comparable`, and Java's ordering should agree with Rust's.
+impl<K, V, JK, JV> ToJavaImpl<java::util::TreeMap<JK, JV>> for BTreeMap<K, V>
+where
+    K: ToJavaImpl<JK>,
+    V: ToJavaImpl<JV>,
+    JK: Upcast<java::lang::Object> + Upcast<JK>,
+    JV: Upcast<java::lang::Object> + Upcast<JV>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::TreeMap<JK, JV>>>> {
+        let jmap: Local<'jvm, java::util::TreeMap<JK, JV>> =
+            java::util::TreeMap::new().execute_with(jvm)?;
+        for (key, value) in rust {
+            in_element_frame(jvm, |jvm| {
+                jmap.put(key.to_java(), value.to_java()).execute_with(jvm)?;
+                Ok(())
+            })?;
+        }
+        Ok(Some(jmap))
+    }
+}
+
+impl<K, V, JK, JV> ToJavaImpl<java::util::Map<JK, JV>> for BTreeMap<K, V>
+where
+    K: ToJavaImpl<JK>,
+    V: ToJavaImpl<JV>,
+    JK: Upcast<java::lang::Object> + Upcast<JK>,
+    JV: Upcast<java::lang::Object> + Upcast<JV>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Map<JK, JV>>>> {
+        Ok(Some(
+            rust.to_java::<java::util::TreeMap<JK, JV>>()
+                .assert_not_null()
+                .upcast()
+                .execute_with(jvm)?,
+        ))
+    }
+}
+
 impl ToJavaImpl<java::lang::String> for String {
     fn to_java_impl<'jvm>(
         rust: &Self,
@@ -182,6 +375,15 @@ impl ToJavaImpl<java::Array<i8>> for Vec<u8> {
     }
 }
 
+impl ToJavaImpl<java::lang::Character> for char {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::lang::Character>>> {
+        java::lang::Character::value_of(*rust).execute_with(jvm)
+    }
+}
+
 impl<J> ToJavaImpl<J> for Local<'_, J>
 where
     J: Upcast<java::lang::Object>,
@@ -234,10 +436,11 @@ where
     }
 }
 
-impl<J, R> ToJavaImpl<J> for crate::Result<'_, R>
+impl<J, R, E> ToJavaImpl<J> for Result<R, E>
 where
     J: Upcast<java::lang::Object>,
     R: ToJavaImpl<J>,
+    E: ToJavaThrowable,
 {
     fn to_java_impl<'jvm>(
         rust: &Self,
@@ -245,43 +448,66 @@ where
     ) -> crate::Result<'jvm, Option<Local<'jvm, J>>> {
         match rust {
             Ok(r) => R::to_java_impl(r, jvm),
-            Err(e) => match e {
-                Error::Thrown(t) => Err(Error::Thrown(jvm.local(t))),
-                Error::SliceTooLong(t) => Err(Error::SliceTooLong(*t)),
-                Error::NullDeref => Err(Error::NullDeref),
-                Error::NestedUsage => Err(Error::NestedUsage),
-                Error::JvmAlreadyExists => Err(Error::JvmAlreadyExists),
-                Error::UnableToLoadLibjvm(t) => Err(Error::UnableToLoadLibjvm(
-                    format!("UnableToLoadLibjvm({t:?})").as_str().into(), // FIXME: should to_java_impl be `self` ?
-                )),
-                Error::JvmInternal(t) => Err(Error::JvmInternal(t.clone())),
-            },
+            Err(e) => Err(e.to_java_throwable(jvm)),
         }
     }
 }
 
-impl<J, R> ToJavaImpl<J> for crate::GlobalResult<R>
+/// Rust error types that can be thrown as a Java exception, e.g. when returned from a
+/// [`#[java_function]`](crate::java_function).
+///
+/// This is implemented for [`Error`] as well as for any type `E` where `&E` is a [`JvmOp`]
+/// producing a `java.lang.Throwable`. In particular, that includes error enums that
+/// `#[derive(ToJava)]` with a Java exception class as their root.
+pub trait ToJavaThrowable {
+    /// Converts `self` into the error that should be propagated to Java: usually
+    /// [`Error::Thrown`] with the exception to throw.
+    fn to_java_throwable<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> Error<Local<'jvm, java::lang::Throwable>>;
+}
+
+impl<E> ToJavaThrowable for E
 where
-    J: Upcast<java::lang::Object>,
-    R: ToJavaImpl<J>,
+    for<'a> &'a E: IntoJava<java::lang::Throwable>,
 {
-    fn to_java_impl<'jvm>(
-        rust: &Self,
+    fn to_java_throwable<'jvm>(
+        &self,
         jvm: &mut Jvm<'jvm>,
-    ) -> crate::Result<'jvm, Option<Local<'jvm, J>>> {
-        match rust {
-            Ok(r) => R::to_java_impl(r, jvm),
-            Err(e) => match e {
-                Error::Thrown(t) => Err(Error::Thrown(jvm.local(t))),
-                Error::SliceTooLong(t) => Err(Error::SliceTooLong(*t)),
-                Error::NullDeref => Err(Error::NullDeref),
-                Error::NestedUsage => Err(Error::NestedUsage),
-                Error::JvmAlreadyExists => Err(Error::JvmAlreadyExists),
-                Error::UnableToLoadLibjvm(t) => Err(Error::UnableToLoadLibjvm(
-                    format!("UnableToLoadLibjvm({t:?})").as_str().into(), // FIXME: should to_java_impl be `self` ?
-                )),
-                Error::JvmInternal(t) => Err(Error::JvmInternal(t.clone())),
+    ) -> Error<Local<'jvm, java::lang::Throwable>> {
+        let exception = match self.into_java(jvm) {
+            Ok(exception) => exception,
+            Err(e) => return e,
+        };
+        match exception.as_jref() {
+            Ok(exception) => Error::Thrown(jvm.local(exception).into()),
+            Err(NullJRef) => Error::NullDeref,
+        }
+    }
+}
+
+impl<T> ToJavaThrowable for Error<T>
+where
+    T: AsJRef<java::lang::Throwable>,
+{
+    fn to_java_throwable<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> Error<Local<'jvm, java::lang::Throwable>> {
+        match self {
+            Error::Thrown(t) => match t.as_jref() {
+                Ok(exception) => Error::Thrown(t.with_exception(jvm.local(exception))),
+                Err(NullJRef) => Error::NullDeref,
             },
+            Error::SliceTooLong(t) => Error::SliceTooLong(*t),
+            Error::NullDeref => Error::NullDeref,
+            Error::JvmAlreadyExists => Error::JvmAlreadyExists,
+            #[cfg(feature = "dylibjvm")]
+            Error::UnableToLoadLibjvm(t) => Error::UnableToLoadLibjvm(
+                format!("UnableToLoadLibjvm({t:?})").as_str().into(), // FIXME: should to_java_impl be `self` ?
+            ),
+            Error::JvmInternal(t) => Error::JvmInternal(t.clone()),
         }
     }
 }
@@ -1,6 +1,8 @@
-use std::marker::PhantomData;
+use std::{ffi::CStr, marker::PhantomData};
 
-use crate::{cast::Upcast, java::lang::Throwable, Jvm, JvmOp, Local};
+use crate::{
+    cast::Upcast, find::find_class, java::lang::Throwable, jvm::JavaObjectExt, Jvm, JvmOp, Local,
+};
 
 #[derive_where::derive_where(Copy, Clone)]
 pub struct TryCatch<This, J>
@@ -37,7 +39,7 @@ where
             Ok(v) => Ok(Ok(v)),
             Err(e) => match e {
                 crate::Error::Thrown(exception) => {
-                    if let Ok(exception) = exception.try_downcast::<J>().execute_with(jvm)? {
+                    if let Ok(exception) = (&*exception).try_downcast::<J>().execute_with(jvm)? {
                         Ok(Err(exception))
                     } else {
                         Err(crate::Error::Thrown(exception))
@@ -48,3 +50,253 @@ where
         }
     }
 }
+
+/// Implemented by the operations generated for Java methods and constructors, so that
+/// [`catch_declared`](JvmOp::catch_declared) can catch the exceptions listed in their `throws` clause
+/// (as declared in `java_package!`).
+pub trait DeclaredExceptions {
+    /// The exception that was caught: a [`Local`] of the declared class, a [`Caught2`] (etc.) if
+    /// several classes are declared, or [`Infallible`](std::convert::Infallible) if the `throws` clause is empty.
+    /// If there are more than four classes, or some of them are not declared in the same `java_package!`,
+    /// it is a `Local<'jvm, Throwable>` instead.
+    type Caught<'jvm>;
+
+    /// Returns the exception as an instance of a declared class, or `None` if it isn't one.
+    fn try_catch<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        exception: &Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Option<Self::Caught<'jvm>>>;
+}
+
+/// Used by the generated [`DeclaredExceptions`] impls.
+pub fn catch_as<'jvm, E>(
+    jvm: &mut Jvm<'jvm>,
+    exception: &Local<'jvm, Throwable>,
+) -> crate::Result<'jvm, Option<Local<'jvm, E>>>
+where
+    E: Upcast<Throwable>,
+{
+    Ok(exception.try_downcast::<E>().execute_with(jvm)?.ok())
+}
+
+/// Used by the generated [`DeclaredExceptions`] impls when the declared classes can't be named in Rust.
+pub fn catch_named<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    exception: &Local<'jvm, Throwable>,
+    jni_class_names: &[&CStr],
+) -> crate::Result<'jvm, Option<Local<'jvm, Throwable>>> {
+    let exception_raw = exception.as_raw();
+    for jni_class_name in jni_class_names {
+        let class = find_class(jvm, jni_class_name)?;
+        let class_raw = class.as_raw();
+        let is_inst = unsafe {
+            jvm.env().invoke_unchecked(
+                |env| env.IsInstanceOf,
+                |env, f| f(env, exception_raw.as_ptr(), class_raw.as_ptr()),
+            ) == jni_sys::JNI_TRUE
+        };
+        if is_inst {
+            return Ok(Some(jvm.local(&**exception)));
+        }
+    }
+    Ok(None)
+}
+
+#[derive_where::derive_where(Copy, Clone)]
+pub struct TryCatchDeclared<This>
+where
+    This: JvmOp + DeclaredExceptions,
+{
+    this: This,
+}
+
+impl<This> TryCatchDeclared<This>
+where
+    This: JvmOp + DeclaredExceptions,
+{
+    pub(crate) fn new(this: This) -> Self {
+        Self { this }
+    }
+}
+
+impl<This> JvmOp for TryCatchDeclared<This>
+where
+    This: JvmOp + DeclaredExceptions,
+{
+    type Output<'jvm> = Result<This::Output<'jvm>, This::Caught<'jvm>>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        match self.this.execute_with(jvm) {
+            Ok(v) => Ok(Ok(v)),
+            Err(crate::Error::Thrown(exception)) => match This::try_catch(jvm, &exception)? {
+                Some(caught) => Ok(Err(caught)),
+                None => Err(crate::Error::Thrown(exception)),
+            },
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// A tuple of exception classes, such as `(java::io::IOException, java::lang::InterruptedException)`,
+/// that can be caught together by [`TryCatchAny`].
+/// Tuples of up to four classes are supported; the empty tuple catches nothing.
+pub trait Catchable {
+    /// The exception that was caught, e.g. [`Caught2`] for a tuple of two classes.
+    type Caught<'jvm>;
+
+    /// Returns the exception as an instance of the first class in the tuple that it is an instance of,
+    /// or `None` if it isn't an instance of any of them.
+    fn try_catch<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        exception: &Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Option<Self::Caught<'jvm>>>;
+}
+
+impl Catchable for () {
+    type Caught<'jvm> = std::convert::Infallible;
+
+    fn try_catch<'jvm>(
+        _jvm: &mut Jvm<'jvm>,
+        _exception: &Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Option<Self::Caught<'jvm>>> {
+        Ok(None)
+    }
+}
+
+impl<A> Catchable for (A,)
+where
+    A: Upcast<Throwable>,
+{
+    type Caught<'jvm> = Local<'jvm, A>;
+
+    fn try_catch<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        exception: &Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Option<Self::Caught<'jvm>>> {
+        catch_as::<A>(jvm, exception)
+    }
+}
+
+macro_rules! caught_enum {
+    ($(#[$attr:meta])* $name:ident { $($variant:ident($class:ident),)* }) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub enum $name<'jvm, $($class,)*>
+        where
+            $($class: Upcast<Throwable>,)*
+        {
+            $($variant(Local<'jvm, $class>),)*
+        }
+
+        impl<$($class,)*> Catchable for ($($class,)*)
+        where
+            $($class: Upcast<Throwable>,)*
+        {
+            type Caught<'jvm> = $name<'jvm, $($class,)*>;
+
+            fn try_catch<'jvm>(
+                jvm: &mut Jvm<'jvm>,
+                exception: &Local<'jvm, Throwable>,
+            ) -> crate::Result<'jvm, Option<Self::Caught<'jvm>>> {
+                $(
+                    if let Some(exception) = catch_as::<$class>(jvm, exception)? {
+                        return Ok(Some($name::$variant(exception)));
+                    }
+                )*
+                Ok(None)
+            }
+        }
+    };
+}
+
+caught_enum! {
+    /// An exception caught as one of two classes, see [`Catchable`].
+    Caught2 { First(A), Second(B), }
+}
+
+caught_enum! {
+    /// An exception caught as one of three classes, see [`Catchable`].
+    Caught3 { First(A), Second(B), Third(C), }
+}
+
+caught_enum! {
+    /// An exception caught as one of four classes, see [`Catchable`].
+    Caught4 { First(A), Second(B), Third(C), Fourth(D), }
+}
+
+#[derive_where::derive_where(Copy, Clone)]
+pub struct TryCatchAny<This, L>
+where
+    This: JvmOp,
+    L: Catchable,
+{
+    this: This,
+    phantom: PhantomData<fn() -> L>,
+}
+
+impl<This, L> TryCatchAny<This, L>
+where
+    This: JvmOp,
+    L: Catchable,
+{
+    pub(crate) fn new(this: This) -> Self {
+        Self {
+            this,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<This, L> JvmOp for TryCatchAny<This, L>
+where
+    This: JvmOp,
+    L: Catchable,
+{
+    type Output<'jvm> = Result<This::Output<'jvm>, L::Caught<'jvm>>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        match self.this.execute_with(jvm) {
+            Ok(v) => Ok(Ok(v)),
+            Err(crate::Error::Thrown(exception)) => match L::try_catch(jvm, &exception)? {
+                Some(caught) => Ok(Err(caught)),
+                None => Err(crate::Error::Thrown(exception)),
+            },
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive_where::derive_where(Copy, Clone)]
+pub struct Finally<This, F>
+where
+    This: JvmOp,
+    F: JvmOp,
+{
+    this: This,
+    finally: F,
+}
+
+impl<This, F> Finally<This, F>
+where
+    This: JvmOp,
+    F: JvmOp,
+{
+    pub(crate) fn new(this: This, finally: F) -> Self {
+        Self { this, finally }
+    }
+}
+
+impl<This, F> JvmOp for Finally<This, F>
+where
+    This: JvmOp,
+    F: JvmOp,
+{
+    type Output<'jvm> = This::Output<'jvm>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let result = self.this.execute_with(jvm);
+        // As in Java, an error from the cleanup replaces the outcome of `this`
+        self.finally.execute_with(jvm)?;
+        result
+    }
+}
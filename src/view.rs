@@ -0,0 +1,353 @@
+//! Passes Rust iterators and collections to Java as live views, whose elements are converted only
+//! when Java accesses them (rather than being copied into a Java collection up front).
+
+use std::{
+    collections::{
+        hash_map::{self, RandomState},
+        HashMap,
+    },
+    hash::{BuildHasher, Hash},
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    bundled::duchess_rs,
+    cast::Upcast,
+    closure::{argument, new_java_closure},
+    java,
+    java::lang::Object,
+    plumbing::ToJavaImpl,
+    proxy::object_result,
+    Error, IntoRust, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// A Rust iterator that can be converted (with [`to_java`](crate::ToJava::to_java)) into a
+/// `java.util.Iterator<J>`, a `java.util.Spliterator<J>` or a (sequential) `java.util.stream.Stream<J>`,
+/// provided that its items can be converted into `J`.
+///
+/// Items are pulled out of the Rust iterator (and converted) one at a time, as Java asks for them,
+/// so the iterator may be huge or even infinite. All Java objects created from the same view (or
+/// its clones) share the underlying iterator.
+///
+/// ```rust,no_run
+/// # use duchess::{java, prelude::*, IteratorView};
+/// # fn main() -> duchess::GlobalResult<()> {
+/// let squares = IteratorView::new((1..).map(|n: i64| n * n));
+/// let count = squares
+///     .to_java::<java::util::stream::Stream<java::lang::Long>>()
+///     .limit(10i64)
+///     .count()
+///     .execute()?;
+/// assert_eq!(count, 10);
+/// # Ok(())
+/// # }
+/// ```
+#[derive_where::derive_where(Clone)]
+pub struct IteratorView<I> {
+    iter: Arc<Mutex<I>>,
+}
+
+impl<I: Iterator> IteratorView<I> {
+    pub fn new(iter: impl IntoIterator<IntoIter = I>) -> Self {
+        IteratorView {
+            iter: Arc::new(Mutex::new(iter.into_iter())),
+        }
+    }
+}
+
+impl<I> IteratorView<I>
+where
+    I: Iterator + Send + 'static,
+{
+    /// Creates one of the `duchess_rs.RustClosure` subclasses that wrap iterators, whose closure
+    /// returns the next item or -- once the iterator is exhausted -- the Java object itself.
+    fn new_java_iterator<'jvm, C, J, K>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        new: impl FnOnce(i64) -> C,
+    ) -> crate::Result<'jvm, Local<'jvm, K>>
+    where
+        for<'a> C: JvmOp<Output<'a> = Local<'a, duchess_rs::RustClosure>>,
+        J: Upcast<Object>,
+        K: JavaObject,
+        I::Item: ToJavaImpl<J>,
+    {
+        let iter = self.iter.clone();
+        new_java_closure(
+            jvm,
+            new,
+            Box::new(move |jvm, this, _| {
+                // A panic while advancing poisons the iterator, so later calls panic as well
+                let item = iter.lock().unwrap().next();
+                match item {
+                    Some(item) => object_result::<J, I::Item>(jvm, item),
+                    None => Ok(this.map(|this| jvm.local(this))),
+                }
+            }),
+        )
+    }
+}
+
+impl<I, J> ToJavaImpl<java::util::Iterator<J>> for IteratorView<I>
+where
+    I: Iterator + Send + 'static,
+    I::Item: ToJavaImpl<J>,
+    J: Upcast<Object>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Iterator<J>>>> {
+        rust.new_java_iterator::<_, J, _>(jvm, |closure| {
+            duchess_rs::RustIterator::new(closure).upcast()
+        })
+        .map(Some)
+    }
+}
+
+impl<I, J> ToJavaImpl<java::util::Spliterator<J>> for IteratorView<I>
+where
+    I: Iterator + Send + 'static,
+    I::Item: ToJavaImpl<J>,
+    J: Upcast<Object>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Spliterator<J>>>> {
+        rust.new_java_iterator::<_, J, _>(jvm, |closure| {
+            duchess_rs::RustSpliterator::new(closure).upcast()
+        })
+        .map(Some)
+    }
+}
+
+impl<I, J> ToJavaImpl<java::util::stream::Stream<J>> for IteratorView<I>
+where
+    I: Iterator + Send + 'static,
+    I::Item: ToJavaImpl<J>,
+    J: Upcast<Object>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::stream::Stream<J>>>> {
+        let spliterator: Local<duchess_rs::RustSpliterator> = rust
+            .new_java_iterator::<_, J, _>(jvm, |closure| {
+                duchess_rs::RustSpliterator::new(closure).upcast()
+            })?;
+        let stream = spliterator.stream().assert_not_null().execute_with(jvm)?;
+        // SAFETY: the stream's elements are the items of the spliterator, which are all `J`s
+        Ok(Some(unsafe { stream.cast() }))
+    }
+}
+
+/// A Rust `Vec` that can be converted (with [`to_java`](crate::ToJava::to_java)) into a read-only
+/// `java.util.List<J>`, provided that its elements can be converted into `J`.
+///
+/// The elements are not copied: each one is converted when Java calls `get` on the list (so calling
+/// `get` twice yields two different Java objects). Methods that would modify the list throw an
+/// `UnsupportedOperationException`.
+#[derive_where::derive_where(Clone)]
+pub struct ListView<T> {
+    elements: Arc<Vec<T>>,
+}
+
+impl<T> ListView<T> {
+    pub fn new(elements: Vec<T>) -> Self {
+        ListView {
+            elements: Arc::new(elements),
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for ListView<T> {
+    fn from(elements: Vec<T>) -> Self {
+        ListView::new(elements)
+    }
+}
+
+impl<T, J> ToJavaImpl<java::util::List<J>> for ListView<T>
+where
+    T: ToJavaImpl<J> + Send + Sync + 'static,
+    J: Upcast<Object>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::List<J>>>> {
+        let elements = rust.elements.clone();
+        new_java_list(jvm, elements.len(), move |jvm, index| {
+            T::to_java_impl(&elements[index], jvm)
+        })
+        .map(Some)
+    }
+}
+
+/// Creates a `duchess_rs.RustList` of `len` elements, where `get` converts the element at an index.
+fn new_java_list<'jvm, J>(
+    jvm: &mut Jvm<'jvm>,
+    len: usize,
+    get: impl for<'a> Fn(&mut Jvm<'a>, usize) -> crate::Result<'a, Option<Local<'a, J>>>
+        + Send
+        + Sync
+        + 'static,
+) -> crate::Result<'jvm, Local<'jvm, java::util::List<J>>>
+where
+    J: Upcast<Object>,
+{
+    let size = i32::try_from(len).map_err(|_| Error::SliceTooLong(len))?;
+    let get: Local<java::util::function::Function<Object, Object>> = new_java_closure(
+        jvm,
+        |closure| duchess_rs::RustFunction::new(closure).upcast(),
+        Box::new(move |jvm, index, _| {
+            // `RustList.get` only passes indices within bounds
+            let index = argument::<java::lang::Integer, i32>(jvm, index)?;
+            Ok(get(jvm, index as usize)?.map(|element| element.upcast()))
+        }),
+    )?;
+    let list = duchess_rs::RustList::new(size, &get).execute_with(jvm)?;
+    // SAFETY: `get` only returns `J`s
+    Ok(unsafe { list.upcast::<java::util::List<Object>>().cast() })
+}
+
+/// A Rust `HashMap` that can be converted (with [`to_java`](crate::ToJava::to_java)) into a read-only
+/// `java.util.Map<JK, JV>`, provided that its keys can be converted to and from `JK` and its values
+/// can be converted into `JV`.
+///
+/// Nothing is copied up front: `get` and `containsKey` convert the key passed by Java into a Rust
+/// key and look it up in the `HashMap` (keys of any other class are absent), converting the value
+/// that is found. Iterating over the map converts each key and looks its value up in turn. Methods
+/// that would modify the map throw an `UnsupportedOperationException`.
+///
+/// Java treats a `null` value as an absent key, so values should not convert to `null`.
+#[derive_where::derive_where(Clone)]
+pub struct MapView<K, V, S = RandomState> {
+    inner: Arc<MapViewInner<K, V, S>>,
+}
+
+struct MapViewInner<K, V, S> {
+    map: HashMap<K, V, S>,
+}
+
+impl<K, V, S> MapView<K, V, S> {
+    pub fn new(map: HashMap<K, V, S>) -> Self {
+        MapView {
+            inner: Arc::new(MapViewInner { map }),
+        }
+    }
+}
+
+impl<K, V, S> From<HashMap<K, V, S>> for MapView<K, V, S> {
+    fn from(map: HashMap<K, V, S>) -> Self {
+        MapView::new(map)
+    }
+}
+
+/// Iterates over the keys of a [`MapView`] in place, without copying them.
+struct MapViewKeys<K: 'static, V: 'static, S: 'static> {
+    // Declared before `inner`, so that it is dropped first
+    keys: hash_map::Keys<'static, K, V>,
+    inner: Arc<MapViewInner<K, V, S>>,
+}
+
+impl<K, V, S: 'static> MapViewKeys<K, V, S> {
+    fn new(inner: Arc<MapViewInner<K, V, S>>) -> Self {
+        // SAFETY: the map is never modified, and the `Arc` keeps it alive (at the same address) for as long
+        // as `keys` and the keys it yields, each of which holds a clone of the `Arc`
+        let map: &'static HashMap<K, V, S> = unsafe { &*std::ptr::addr_of!(inner.map) };
+        MapViewKeys {
+            keys: map.keys(),
+            inner,
+        }
+    }
+}
+
+impl<K, V, S: 'static> Iterator for MapViewKeys<K, V, S> {
+    type Item = MapViewKey<K, V, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        Some(MapViewKey {
+            key,
+            _inner: self.inner.clone(),
+        })
+    }
+}
+
+/// A key of a [`MapView`], which keeps the map alive while Java converts it.
+struct MapViewKey<K: 'static, V: 'static, S: 'static> {
+    key: &'static K,
+    _inner: Arc<MapViewInner<K, V, S>>,
+}
+
+impl<K, V, S, JK> ToJavaImpl<JK> for MapViewKey<K, V, S>
+where
+    K: ToJavaImpl<JK>,
+    S: 'static,
+    JK: Upcast<Object>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, JK>>> {
+        K::to_java_impl(rust.key, jvm)
+    }
+}
+
+impl<K, V, S, JK, JV> ToJavaImpl<java::util::Map<JK, JV>> for MapView<K, V, S>
+where
+    K: ToJavaImpl<JK> + Eq + Hash + Send + Sync + 'static,
+    V: ToJavaImpl<JV> + Send + Sync + 'static,
+    S: BuildHasher + Send + Sync + 'static,
+    JK: Upcast<Object>,
+    JV: Upcast<Object>,
+    for<'a> &'a JK: IntoRust<K>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Map<JK, JV>>>> {
+        let inner = rust.inner.clone();
+        let get: Local<java::util::function::Function<Object, Object>> = new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustFunction::new(closure).upcast(),
+            Box::new(move |jvm, key, _| {
+                // `RustMap.get` never passes `null`, but it may pass keys of any class
+                let key = key.ok_or(Error::NullDeref)?;
+                let Ok(key) = key.try_downcast::<JK>().execute_with(jvm)? else {
+                    return Ok(None);
+                };
+                let key: K = (&*key).into_rust(jvm)?;
+                match inner.map.get(&key) {
+                    Some(value) => Ok(V::to_java_impl(value, jvm)?.map(|value| value.upcast())),
+                    None => Ok(None),
+                }
+            }),
+        )?;
+
+        let len = rust.inner.map.len();
+        let size = i32::try_from(len).map_err(|_| Error::SliceTooLong(len))?;
+
+        // Each iteration over the map walks the keys of the `HashMap` afresh
+        let inner = rust.inner.clone();
+        let keys: Local<java::util::function::Supplier<Object>> = new_java_closure(
+            jvm,
+            |closure| duchess_rs::RustSupplier::new(closure).upcast(),
+            Box::new(move |jvm, _, _| {
+                let keys = IteratorView::new(MapViewKeys::new(inner.clone()));
+                let keys = keys
+                    .new_java_iterator::<_, JK, java::util::Iterator<JK>>(jvm, |closure| {
+                        duchess_rs::RustIterator::new(closure).upcast()
+                    })?;
+                Ok(Some(keys.upcast()))
+            }),
+        )?;
+
+        let map = duchess_rs::RustMap::new(&get, size, &keys).execute_with(jvm)?;
+        // SAFETY: the keys of the map are `JK`s and `get` only returns `JV`s
+        Ok(Some(unsafe {
+            map.upcast::<java::util::Map<Object, Object>>().cast()
+        }))
+    }
+}
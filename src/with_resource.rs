@@ -0,0 +1,68 @@
+use crate::{java::lang::AutoCloseable, AsJRef, Error, Jvm, JvmOp};
+
+/// Operation returned by [`JvmOp::with_resource`].
+#[derive(Copy, Clone)]
+pub struct WithResource<This, F>
+where
+    This: JvmOp,
+{
+    this: This,
+    body: F,
+}
+
+impl<This, F> WithResource<This, F>
+where
+    This: JvmOp,
+{
+    pub(crate) fn new(this: This, body: F) -> Self {
+        Self { this, body }
+    }
+}
+
+impl<This, F, T> JvmOp for WithResource<This, F>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<AutoCloseable>,
+    F: Copy + for<'jvm> FnOnce(&This::Output<'jvm>, &mut Jvm<'jvm>) -> crate::Result<'jvm, T>,
+{
+    type Output<'jvm> = T;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, T> {
+        with_resource(jvm, self.this, self.body)
+    }
+}
+
+/// Shared by [`Jvm::with_resource`] and [`WithResource`].
+pub(crate) fn with_resource<'jvm, R, T>(
+    jvm: &mut Jvm<'jvm>,
+    resource: R,
+    body: impl FnOnce(&R::Output<'jvm>, &mut Jvm<'jvm>) -> crate::Result<'jvm, T>,
+) -> crate::Result<'jvm, T>
+where
+    R: JvmOp,
+    R::Output<'jvm>: AsJRef<AutoCloseable>,
+{
+    let resource = resource.execute_with(jvm)?;
+    let result = body(&resource, jvm);
+
+    // Like try-with-resources in Java, a `null` resource is not closed
+    let Ok(resource) = resource.as_jref() else {
+        return result;
+    };
+    match (result, resource.close().execute_with(jvm)) {
+        (result, Ok(())) => result,
+        (Ok(_), Err(close_error)) => Err(close_error),
+        (Err(Error::Thrown(mut primary)), Err(Error::Thrown(close_exception))) => {
+            primary
+                .add_suppressed(&*close_exception)
+                .execute_with(jvm)?;
+            primary.retake_snapshot(jvm);
+            Err(Error::Thrown(primary))
+        }
+        // Only Java exceptions can be suppressed, so other errors from `close()` can't be attached to the first one
+        (Err(error), Err(close_error)) => {
+            tracing::warn!(?close_error, "error closing resource after an error, dropping it");
+            Err(error)
+        }
+    }
+}
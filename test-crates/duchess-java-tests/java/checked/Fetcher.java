@@ -0,0 +1,30 @@
+package checked;
+
+import java.io.FileNotFoundException;
+import java.io.IOException;
+
+public class Fetcher {
+    public String fetch(String key) throws MissingKeyException {
+        if (key.equals("invalid")) {
+            throw new IllegalArgumentException("invalid key");
+        }
+        if (!key.equals("present")) {
+            throw new MissingKeyException(key);
+        }
+        return "value";
+    }
+
+    public String fetchRemote(String key) throws IOException, MissingKeyException {
+        if (key.isEmpty()) {
+            throw new IOException("connection reset");
+        }
+        return fetch(key);
+    }
+
+    public String fetchFile(String path) throws FileNotFoundException {
+        if (path.isEmpty()) {
+            throw new FileNotFoundException("no path");
+        }
+        return "contents";
+    }
+}
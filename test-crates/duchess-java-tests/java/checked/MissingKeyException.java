@@ -0,0 +1,7 @@
+package checked;
+
+public class MissingKeyException extends Exception {
+    public MissingKeyException(String key) {
+        super("no value for " + key);
+    }
+}
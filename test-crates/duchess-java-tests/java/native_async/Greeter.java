@@ -0,0 +1,11 @@
+package native_async;
+
+import java.util.concurrent.CompletableFuture;
+
+public class Greeter {
+    public native CompletableFuture<String> greet(String name);
+    public native CompletableFuture<String> greetLater(String name);
+    public native CompletableFuture<String> nobody();
+    public native CompletableFuture<String> fail(String reason);
+    public native CompletableFuture<String> explode();
+}
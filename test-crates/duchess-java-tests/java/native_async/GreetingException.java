@@ -0,0 +1,7 @@
+package native_async;
+
+public class GreetingException extends Exception {
+    public GreetingException(String message) {
+        super(message);
+    }
+}
@@ -0,0 +1,14 @@
+package native_nullable;
+
+public class Describer {
+    public native String describe(String s);
+    public native int length(String s);
+
+    public String describeNothing() {
+        return describe(null);
+    }
+
+    public int lengthOfNothing() {
+        return length(null);
+    }
+}
@@ -0,0 +1,26 @@
+package native_peer;
+
+import java.util.Iterator;
+
+public class Calls {
+    public static int countAndClose(Counter counter) {
+        try (Counter c = counter) {
+            c.add(2);
+            c.add(3);
+            return c.total();
+        }
+    }
+
+    public static String describe(LabeledCounter counter) {
+        counter.add(4);
+        return counter.label("the");
+    }
+
+    public static String join(Iterator<String> iterator) {
+        StringBuilder result = new StringBuilder();
+        while (iterator.hasNext()) {
+            result.append(iterator.next());
+        }
+        return result.toString();
+    }
+}
@@ -0,0 +1,10 @@
+package native_peer;
+
+public interface Counter extends AutoCloseable {
+    void add(int amount);
+
+    int total();
+
+    @Override
+    void close();
+}
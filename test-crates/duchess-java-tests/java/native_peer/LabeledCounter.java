@@ -0,0 +1,9 @@
+package native_peer;
+
+public interface LabeledCounter extends Counter {
+    String label();
+
+    default String label(String prefix) {
+        return prefix + " " + label() + ": " + total();
+    }
+}
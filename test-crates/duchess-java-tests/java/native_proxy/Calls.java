@@ -0,0 +1,25 @@
+package native_proxy;
+
+public class Calls {
+    public static String count(Counter counter) {
+        counter.add(2);
+        counter.add(3);
+        return counter.describe("total");
+    }
+
+    public static String describe(Described described) {
+        return described.describe();
+    }
+
+    public static <T> T transformTwice(Transformer<T> transformer, T input) {
+        return transformer.transform(transformer.transform(input));
+    }
+
+    public static String shout(Shouter shouter, String input) {
+        return shouter.describe(shouter.transform(input));
+    }
+
+    public static <T> T transformNull(Transformer<T> transformer) {
+        return transformer.transform(null);
+    }
+}
@@ -0,0 +1,13 @@
+package native_proxy;
+
+public interface Counter extends Described {
+    void add(int amount);
+
+    int total();
+
+    String describe(String prefix);
+
+    default String label() {
+        return "counter of " + total();
+    }
+}
@@ -0,0 +1,9 @@
+package native_proxy;
+
+public interface Described {
+    String describe(String prefix);
+
+    default String describe() {
+        return describe("described");
+    }
+}
@@ -0,0 +1,4 @@
+package native_proxy;
+
+public interface Shouter extends Transformer<String>, Described {
+}
@@ -0,0 +1,5 @@
+package native_proxy;
+
+public interface Transformer<T> {
+    T transform(T input);
+}
@@ -0,0 +1,10 @@
+package native_reentrant;
+
+public class Countdown {
+    // Implemented in Rust, which calls back into `step` until `n` reaches zero
+    public native int countdown(int n);
+
+    public int step(int n) {
+        return countdown(n - 1) + 1;
+    }
+}
@@ -0,0 +1,7 @@
+package native_results;
+
+public class EmptyInputException extends ParseException {
+    public EmptyInputException() {
+        super("input was empty");
+    }
+}
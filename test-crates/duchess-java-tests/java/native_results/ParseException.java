@@ -0,0 +1,7 @@
+package native_results;
+
+public class ParseException extends Exception {
+    public ParseException(String message) {
+        super(message);
+    }
+}
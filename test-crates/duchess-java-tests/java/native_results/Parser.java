@@ -0,0 +1,7 @@
+package native_results;
+
+public class Parser {
+    public native String normalize(String input) throws ParseException;
+    public native int parse(String input) throws ParseException;
+    public native void check(String input) throws ParseException;
+}
@@ -0,0 +1,15 @@
+package native_subclass;
+
+public abstract class Polygon extends Shape {
+    protected Polygon() {}
+
+    public abstract int sides();
+
+    public String describe(String unit) {
+        return describe() + " " + unit;
+    }
+
+    public static String describeIn(Polygon polygon, String unit) {
+        return polygon.describe(unit);
+    }
+}
@@ -0,0 +1,21 @@
+package native_subclass;
+
+public abstract class Shape {
+    protected Shape() {}
+
+    public abstract double area();
+
+    protected abstract String kind();
+
+    public String describe() {
+        return kind() + " of area " + area();
+    }
+
+    protected String prefix() {
+        return "a ";
+    }
+
+    public static String describeAll(Shape a, Shape b) {
+        return a.describe() + " and " + b.describe();
+    }
+}
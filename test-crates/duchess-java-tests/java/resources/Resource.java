@@ -0,0 +1,27 @@
+package resources;
+
+public class Resource implements AutoCloseable {
+    private final boolean failOnClose;
+    private boolean closed;
+
+    public Resource(boolean failOnClose) {
+        this.failOnClose = failOnClose;
+    }
+
+    public void work(boolean fail) {
+        if (fail) {
+            throw new IllegalStateException("work failed");
+        }
+    }
+
+    public boolean isClosed() {
+        return closed;
+    }
+
+    public void close() {
+        closed = true;
+        if (failOnClose) {
+            throw new IllegalArgumentException("close failed");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//@ run
+
+use duchess::{java, prelude::*, Jvm};
+
+duchess::java_package! {
+    package checked;
+
+    public class checked.Fetcher {
+        public checked.Fetcher();
+        public java.lang.String fetch(java.lang.String) throws checked.MissingKeyException;
+        public java.lang.String fetchRemote(java.lang.String) throws java.io.IOException, checked.MissingKeyException;
+
+        public java.lang.String fetchFile(java.lang.String) throws java.io.FileNotFoundException;
+    }
+
+    public class checked.MissingKeyException extends java.lang.Exception {
+        public checked.MissingKeyException(java.lang.String);
+    }
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    Jvm::with(|jvm| {
+        let fetcher = checked::Fetcher::new().execute_with(jvm)?;
+
+        // The exception in the `throws` clause has to be handled
+        for (key, expected) in [
+            ("present", "value"),
+            ("absent", "MissingKeyException: no value for absent"),
+        ] {
+            let outcome = match fetcher
+                .fetch(key)
+                .assert_not_null()
+                .to_rust()
+                .catch_declared()
+                .execute_with(jvm)?
+            {
+                Ok(value) => value,
+                Err(missing) => {
+                    let message: String = missing
+                        .get_message()
+                        .assert_not_null()
+                        .to_rust()
+                        .execute_with(jvm)?;
+                    format!("MissingKeyException: {message}")
+                }
+            };
+            assert_eq!(outcome, expected);
+        }
+
+        // `java.io.IOException` is not declared in this `java_package!`, so the exceptions
+        // in the `throws` clause are caught as a `Throwable`
+        for (key, expected) in [
+            ("present", "value"),
+            ("", "connection reset"),
+            ("absent", "no value for absent"),
+        ] {
+            let outcome = match fetcher
+                .fetch_remote(key)
+                .assert_not_null()
+                .to_rust()
+                .catch_declared()
+                .execute_with(jvm)?
+            {
+                Ok(value) => value,
+                Err(exception) => exception
+                    .get_message()
+                    .assert_not_null()
+                    .to_rust()
+                    .execute_with(jvm)?,
+            };
+            assert_eq!(outcome, expected);
+        }
+
+        let contents: String = fetcher
+            .fetch_file("data.txt")
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(contents, "contents");
+
+        let exception = fetcher
+            .fetch_file("")
+            .catch_declared()
+            .execute_with(jvm)?
+            .expect_err("an empty path should throw");
+        let message: String = exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "no path");
+
+        // Undeclared exceptions are not caught
+        let exception = fetcher
+            .fetch("invalid")
+            .catch_declared()
+            .catch::<java::lang::IllegalArgumentException>()
+            .execute_with(jvm)?;
+        assert!(exception.is_err());
+
+        Ok(())
+    })
+}
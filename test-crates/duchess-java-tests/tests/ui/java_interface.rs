@@ -0,0 +1,145 @@
+//@ run
+
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use duchess::{java, prelude::*, Jvm, Proxy};
+
+duchess::java_package! {
+    package native_proxy;
+
+    public interface native_proxy.Described {
+        public abstract java.lang.String describe(java.lang.String);
+    }
+
+    public interface native_proxy.Counter extends native_proxy.Described {
+        public abstract void add(int);
+        public abstract int total();
+        public abstract java.lang.String describe(java.lang.String);
+        public default java.lang.String label();
+    }
+
+    public interface native_proxy.Transformer<T> {
+        public abstract T transform(T);
+    }
+
+    public interface native_proxy.Shouter extends native_proxy.Transformer<java.lang.String>, native_proxy.Described {
+    }
+
+    public class native_proxy.Calls {
+        public static java.lang.String count(native_proxy.Counter);
+        public static java.lang.String describe(native_proxy.Described);
+        public static <T> T transformTwice(native_proxy.Transformer<T>, T);
+        public static java.lang.String shout(native_proxy.Shouter, java.lang.String);
+        public static <T> T transformNull(native_proxy.Transformer<T>);
+    }
+}
+
+#[derive(Default)]
+struct AtomicCounter {
+    total: AtomicI32,
+}
+
+#[duchess::java_interface(native_proxy.Counter)]
+impl native_proxy::Counter for AtomicCounter {
+    fn add(&self, amount: i32) {
+        self.total.fetch_add(amount, Ordering::SeqCst);
+    }
+
+    fn total(&self) -> i32 {
+        self.total.load(Ordering::SeqCst)
+    }
+
+    fn describe(&self, prefix: String) -> String {
+        format!("{prefix}: {}", self.total())
+    }
+}
+
+struct Shout;
+
+#[duchess::java_interface(native_proxy.Transformer)]
+impl native_proxy::Transformer<java::lang::String> for Shout {
+    fn transform(&self, input: Option<String>) -> duchess::GlobalResult<String> {
+        match input {
+            Some(input) => Ok(format!("{}!", input.to_uppercase())),
+            None => Err(duchess::Error::NullDeref),
+        }
+    }
+}
+
+struct Loud;
+
+// The methods of the super-interfaces have to be implemented too, with their type arguments.
+#[duchess::java_interface(native_proxy.Shouter)]
+impl native_proxy::Shouter for Loud {
+    fn transform(&self, input: Option<String>) -> duchess::GlobalResult<String> {
+        Ok(format!("{}!", input.unwrap_or_default().to_uppercase()))
+    }
+
+    fn describe(&self, text: String) -> String {
+        format!("shouted {text}")
+    }
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    Jvm::with(|jvm| {
+        // Java calls the Rust methods...
+        let counter = Proxy::new(AtomicCounter::default());
+        let description: String =
+            native_proxy::Calls::count(counter.to_java::<native_proxy::Counter>())
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+        assert_eq!(description, "total: 5");
+
+        // ...as does Rust, through the Java object.
+        let counter = counter
+            .to_java::<native_proxy::Counter>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        counter.add(10).execute_with(jvm)?;
+        assert_eq!(counter.total().execute_with(jvm)?, 15);
+
+        // Default methods that the Rust type does not implement run their default body...
+        let label: String = counter
+            .label()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(label, "counter of 15");
+
+        // ...even if the Rust type implements an overload of them.
+        let description: String = native_proxy::Calls::describe(&counter)
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(description, "described: 15");
+
+        // Generic interfaces are implemented for the type arguments given in the `impl`.
+        let shout = Proxy::new(Shout);
+        let result: String = native_proxy::Calls::transform_twice(
+            shout.to_java::<native_proxy::Transformer<java::lang::String>>(),
+            "hi",
+        )
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+        assert_eq!(result, "HI!!");
+
+        let result: String =
+            native_proxy::Calls::shout(Proxy::new(Loud).to_java::<native_proxy::Shouter>(), "hey")
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+        assert_eq!(result, "shouted HEY!");
+
+        // Errors returned by Rust are thrown.
+        native_proxy::Calls::transform_null(
+            shout.to_java::<native_proxy::Transformer<java::lang::String>>(),
+        )
+        .catch::<java::lang::NullPointerException>()
+        .execute_with(jvm)?
+        .expect_err("error should be thrown");
+
+        Ok(())
+    })
+}
@@ -0,0 +1,125 @@
+//@ run
+
+use duchess::{java, prelude::*, Jvm, RustObject};
+
+duchess::java_package! {
+    package native_peer;
+
+    public interface native_peer.Counter extends java.lang.AutoCloseable {
+        public abstract void add(int);
+        public abstract int total();
+        public abstract void close();
+    }
+
+    public interface native_peer.LabeledCounter extends native_peer.Counter {
+        public abstract java.lang.String label();
+    }
+
+    public class native_peer.Calls {
+        public static int countAndClose(native_peer.Counter);
+        public static java.lang.String describe(native_peer.LabeledCounter);
+        public static java.lang.String join(java.util.Iterator<java.lang.String>);
+    }
+}
+
+#[derive(Default)]
+struct Counter {
+    total: i32,
+}
+
+#[duchess::java_peer(native_peer.Counter)]
+impl native_peer::Counter for Counter {
+    fn add(&mut self, amount: i32) {
+        self.total += amount;
+    }
+
+    fn total(&self) -> i32 {
+        self.total
+    }
+}
+
+struct Labeled {
+    total: i32,
+}
+
+// The methods of the super-interfaces have to be implemented too; the overload of `label` with a default
+// body does not have to be.
+#[duchess::java_peer(native_peer.LabeledCounter)]
+impl native_peer::LabeledCounter for Labeled {
+    fn add(&mut self, amount: i32) {
+        self.total += amount;
+    }
+
+    fn total(&self) -> i32 {
+        self.total
+    }
+
+    fn label(&self) -> String {
+        "labeled counter".to_string()
+    }
+}
+
+struct Words {
+    words: Vec<String>,
+}
+
+#[duchess::java_peer(java.util.Iterator)]
+impl java::util::Iterator<java::lang::String> for Words {
+    fn has_next(&self) -> bool {
+        !self.words.is_empty()
+    }
+
+    fn next(&mut self) -> String {
+        self.words.remove(0)
+    }
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    Jvm::with(|jvm| {
+        // Java calls the Rust methods, then closes the object with try-with-resources...
+        let counter = RustObject::new(Counter::default())
+            .to_java::<native_peer::Counter>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let total = native_peer::Calls::count_and_close(&counter).execute_with(jvm)?;
+        assert_eq!(total, 5);
+
+        // ...after which calls throw.
+        counter
+            .total()
+            .catch::<java::lang::IllegalStateException>()
+            .execute_with(jvm)?
+            .expect_err("closed object should throw");
+
+        // Rust can reach the Rust value of a Java object, too.
+        let counter = RustObject::new(Counter::default())
+            .to_java::<native_peer::Counter>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        counter.add(7).execute_with(jvm)?;
+        let total = RustObject::<Counter>::with(jvm, &*counter, |_, counter| counter.total)?;
+        assert_eq!(total, 7);
+        counter.close().execute_with(jvm)?;
+
+        let description: String = native_peer::Calls::describe(
+            RustObject::new(Labeled { total: 1 }).to_java::<native_peer::LabeledCounter>(),
+        )
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+        assert_eq!(description, "the labeled counter: 5");
+
+        // Generic interfaces are implemented for the type arguments given in the `impl`.
+        let words = RustObject::new(Words {
+            words: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        });
+        let joined: String =
+            native_peer::Calls::join(words.to_java::<java::util::Iterator<java::lang::String>>())
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+        assert_eq!(joined, "abc");
+
+        Ok(())
+    })
+}
@@ -0,0 +1,133 @@
+//@ run
+
+use duchess::{java, prelude::*, Jvm, Protected, Subclass};
+
+duchess::java_package! {
+    package native_subclass;
+
+    public abstract class native_subclass.Shape {
+        protected native_subclass.Shape();
+        public abstract double area();
+        protected abstract java.lang.String kind();
+        public java.lang.String describe();
+        protected java.lang.String prefix();
+        public static java.lang.String describeAll(native_subclass.Shape, native_subclass.Shape);
+    }
+
+    public abstract class native_subclass.Polygon extends native_subclass.Shape {
+        protected native_subclass.Polygon();
+        public abstract int sides();
+        public static java.lang.String describeIn(native_subclass.Polygon, java.lang.String);
+    }
+}
+
+struct Square {
+    side: f64,
+}
+
+#[duchess::java_subclass(native_subclass.Shape)]
+impl native_subclass::Shape for Square {
+    fn area(&self, _this: &native_subclass::Shape) -> f64 {
+        self.side * self.side
+    }
+
+    fn kind(&self, this: &Protected<native_subclass::Shape>) -> duchess::GlobalResult<String> {
+        // Protected methods of the superclass can be called through `this`.
+        let prefix: String = this.prefix().assert_not_null().to_rust().execute()?;
+        Ok(format!("{prefix}square"))
+    }
+}
+
+struct Broken;
+
+#[duchess::java_subclass(native_subclass.Shape)]
+impl native_subclass::Shape for Broken {
+    fn area(&self, _this: &native_subclass::Shape) -> duchess::GlobalResult<f64> {
+        Err(duchess::Error::NullDeref)
+    }
+
+    fn kind(&self, _this: &native_subclass::Shape) -> String {
+        "broken".to_string()
+    }
+}
+
+struct Triangle;
+
+// The abstract methods inherited from `Shape` have to be overridden too; among the overloads of
+// `describe`, the one taking a single argument is overridden.
+#[duchess::java_subclass(native_subclass.Polygon)]
+impl native_subclass::Polygon for Triangle {
+    fn area(&self, _this: &native_subclass::Polygon) -> f64 {
+        0.5
+    }
+
+    fn kind(&self, _this: &native_subclass::Polygon) -> String {
+        "triangle".to_string()
+    }
+
+    fn sides(&self, _this: &native_subclass::Polygon) -> i32 {
+        3
+    }
+
+    fn describe(
+        &self,
+        this: &native_subclass::Polygon,
+        unit: String,
+    ) -> duchess::GlobalResult<String> {
+        let sides = this.sides().execute()?;
+        Ok(format!("{sides} sides, area 0.5 {unit}"))
+    }
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    Jvm::with(|jvm| {
+        // Java calls the Rust methods...
+        let square = Subclass::new(Square { side: 2.0 })
+            .to_java::<native_subclass::Shape>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let description: String = square
+            .describe()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(description, "a square of area 4.0");
+
+        // ...as does Rust, through the Java object.
+        assert_eq!(square.area().execute_with(jvm)?, 4.0);
+
+        // Each Java object has its own Rust value.
+        let small = Subclass::new(Square { side: 1.0 });
+        let descriptions: String = native_subclass::Shape::describe_all(
+            &square,
+            small.to_java::<native_subclass::Shape>(),
+        )
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+        assert_eq!(
+            descriptions,
+            "a square of area 4.0 and a square of area 1.0"
+        );
+
+        let triangle = Subclass::new(Triangle)
+            .to_java::<native_subclass::Polygon>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let description: String = native_subclass::Polygon::describe_in(&triangle, "cm2")
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(description, "3 sides, area 0.5 cm2");
+
+        // Errors returned by Rust are thrown.
+        Subclass::new(Broken)
+            .to_java::<native_subclass::Shape>()
+            .describe()
+            .catch::<java::lang::NullPointerException>()
+            .execute_with(jvm)?
+            .expect_err("error should be thrown");
+
+        Ok(())
+    })
+}
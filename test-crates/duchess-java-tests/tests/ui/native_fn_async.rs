@@ -0,0 +1,185 @@
+//@ run
+
+use std::{
+    future::{Future, IntoFuture},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use duchess::{java, prelude::*, Global, RustPanicException};
+
+duchess::java_package! {
+    package native_async;
+
+    public class native_async.Greeter {
+        public native_async.Greeter();
+        public native java.util.concurrent.CompletableFuture<java.lang.String> greet(java.lang.String);
+        public native java.util.concurrent.CompletableFuture<java.lang.String> greetLater(java.lang.String);
+        public native java.util.concurrent.CompletableFuture<java.lang.String> nobody();
+        public native java.util.concurrent.CompletableFuture<java.lang.String> fail(java.lang.String);
+        public native java.util.concurrent.CompletableFuture<java.lang.String> explode();
+    }
+
+    public class native_async.GreetingException extends java.lang.Exception {
+        public native_async.GreetingException(java.lang.String);
+    }
+}
+
+#[duchess::java_function(native_async.Greeter::greet)]
+async fn greet(_this: Global<native_async::Greeter>, name: String) -> String {
+    format!("Hello, {name}!")
+}
+
+#[duchess::java_function(native_async.Greeter::greetLater)]
+fn greet_later(
+    _this: &native_async::Greeter,
+    name: &java::lang::String,
+) -> impl Future<Output = String> {
+    let name: String = name.to_rust().execute().unwrap();
+    async move {
+        // Yield once, so that the future is not complete when first polled
+        let mut yielded = false;
+        std::future::poll_fn(|cx| {
+            if yielded {
+                Poll::Ready(())
+            } else {
+                yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+        format!("Goodbye, {name}!")
+    }
+}
+
+#[duchess::java_function(native_async.Greeter::nobody)]
+async fn nobody(_this: Global<native_async::Greeter>) -> Option<String> {
+    None
+}
+
+#[duchess::java_function(native_async.Greeter::fail)]
+async fn fail(
+    _this: Global<native_async::Greeter>,
+    reason: String,
+) -> Result<String, GreetingError> {
+    Err(GreetingError {
+        message: format!("failed: {reason}"),
+    })
+}
+
+#[duchess::java_function(native_async.Greeter::explode)]
+async fn explode(_this: Global<native_async::Greeter>) -> String {
+    panic!("kaboom")
+}
+
+#[derive(Debug, duchess::ToJava)]
+#[java(native_async.GreetingException)]
+struct GreetingError {
+    message: String,
+}
+
+/// Drives `future` to completion on the current thread.
+fn block_on<F: IntoFuture>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut future = std::pin::pin!(future.into_future());
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    // Don't print the panic to stderr
+    std::panic::set_hook(Box::new(|_| {}));
+
+    duchess::Jvm::builder()
+        .link(greet::java_fn())
+        .link(greet_later::java_fn())
+        .link(nobody::java_fn())
+        .link(fail::java_fn())
+        .link(explode::java_fn())
+        .try_launch()?;
+
+    let greeter = native_async::Greeter::new().global().execute()?;
+
+    let greeting = block_on(
+        greeter
+            .greet("Ferris")
+            .assert_not_null()
+            .global()
+            .execute()?,
+    )?
+    .expect("greeting");
+    let greeting: String = (&*greeting).to_rust().execute()?;
+    assert_eq!(greeting, "Hello, Ferris!");
+
+    let greeting = block_on(
+        greeter
+            .greet_later("Ferris")
+            .assert_not_null()
+            .global()
+            .execute()?,
+    )?
+    .expect("greeting");
+    let greeting: String = (&*greeting).to_rust().execute()?;
+    assert_eq!(greeting, "Goodbye, Ferris!");
+
+    // `None` completes the Java future with `null`
+    let nobody: Option<String> = greeter.nobody().join().to_rust().execute()?;
+    assert_eq!(nobody, None);
+    let nobody = block_on(greeter.nobody().assert_not_null().global().execute()?)?;
+    assert!(nobody.is_none());
+
+    // Errors complete the Java future exceptionally
+    let exception = match block_on(
+        greeter
+            .fail("no reason")
+            .assert_not_null()
+            .global()
+            .execute()?,
+    ) {
+        Err(duchess::Error::Thrown(exception)) => exception,
+        _ => panic!("expected an exception"),
+    };
+    let message: String = exception
+        .get_message()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(message, "failed: no reason");
+
+    // ...as do panics
+    let exception = match block_on(greeter.explode().assert_not_null().global().execute()?) {
+        Err(duchess::Error::Thrown(exception)) => exception,
+        _ => panic!("expected an exception"),
+    };
+    let message: String = duchess::Jvm::with(|jvm| {
+        let Ok(exception) = exception
+            .try_downcast::<RustPanicException>()
+            .execute_with(jvm)?
+        else {
+            panic!("panic should be reported as a RustPanicException");
+        };
+        exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)
+    })?;
+    assert_eq!(message, "kaboom");
+
+    Ok(())
+}
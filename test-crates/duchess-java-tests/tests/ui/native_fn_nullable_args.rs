@@ -0,0 +1,64 @@
+//@ run
+
+use duchess::{java, prelude::*, Jvm};
+
+duchess::java_package! {
+    package native_nullable;
+
+    public class native_nullable.Describer {
+        public native_nullable.Describer();
+        public native java.lang.String describe(java.lang.String);
+        public native int length(java.lang.String);
+        public java.lang.String describeNothing();
+        public int lengthOfNothing();
+    }
+}
+
+// `null` becomes `None`
+#[duchess::java_function(native_nullable.Describer::describe)]
+fn describe(_this: &native_nullable::Describer, s: Option<String>) -> String {
+    match s {
+        Some(s) => format!("Some({s})"),
+        None => "None".to_string(),
+    }
+}
+
+// `null` is thrown as a `NullPointerException`
+#[duchess::java_function(native_nullable.Describer::length)]
+fn length(_this: &native_nullable::Describer, s: &java::lang::String) -> i32 {
+    s.length().execute().unwrap()
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    duchess::Jvm::builder()
+        .link(describe::java_fn())
+        .link(length::java_fn())
+        .try_launch()?;
+
+    Jvm::with(|jvm| {
+        let describer = native_nullable::Describer::new().execute_with(jvm)?;
+
+        let description: String = describer
+            .describe("x")
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(description, "Some(x)");
+
+        let description: String = describer
+            .describe_nothing()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(description, "None");
+
+        assert_eq!(describer.length("abc").execute_with(jvm)?, 3);
+        describer
+            .length_of_nothing()
+            .catch::<java::lang::NullPointerException>()
+            .execute_with(jvm)?
+            .expect_err("null argument should be thrown");
+
+        Ok(())
+    })
+}
@@ -0,0 +1,56 @@
+//@ run
+
+use duchess::{java, prelude::*, Jvm, RustPanicException};
+
+duchess::java_package! {
+    package native_greeting;
+
+    public class native_greeting.Native {
+        public native_greeting.Native();
+        public java.lang.String greet(java.lang.String);
+        native java.lang.String baseGreeting(java.lang.String);
+    }
+}
+
+#[duchess::java_function(native_greeting.Native::baseGreeting)]
+fn base_greeting(
+    _this: &native_greeting::Native,
+    name: &java::lang::String,
+) -> duchess::GlobalResult<String> {
+    let name: String = name.to_rust().execute()?;
+    panic!("no greeting for {name}")
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    // Capture backtraces, but don't print the panic to stderr
+    std::env::set_var("RUST_BACKTRACE", "1");
+    std::panic::set_hook(Box::new(|_| {}));
+
+    duchess::Jvm::builder()
+        .link(base_greeting::java_fn())
+        .capture_panic_backtraces(true)
+        .try_launch()?;
+
+    Jvm::with(|jvm| {
+        let exception = native_greeting::Native::new()
+            .greet("Ferris")
+            .catch::<RustPanicException>()
+            .execute_with(jvm)?
+            .expect_err("native method should have panicked");
+
+        let message: String = exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "no greeting for Ferris");
+
+        let backtrace: Option<String> = exception
+            .get_rust_backtrace()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert!(backtrace.unwrap().contains("native_fn_panic::base_greeting"));
+
+        Ok(())
+    })
+}
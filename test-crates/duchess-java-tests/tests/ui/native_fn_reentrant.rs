@@ -0,0 +1,48 @@
+//@ run
+
+use duchess::{prelude::*, Jvm};
+
+duchess::java_package! {
+    package native_reentrant;
+
+    public class native_reentrant.Countdown {
+        public native_reentrant.Countdown();
+        public native int countdown(int);
+        public int step(int);
+    }
+}
+
+#[duchess::java_function(native_reentrant.Countdown::countdown)]
+fn countdown<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    this: &native_reentrant::Countdown,
+    n: i32,
+) -> duchess::Result<'jvm, i32> {
+    if n == 0 {
+        Ok(0)
+    } else if n % 2 == 0 {
+        // Call back into Java on the environment this function was invoked with...
+        this.step(n).execute_with(jvm)
+    } else {
+        // ...or through a nested `Jvm::with`
+        Ok(this.step(n).execute().unwrap())
+    }
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    duchess::Jvm::builder()
+        .link(countdown::java_fn())
+        .try_launch()?;
+
+    Jvm::with(|jvm| {
+        let countdown = native_reentrant::Countdown::new().execute_with(jvm)?;
+        assert_eq!(countdown.countdown(10).execute_with(jvm)?, 10);
+        Ok(())
+    })?;
+
+    // Also works when the outermost call does not come from `Jvm::with`
+    let result = native_reentrant::Countdown::new().countdown(5).execute()?;
+    assert_eq!(result, 5);
+
+    Ok(())
+}
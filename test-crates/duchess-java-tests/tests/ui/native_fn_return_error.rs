@@ -0,0 +1,92 @@
+//@ run
+
+use duchess::{java, prelude::*, Jvm};
+
+duchess::java_package! {
+    package native_greeting;
+
+    public class native_greeting.Native {
+        public native_greeting.Native();
+        public java.lang.String greet(java.lang.String);
+        native java.lang.String baseGreeting(java.lang.String);
+    }
+}
+
+#[duchess::java_function(native_greeting.Native::baseGreeting)]
+fn base_greeting(
+    _this: &native_greeting::Native,
+    name: &java::lang::String,
+) -> duchess::GlobalResult<String> {
+    let name: String = name.to_rust().execute()?;
+    match &name[..] {
+        "NullDeref" => Err(duchess::Error::NullDeref),
+        "Thrown" => {
+            let exception = java::lang::IllegalArgumentException::new()
+                .upcast::<java::lang::Throwable>()
+                .global()
+                .execute()?;
+            Err(duchess::Error::Thrown(exception.into()))
+        }
+        "ThrowNew" => Jvm::with(|jvm| {
+            Err(jvm.throw_new::<java::lang::UnsupportedOperationException>("no greeting"))
+        }),
+        "ThrowOp" => java::lang::IllegalStateException::new().throw().execute(),
+        _ => Ok(format!("Hello, {name}")),
+    }
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    duchess::Jvm::builder()
+        .link(base_greeting::java_fn())
+        .try_launch()?;
+
+    Jvm::with(|jvm| {
+        // Non-exception errors are converted into a matching Java exception
+        let exception = native_greeting::Native::new()
+            .greet("NullDeref")
+            .catch::<java::lang::NullPointerException>()
+            .execute_with(jvm)?
+            .expect_err("native method should have thrown");
+        let message: String = exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "attempted to deref a null Java object pointer");
+
+        // Java exceptions are rethrown as-is
+        native_greeting::Native::new()
+            .greet("Thrown")
+            .catch::<java::lang::IllegalArgumentException>()
+            .execute_with(jvm)?
+            .expect_err("native method should have thrown");
+
+        // Exceptions thrown explicitly reach the Java caller
+        let exception = native_greeting::Native::new()
+            .greet("ThrowNew")
+            .catch::<java::lang::UnsupportedOperationException>()
+            .execute_with(jvm)?
+            .expect_err("native method should have thrown");
+        let message: String = exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "no greeting");
+
+        native_greeting::Native::new()
+            .greet("ThrowOp")
+            .catch::<java::lang::IllegalStateException>()
+            .execute_with(jvm)?
+            .expect_err("native method should have thrown");
+
+        let greeting: String = native_greeting::Native::new()
+            .greet("Ferris")
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(greeting, "Hello, Ferris, from Java");
+
+        Ok(())
+    })
+}
@@ -0,0 +1,118 @@
+//@ run
+
+use duchess::{java, prelude::*, Jvm};
+
+duchess::java_package! {
+    package native_results;
+
+    public class native_results.Parser {
+        public native_results.Parser();
+        public native java.lang.String normalize(java.lang.String) throws native_results.ParseException;
+        public native int parse(java.lang.String) throws native_results.ParseException;
+        public native void check(java.lang.String) throws native_results.ParseException;
+    }
+
+    public class native_results.ParseException extends java.lang.Exception {
+        public native_results.ParseException(java.lang.String);
+    }
+
+    public class native_results.EmptyInputException extends native_results.ParseException {
+        public native_results.EmptyInputException();
+    }
+}
+
+#[derive(Debug, duchess::ToJava)]
+#[java(native_results.ParseException)]
+enum ParseError {
+    #[java(native_results.EmptyInputException)]
+    Empty,
+
+    #[java(native_results.ParseException)]
+    Invalid { message: String },
+}
+
+fn parse_input(input: &str) -> Result<i32, ParseError> {
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    input.trim().parse().map_err(|_| ParseError::Invalid {
+        message: format!("not a number: {input:?}"),
+    })
+}
+
+#[duchess::java_function(native_results.Parser::normalize)]
+fn normalize(
+    _this: &native_results::Parser,
+    input: &java::lang::String,
+) -> Result<String, ParseError> {
+    let input: String = input.to_rust().execute().unwrap();
+    Ok(parse_input(&input)?.to_string())
+}
+
+#[duchess::java_function(native_results.Parser::parse)]
+fn parse(_this: &native_results::Parser, input: &java::lang::String) -> Result<i32, ParseError> {
+    let input: String = input.to_rust().execute().unwrap();
+    parse_input(&input)
+}
+
+#[duchess::java_function(native_results.Parser::check)]
+fn check(_this: &native_results::Parser, input: &java::lang::String) -> Result<(), ParseError> {
+    let input: String = input.to_rust().execute().unwrap();
+    parse_input(&input).map(|_| ())
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    duchess::Jvm::builder()
+        .link(normalize::java_fn())
+        .link(parse::java_fn())
+        .link(check::java_fn())
+        .try_launch()?;
+
+    Jvm::with(|jvm| {
+        let parser = native_results::Parser::new().execute_with(jvm)?;
+
+        let normalized: String = parser
+            .normalize(" 22 ")
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(normalized, "22");
+        assert_eq!(parser.parse("44").execute_with(jvm)?, 44);
+        parser.check("66").execute_with(jvm)?;
+
+        // Object return type: the error is thrown as the matching exception subclass
+        let exception = parser
+            .normalize("")
+            .catch::<native_results::EmptyInputException>()
+            .execute_with(jvm)?
+            .expect_err("empty input should throw");
+        let message: String = exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "input was empty");
+
+        // Scalar return type
+        let exception = parser
+            .parse("x")
+            .catch::<native_results::ParseException>()
+            .execute_with(jvm)?
+            .expect_err("invalid input should throw");
+        let message: String = exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "not a number: \"x\"");
+
+        // Void return type
+        parser
+            .check("y")
+            .catch::<native_results::ParseException>()
+            .execute_with(jvm)?
+            .expect_err("invalid input should throw");
+
+        Ok(())
+    })
+}
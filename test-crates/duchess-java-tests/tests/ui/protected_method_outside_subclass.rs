@@ -0,0 +1,20 @@
+//@compile-flags: --crate-type lib
+
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package native_subclass;
+
+    public abstract class native_subclass.Shape {
+        protected native_subclass.Shape();
+        public abstract double area();
+        protected abstract java.lang.String kind();
+        public java.lang.String describe();
+        protected java.lang.String prefix();
+    }
+}
+
+// Protected methods are only mirrored on the `Protected` view that Rust subclasses get as `this`.
+pub fn prefix(shape: &native_subclass::Shape) -> duchess::GlobalResult<String> {
+    shape.prefix().assert_not_null().to_rust().execute() //~ ERROR: no method named `prefix` found
+}
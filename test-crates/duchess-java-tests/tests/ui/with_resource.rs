@@ -0,0 +1,52 @@
+//@ run
+
+use duchess::{java, java::ArrayExt, prelude::*, Error, Jvm};
+
+duchess::java_package! {
+    package resources;
+
+    public class resources.Resource implements java.lang.AutoCloseable {
+        public resources.Resource(boolean);
+        public void work(boolean);
+        public boolean isClosed();
+        public void close();
+    }
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    Jvm::with(|jvm| {
+        // The resource is closed after the body succeeds
+        let resource = resources::Resource::new(false).execute_with(jvm)?;
+        (&resource)
+            .with_resource(|resource, jvm| resource.work(false).execute_with(jvm))
+            .execute_with(jvm)?;
+        assert!(resource.is_closed().execute_with(jvm)?);
+
+        // An exception from `close()` is returned if the body succeeded...
+        let result = resources::Resource::new(true)
+            .with_resource(|resource, jvm| resource.work(false).execute_with(jvm))
+            .catch::<java::lang::IllegalArgumentException>()
+            .execute_with(jvm)?;
+        assert!(result.is_err());
+
+        // ...and suppressed by the exception from the body otherwise
+        let result = resources::Resource::new(true)
+            .with_resource(|resource, jvm| resource.work(true).execute_with(jvm))
+            .execute_with(jvm);
+        let Err(Error::Thrown(thrown)) = result else {
+            panic!("expected an exception, got {result:?}");
+        };
+        let snapshot = thrown.snapshot().unwrap();
+        assert_eq!(snapshot.class_name(), "java.lang.IllegalStateException");
+        assert_eq!(snapshot.suppressed().len(), 1);
+        assert_eq!(snapshot.suppressed()[0].message(), Some("close failed"));
+
+        let suppressed = thrown
+            .get_suppressed()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!((&suppressed).length().execute_with(jvm)?, 1);
+
+        Ok(())
+    })
+}
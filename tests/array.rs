@@ -0,0 +1,192 @@
+use duchess::{java, java::ArrayExt, prelude::*, IntoRust, Jvm};
+
+#[test]
+fn critical_borrows_in_place() {
+    Jvm::with(|jvm| {
+        let array = [1i32, 2, 3, 4].execute_with(jvm)?;
+        // SAFETY: the closures do not call into the JVM, and no other thread has the array
+        let sum = unsafe { array.with_critical(jvm, |ints| ints.iter().sum::<i32>()) }?;
+        assert_eq!(sum, 10);
+
+        unsafe { array.with_critical_mut(jvm, |ints| ints.iter_mut().for_each(|i| *i *= 2)) }?;
+        let doubled: Vec<i32> = (&*array).into_rust(jvm)?;
+        assert_eq!(doubled, [2, 4, 6, 8]);
+
+        let empty = [0f64; 0].execute_with(jvm)?;
+        assert_eq!(
+            unsafe { empty.with_critical(jvm, |doubles| doubles.len()) }?,
+            0
+        );
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn elements_commit_on_drop() {
+    Jvm::with(|jvm| {
+        let array = [1i64, 2, 3].execute_with(jvm)?;
+        // SAFETY: the array is local to this thread and only accessed through `elements` while it is alive
+        let mut elements = unsafe { array.elements(jvm) }?;
+        elements[0] = 10;
+
+        // The JVM stays usable while the elements are borrowed
+        let length = (&array).length().execute_with(elements.jvm())?;
+        assert_eq!(length, 3);
+
+        drop(elements);
+        let longs: Vec<i64> = (&*array).into_rust(jvm)?;
+        assert_eq!(longs, [10, 2, 3]);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn elements_commit_and_abort() {
+    Jvm::with(|jvm| {
+        let array: duchess::Local<java::Array<u16>> = [0u16; 2].execute_with(jvm)?;
+        // SAFETY: the array is local to this thread and only accessed through `elements` while it is alive
+        let mut elements = unsafe { array.elements(jvm) }?;
+        elements[0] = 'a' as u16;
+        elements.commit();
+        elements[1] = 'b' as u16;
+        let is_copy = elements.is_copy();
+        elements.abort();
+
+        let chars: Vec<u16> = (&*array).into_rust(jvm)?;
+        assert_eq!(chars[0], 'a' as u16);
+        if is_copy {
+            // Uncommitted modifications to a copy are discarded
+            assert_eq!(chars[1], 0);
+        }
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn primitive_elements_and_regions() {
+    Jvm::with(|jvm| {
+        let array = [0i32; 6].execute_with(jvm)?;
+        (&array).set(1, 7i32).execute_with(jvm)?;
+        assert_eq!((&array).get(1).execute_with(jvm)?, 7);
+
+        (&array).write_region(2, &[1, 2, 3]).execute_with(jvm)?;
+        let mut buffer = [0; 4];
+        (&array).read_region(1, &mut buffer).execute_with(jvm)?;
+        assert_eq!(buffer, [7, 1, 2, 3]);
+
+        // Regions must lie entirely within the array
+        (&array)
+            .read_region(4, &mut buffer)
+            .catch::<java::lang::ArrayIndexOutOfBoundsException>()
+            .execute_with(jvm)?
+            .expect_err("region is out of bounds");
+        (&array)
+            .get(-1)
+            .catch::<java::lang::ArrayIndexOutOfBoundsException>()
+            .execute_with(jvm)?
+            .expect_err("index is out of bounds");
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn chunked_iteration() {
+    Jvm::with(|jvm| {
+        let bytes: Vec<i8> = (0..10).collect();
+        let array = bytes.execute_with(jvm)?;
+
+        let chunks = (&array).chunks(4).execute_with(jvm)?;
+        assert_eq!(chunks.size_hint(), (3, Some(3)));
+        let chunks = chunks.with_jvm(jvm).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(chunks, [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+
+        let empty = [0f64; 0].execute_with(jvm)?;
+        let mut empty_chunks = (&empty).chunks(4).execute_with(jvm)?;
+        assert!(empty_chunks.next(jvm).is_none());
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn object_arrays_round_trip() {
+    Jvm::with(|jvm| {
+        let words = vec!["hello".to_string(), "world".to_string()];
+        let array = words
+            .to_java::<java::Array<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!((&array).length().execute_with(jvm)?, 2);
+        let back: Vec<String> = (&*array).into_rust(jvm)?;
+        assert_eq!(back, words);
+
+        let matrix = vec![vec![1i32, 2], vec![], vec![3]];
+        let array = matrix
+            .to_java::<java::Array<java::Array<i32>>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let back: Vec<Vec<i32>> = (&*array).into_rust(jvm)?;
+        assert_eq!(back, matrix);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn object_array_elements() {
+    Jvm::with(|jvm| {
+        let array = vec![None, Some("b".to_string())]
+            .to_java::<java::Array<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert!((&array).get(0).execute_with(jvm)?.is_none());
+
+        // Arrays with null elements can't be converted to a `Vec<String>`
+        let err = IntoRust::<Vec<String>>::into_rust(&*array, jvm).unwrap_err();
+        assert!(matches!(err, duchess::Error::NullDeref));
+
+        (&array).set(0, "a").execute_with(jvm)?;
+        let first: String = (&array)
+            .get(0)
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(first, "a");
+
+        (&array)
+            .get(2)
+            .catch::<java::lang::ArrayIndexOutOfBoundsException>()
+            .execute_with(jvm)?
+            .expect_err("index is out of bounds");
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn suppressed_exceptions() {
+    Jvm::with(|jvm| {
+        let error = java::lang::Throwable::new().execute_with(jvm)?;
+        let suppressed = java::lang::Throwable::new().execute_with(jvm)?;
+        error.add_suppressed(&suppressed).execute_with(jvm)?;
+
+        let all = error.get_suppressed().assert_not_null().execute_with(jvm)?;
+        assert_eq!((&all).length().execute_with(jvm)?, 1);
+        let first = (&all).get(0).assert_not_null().execute_with(jvm)?;
+        assert!(first.equals(&suppressed).execute_with(jvm)?);
+
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,121 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use duchess::{java, prelude::*, Jvm};
+
+struct Bytes {
+    bytes: Vec<u8>,
+    dropped: Arc<AtomicBool>,
+}
+
+impl AsMut<[u8]> for Bytes {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
+
+impl Drop for Bytes {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn java_views_rust_bytes() {
+    Jvm::with(|jvm| {
+        let buffer = jvm.new_direct_byte_buffer(vec![1u8, 2, 3])?;
+        assert!(buffer.is_direct().execute_with(jvm)?);
+        assert_eq!(buffer.capacity().execute_with(jvm)?, 3);
+        assert_eq!(buffer.get(1).execute_with(jvm)?, 2);
+
+        // Writes from Java are visible from Rust, and the other way around
+        buffer.put(0, 42i8).execute_with(jvm)?;
+        // SAFETY: no Java code accesses the buffers while they are borrowed
+        unsafe {
+            assert_eq!(jvm.direct_buffer(&buffer)?, [42, 2, 3]);
+            jvm.direct_buffer_mut(&buffer)?[2] = 7;
+        }
+        assert_eq!(buffer.get(2).execute_with(jvm)?, 7);
+
+        // Empty buffers work too
+        let empty = jvm.new_direct_byte_buffer(Vec::new())?;
+        assert_eq!(unsafe { jvm.direct_buffer(&empty) }?, []);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn too_many_bytes() {
+    Jvm::with(|jvm| {
+        // The pages are never touched, so this does not actually use 2 GiB of memory
+        let bytes = vec![0u8; i32::MAX as usize + 1];
+        match jvm.new_direct_byte_buffer(bytes) {
+            Err(duchess::Error::SliceTooLong(len)) => assert_eq!(len, i32::MAX as usize + 1),
+            Err(err) => panic!("unexpected error: {err}"),
+            Ok(_) => panic!("created a buffer larger than `i32::MAX`"),
+        }
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn rust_borrows_java_buffer() {
+    Jvm::with(|jvm| {
+        let buffer = java::nio::ByteBuffer::allocate_direct(4)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        // SAFETY: no Java code accesses the buffers while they are borrowed
+        unsafe { jvm.direct_buffer_mut(&buffer) }?.copy_from_slice(b"duck");
+        assert_eq!(buffer.get(3).execute_with(jvm)?, b'k' as i8);
+
+        // Read-only buffers can only be borrowed immutably
+        let read_only = buffer
+            .as_read_only_buffer()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(unsafe { jvm.direct_buffer(&read_only) }?, b"duck");
+        let err = unsafe { jvm.direct_buffer_mut(&read_only) }.unwrap_err();
+        assert!(matches!(err, duchess::Error::Thrown(_)));
+
+        // Heap buffers cannot be borrowed at all
+        let heap = java::nio::ByteBuffer::allocate(4)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let err = unsafe { jvm.direct_buffer(&heap) }.unwrap_err();
+        assert!(matches!(err, duchess::Error::Thrown(_)));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn garbage_collection_drops_bytes() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let bytes = Bytes {
+        bytes: vec![0; 16],
+        dropped: dropped.clone(),
+    };
+    Jvm::with(|jvm| {
+        let buffer = jvm.new_direct_byte_buffer(bytes)?;
+        assert_eq!(buffer.capacity().execute_with(jvm)?, 16);
+        Ok(())
+    })
+    .unwrap();
+    assert!(!dropped.load(Ordering::SeqCst));
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !dropped.load(Ordering::SeqCst) {
+        assert!(Instant::now() < deadline, "bytes were not dropped");
+        java::lang::System::gc().execute().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,38 @@
+use duchess::{java, prelude::*, Jvm};
+
+#[test]
+fn catch_declared_io_exception() {
+    Jvm::with(|jvm| {
+        let reader = java::io::StringReader::new("ab").execute_with(jvm)?;
+        let first = reader.read().catch_declared().execute_with(jvm)?;
+        assert_eq!(first.ok(), Some('a' as i32));
+
+        reader.close().execute_with(jvm)?;
+        let io_exception = reader
+            .read()
+            .catch_declared()
+            .execute_with(jvm)?
+            .expect_err("reading a closed reader should throw");
+        let message: String = io_exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "Stream closed");
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn catch_declared_without_throws() {
+    Jvm::with(|jvm| {
+        // `size()` declares no exceptions, so there is nothing to handle
+        let list = java::util::ArrayList::<java::lang::Object>::new().execute_with(jvm)?;
+        let Ok(size) = list.size().catch_declared().execute_with(jvm)?;
+        assert_eq!(size, 0);
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,216 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use duchess::{
+    java::{
+        self,
+        lang::String as JavaString,
+        util::function::{BiConsumer, BiFunction, Consumer, Function, Predicate, Supplier},
+    },
+    prelude::*,
+    Closure, Jvm, RustPanicException,
+};
+
+#[test]
+fn map_callbacks() {
+    let map = java::util::HashMap::<JavaString, JavaString>::new()
+        .global()
+        .execute()
+        .unwrap();
+
+    let append = Closure::new(|old: String, new: String| format!("{old}, {new}"));
+    let append = append.to_java::<BiFunction<_, _, _>>();
+    let merged: Option<String> = map
+        .merge("greeting", "hello", append)
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(merged.as_deref(), Some("hello"));
+    let merged: Option<String> = map
+        .merge("greeting", "world", append)
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(merged.as_deref(), Some("hello, world"));
+
+    let shout = Closure::new(|key: String| key.to_uppercase());
+    let computed: Option<String> = map
+        .compute_if_absent("name", shout.to_java::<Function<_, _>>())
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(computed.as_deref(), Some("NAME"));
+
+    let entries = Arc::new(Mutex::new(vec![]));
+    let collect = Closure::new({
+        let entries = entries.clone();
+        move |key: String, value: String| entries.lock().unwrap().push((key, value))
+    });
+    map.for_each(collect.to_java::<BiConsumer<_, _>>())
+        .execute()
+        .unwrap();
+
+    let mut entries = entries.lock().unwrap().clone();
+    entries.sort();
+    assert_eq!(
+        entries,
+        [
+            ("greeting".to_string(), "hello, world".to_string()),
+            ("name".to_string(), "NAME".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn functional_interfaces() {
+    Jvm::with(|jvm| {
+        let length = Closure::new(|s: String| s.len().to_string());
+        let length = length
+            .to_java::<Function<JavaString, JavaString>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let result: String = length
+            .apply("four")
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(result, "4");
+
+        let supplier = Closure::new(|| "supplied".to_string());
+        let supplier = supplier
+            .to_java::<Supplier<JavaString>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let result: Option<String> = supplier.get().to_rust().execute_with(jvm)?;
+        assert_eq!(result.as_deref(), Some("supplied"));
+
+        let is_empty = Closure::new(|s: String| s.is_empty());
+        let is_empty = is_empty
+            .to_java::<Predicate<JavaString>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert!(is_empty.test("").execute_with(jvm)?);
+        assert!(!is_empty.test("x").execute_with(jvm)?);
+
+        let seen = Arc::new(Mutex::new(vec![]));
+        let consumer = Closure::new({
+            let seen = seen.clone();
+            move |s: String| seen.lock().unwrap().push(s)
+        });
+        let consumer = consumer
+            .to_java::<Consumer<JavaString>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        consumer.accept("consumed").execute_with(jvm)?;
+        assert_eq!(*seen.lock().unwrap(), ["consumed"]);
+
+        let runs = Arc::new(Mutex::new(0));
+        let runnable = Closure::new({
+            let runs = runs.clone();
+            move || *runs.lock().unwrap() += 1
+        });
+        let runnable = runnable
+            .to_java::<java::lang::Runnable>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        runnable.run().execute_with(jvm)?;
+        runnable.run().execute_with(jvm)?;
+        assert_eq!(*runs.lock().unwrap(), 2);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn closure_errors_are_thrown() {
+    Jvm::with(|jvm| {
+        let parse = Closure::new(|s: String| -> duchess::GlobalResult<String> {
+            match s.parse::<i32>() {
+                Ok(i) => Ok((i * 2).to_string()),
+                Err(_) => Err(duchess::Error::NullDeref),
+            }
+        });
+        let parse = parse
+            .to_java::<Function<JavaString, JavaString>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        parse
+            .apply("x")
+            .catch::<java::lang::NullPointerException>()
+            .execute_with(jvm)?
+            .expect_err("error should be thrown");
+
+        // Passing `null` where the closure expects a `String` also throws: `compute` passes
+        // `null` as the old value of an absent key
+        let map = java::util::HashMap::<JavaString, JavaString>::new().execute_with(jvm)?;
+        let concat = Closure::new(|key: String, old: String| format!("{key}{old}"));
+        map.compute("absent", concat.to_java::<BiFunction<_, _, _>>())
+            .catch::<java::lang::NullPointerException>()
+            .execute_with(jvm)?
+            .expect_err("null argument should be thrown");
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn closure_panics_are_thrown() {
+    Jvm::with(|jvm| {
+        let explode = Closure::new(|| -> String { panic!("kaboom") });
+        let explode = explode
+            .to_java::<java::util::concurrent::Callable<JavaString>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let exception = explode
+            .call()
+            .catch::<RustPanicException>()
+            .execute_with(jvm)?
+            .expect_err("panic should be thrown");
+        let message: String = exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "kaboom");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn garbage_collection_drops_closure() {
+    struct SetOnDrop(Arc<AtomicBool>);
+
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let guard = SetOnDrop(dropped.clone());
+    let closure = Closure::new(move || {
+        let _ = &guard;
+    })
+    .to_java::<java::lang::Runnable>()
+    .assert_not_null()
+    .global()
+    .execute()
+    .unwrap();
+    drop(closure);
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !dropped.load(Ordering::SeqCst) {
+        assert!(Instant::now() < deadline, "closure was not dropped");
+        java::lang::System::gc().execute().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use duchess::{java, prelude::*, Jvm};
+
+#[test]
+fn lists_round_trip() {
+    Jvm::with(|jvm| {
+        let numbers = vec![3i32, 1, 2];
+        let list = numbers
+            .to_java::<java::util::List<java::lang::Integer>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let back: Vec<i32> = (&*list).to_rust().execute_with(jvm)?;
+        assert_eq!(back, numbers);
+
+        // Any collection can be collected into a set
+        let set: BTreeSet<i32> = (&*list).to_rust().execute_with(jvm)?;
+        assert_eq!(set, BTreeSet::from([1, 2, 3]));
+
+        let deque = VecDeque::from(["a".to_string(), "b".to_string()]);
+        let list = deque
+            .to_java::<java::util::ArrayList<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let back: Vec<String> = (&*list).to_rust().execute_with(jvm)?;
+        assert!(back.iter().eq(&deque));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn sets_round_trip() {
+    Jvm::with(|jvm| {
+        let words: HashSet<String> = ["duck", "goose"].map(String::from).into();
+        let set = words
+            .to_java::<java::util::Set<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(set.size().execute_with(jvm)?, 2);
+        let back: HashSet<String> = (&*set).to_rust().execute_with(jvm)?;
+        assert_eq!(back, words);
+
+        let sorted = BTreeSet::from([5i64, -1, 3]);
+        let set = sorted
+            .to_java::<java::util::TreeSet<java::lang::Long>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let first: i64 = set.first().assert_not_null().to_rust().execute_with(jvm)?;
+        assert_eq!(first, -1);
+        let back: Vec<i64> = (&*set).to_rust().execute_with(jvm)?;
+        assert_eq!(back, [-1, 3, 5]);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn maps_round_trip() {
+    Jvm::with(|jvm| {
+        let ages = HashMap::from([("ada".to_string(), 36i32), ("grace".to_string(), 85)]);
+        let map = ages
+            .to_java::<java::util::Map<java::lang::String, java::lang::Integer>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let back: HashMap<String, i32> = (&*map).to_rust().execute_with(jvm)?;
+        assert_eq!(back, ages);
+
+        let sorted = BTreeMap::from([(2i32, "two".to_string()), (1, "one".to_string())]);
+        let map = sorted
+            .to_java::<java::util::TreeMap<java::lang::Integer, java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let first: i32 = map
+            .first_key()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(first, 1);
+        let back: BTreeMap<i32, String> = (&*map).to_rust().execute_with(jvm)?;
+        assert_eq!(back, sorted);
+
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,116 @@
+use std::error::Error as _;
+
+use duchess::{java, prelude::*, Error, ExceptionSnapshot, Jvm};
+
+fn chain(snapshot: &ExceptionSnapshot) -> Vec<&str> {
+    std::iter::successors(Some(snapshot), |&s| s.cause())
+        .map(|s| s.class_name())
+        .collect()
+}
+
+#[test]
+fn snapshot_of_causes_and_suppressed_exceptions() {
+    let error = Jvm::with(|jvm| {
+        let cause = java::lang::UnsupportedOperationException::new("boom").execute_with(jvm)?;
+        let suppressed = java::lang::Throwable::new().execute_with(jvm)?;
+        cause.add_suppressed(&suppressed).execute_with(jvm)?;
+
+        let future = java::util::concurrent::CompletableFuture::<java::lang::Object>::new()
+            .execute_with(jvm)?;
+        future.complete_exceptionally(&cause).execute_with(jvm)?;
+        future.join().execute_with(jvm)?;
+        Ok(())
+    })
+    .unwrap_err();
+
+    // The JVM isn't needed to inspect or format the error
+    let Error::Thrown(thrown) = &error else {
+        panic!("expected an exception, got {error}");
+    };
+    let snapshot = thrown.snapshot().expect("snapshot was taken");
+    assert_eq!(
+        chain(snapshot),
+        [
+            "java.util.concurrent.CompletionException",
+            "java.lang.UnsupportedOperationException"
+        ]
+    );
+    assert!(snapshot
+        .stack_trace()
+        .iter()
+        .any(|frame| frame.contains("java.util.concurrent.CompletableFuture.")));
+
+    let cause = snapshot.cause().unwrap();
+    assert_eq!(cause.message(), Some("boom"));
+    assert_eq!(cause.suppressed().len(), 1);
+    assert_eq!(cause.suppressed()[0].class_name(), "java.lang.Throwable");
+
+    assert!(error
+        .to_string()
+        .starts_with("Java invocation threw: java.util.concurrent.CompletionException"));
+    assert_eq!(
+        error.source().unwrap().to_string(),
+        "java.lang.UnsupportedOperationException: boom"
+    );
+
+    let stack_trace = format!("{snapshot:#}");
+    assert!(stack_trace.contains("\n\tat "));
+    assert!(stack_trace.contains("\nCaused by: java.lang.UnsupportedOperationException: boom"));
+    assert!(stack_trace.contains("\n\tSuppressed: java.lang.Throwable"));
+}
+
+#[test]
+fn format_error_inside_jvm_with() {
+    Jvm::with(|jvm| {
+        let list = java::util::ArrayList::<java::lang::Object>::new().execute_with(jvm)?;
+        let error = list.get(0).execute_with(jvm).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Java invocation threw: java.lang.IndexOutOfBoundsException"));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn format_error_on_another_thread() {
+    let error = Jvm::with(|jvm| {
+        let list = java::util::ArrayList::<java::lang::Object>::new().execute_with(jvm)?;
+        list.get(0).execute_with(jvm)?;
+        Ok(())
+    })
+    .unwrap_err();
+
+    // Formatting uses the snapshot taken when the exception was caught, without attaching this thread
+    let message = std::thread::spawn(move || error.to_string()).join().unwrap();
+    assert!(message.starts_with("Java invocation threw: java.lang.IndexOutOfBoundsException"));
+}
+
+#[test]
+fn circular_causes() {
+    let error = Jvm::with(|jvm| {
+        let first = java::lang::Throwable::new().execute_with(jvm)?;
+        let second = java::lang::Throwable::new().execute_with(jvm)?;
+        first.init_cause(&second).execute_with(jvm)?;
+        second.init_cause(&first).execute_with(jvm)?;
+
+        let future = java::util::concurrent::CompletableFuture::<java::lang::Object>::new()
+            .execute_with(jvm)?;
+        future.complete_exceptionally(&first).execute_with(jvm)?;
+        future.join().execute_with(jvm)?;
+        Ok(())
+    })
+    .unwrap_err();
+
+    let Error::Thrown(thrown) = &error else {
+        panic!("expected an exception, got {error}");
+    };
+    assert_eq!(
+        chain(thrown.snapshot().unwrap()),
+        [
+            "java.util.concurrent.CompletionException",
+            "java.lang.Throwable",
+            "java.lang.Throwable"
+        ]
+    );
+}
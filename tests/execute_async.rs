@@ -0,0 +1,49 @@
+use duchess::{java, prelude::*};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn execute_async_to_rust() {
+    let value: String = "hello"
+        .to_java::<java::lang::String>()
+        .assert_not_null()
+        .to_rust()
+        .execute_async()
+        .await
+        .unwrap();
+    assert_eq!(value, "hello");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn execute_async_concurrently() {
+    let tasks: Vec<_> = (0..32)
+        .map(|_| {
+            tokio::spawn(
+                java::util::ArrayList::<java::lang::String>::new()
+                    .size()
+                    .execute_async(),
+            )
+        })
+        .collect();
+    for task in tasks {
+        assert_eq!(task.await.unwrap().unwrap(), 0);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn execute_async_exception() {
+    let result = java::util::ArrayList::<java::lang::String>::new()
+        .get(0)
+        .global()
+        .execute_async()
+        .await;
+    let Err(duchess::Error::Thrown(exception)) = result else {
+        panic!("expected an exception");
+    };
+    // The exception is a global reference, so it can be used from this (unattached) thread too
+    let description: String = exception
+        .to_string()
+        .assert_not_null()
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert!(description.starts_with("java.lang.IndexOutOfBoundsException"));
+}
@@ -0,0 +1,90 @@
+use duchess::{java, prelude::*, Jvm};
+
+#[test]
+fn iterate_over_list() {
+    Jvm::with(|jvm| {
+        let list = vec!["a".to_string(), "b".to_string()]
+            .to_java::<java::util::List<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        let mut strings = vec![];
+        let mut elements = list.iter().execute_with(jvm)?;
+        while let Some(element) = elements.next(jvm) {
+            let element = element?.expect("no nulls");
+            strings.push((&*element).to_rust().execute_with(jvm)?);
+        }
+        assert_eq!(strings, ["a", "b"]);
+
+        let strings = list
+            .iter()
+            .execute_with(jvm)?
+            .to_rust()
+            .with_jvm(jvm)
+            .collect::<Result<Vec<String>, _>>()?;
+        assert_eq!(strings, ["a", "b"]);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn iterate_lazily_over_stream() {
+    Jvm::with(|jvm| {
+        let numbers: Vec<i64> = (0..100_000).collect();
+        let list = numbers
+            .to_java::<java::util::List<java::lang::Long>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        // Only the elements that are needed are pulled out of the stream
+        let mut iter = list
+            .stream()
+            .assert_not_null()
+            .iter()
+            .execute_with(jvm)?
+            .to_rust::<i64>();
+        assert_eq!(iter.next(jvm).transpose()?, Some(0));
+        assert_eq!(iter.next(jvm).transpose()?, Some(1));
+
+        // Converting each element in its own frame lets the iteration run for as long as needed
+        let sum = iter
+            .with_jvm(jvm)
+            .try_fold(0, |sum, n| n.map(|n| sum + n))?;
+        assert_eq!(sum, numbers.iter().sum::<i64>() - 1);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn iterate_over_map_entries() {
+    Jvm::with(|jvm| {
+        let map = std::collections::BTreeMap::from([(1i32, "one".to_string())])
+            .to_java::<java::util::Map<java::lang::Integer, java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        let entries = map.entry_set().assert_not_null().execute_with(jvm)?;
+        let mut entries = entries.iter().execute_with(jvm)?;
+        while let Some(entry) = entries.next(jvm) {
+            let entry = entry?.expect("no nulls");
+            let key: i32 = entry
+                .get_key()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            let value: String = entry
+                .get_value()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            assert_eq!((key, value.as_str()), (1, "one"));
+        }
+
+        Ok(())
+    })
+    .unwrap();
+}
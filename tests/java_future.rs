@@ -0,0 +1,58 @@
+use std::{future::IntoFuture, time::Duration};
+
+use duchess::{java, prelude::*, Global, Jvm};
+
+fn new_future() -> Global<java::util::concurrent::CompletableFuture<java::lang::String>> {
+    java::util::concurrent::CompletableFuture::new()
+        .global()
+        .execute()
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn await_completed_future() {
+    let future = new_future();
+    assert!(future.complete("done").execute().unwrap());
+    let value = future
+        .await
+        .unwrap()
+        .expect("future completed with a value");
+    let value: String = (&*value).to_rust().execute().unwrap();
+    assert_eq!(value, "done");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn await_future_completed_later() {
+    let future = new_future();
+    let completer = Jvm::with(|jvm| Ok(jvm.global(&*future))).unwrap();
+    let task = tokio::spawn(future.into_future());
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!task.is_finished());
+    std::thread::spawn(move || completer.complete("later").execute().unwrap())
+        .join()
+        .unwrap();
+
+    let value = task
+        .await
+        .unwrap()
+        .unwrap()
+        .expect("future completed with a value");
+    let value: String = (&*value).to_rust().execute().unwrap();
+    assert_eq!(value, "later");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn await_failed_future() {
+    let future = new_future();
+    let exception = java::lang::IllegalStateException::new()
+        .global()
+        .execute()
+        .unwrap();
+    assert!(future.complete_exceptionally(&exception).execute().unwrap());
+
+    let Err(duchess::Error::Thrown(thrown)) = future.await else {
+        panic!("expected the future to fail");
+    };
+    assert!(thrown.equals(&exception).execute().unwrap());
+}
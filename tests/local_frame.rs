@@ -0,0 +1,101 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use duchess::{java, plumbing::ToJavaImpl, prelude::*, Jvm, Local, RustObject};
+
+#[test]
+fn local_frame_promotes_result() {
+    let value: String = Jvm::with(|jvm| {
+        let promoted = jvm.with_local_frame(16, |jvm| {
+            let _discarded = java::lang::Object::new().execute_with(jvm)?;
+            "promoted".to_java::<java::lang::String>().execute_with(jvm)
+        })?;
+        let promoted = promoted.unwrap();
+        (&*promoted).to_rust().execute_with(jvm)
+    })
+    .unwrap();
+    assert_eq!(value, "promoted");
+}
+
+#[test]
+fn local_frame_promotes_exception() {
+    Jvm::with(|jvm| {
+        let result = jvm.with_local_frame(16, |jvm| {
+            let exception = java::lang::Throwable::new().execute_with(jvm)?;
+            Err::<Option<Local<'_, java::lang::Object>>, _>(duchess::Error::Thrown(
+                exception.into(),
+            ))
+        });
+        let Err(duchess::Error::Thrown(exception)) = result else {
+            panic!("expected an exception, got {result:?}");
+        };
+
+        // The exception is still usable after its frame was popped
+        let message: Option<String> = exception.get_message().to_rust().execute_with(jvm)?;
+        assert_eq!(message, None);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn collection_elements_release_their_local_references() {
+    // Without a frame per element, the local references left behind by each element would only be released
+    // once the thread detaches, so the peers could never be collected
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let elements: Vec<_> = (0..100).map(|_| LeakyElement(dropped.clone())).collect();
+    Jvm::with(|jvm| {
+        let list = elements
+            .to_java::<java::util::ArrayList<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(list.size().execute_with(jvm)?, 100);
+
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while dropped.load(Ordering::SeqCst) < 100 {
+            assert!(
+                Instant::now() < deadline,
+                "local references were not released"
+            );
+            java::lang::System::gc().execute_with(jvm)?;
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Converts to a Java string, leaving behind a local reference to a peer, like a conversion that makes raw JNI
+/// calls of its own. The Rust value of the peer increments the counter when it is dropped.
+struct LeakyElement(Arc<AtomicUsize>);
+
+impl ToJavaImpl<java::lang::String> for LeakyElement {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> duchess::Result<'jvm, Option<Local<'jvm, java::lang::String>>> {
+        let peer = RustObject::new(CountDrop(rust.0.clone()))
+            .to_java::<java::lang::Runnable>()
+            .execute_with(jvm)?;
+        std::mem::forget(peer);
+        "element".to_java::<java::lang::String>().execute_with(jvm)
+    }
+}
+
+struct CountDrop(Arc<AtomicUsize>);
+
+impl Drop for CountDrop {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[duchess::java_peer(java.lang.Runnable)]
+impl java::lang::Runnable for CountDrop {
+    fn run(&self) {}
+}
@@ -0,0 +1,50 @@
+use duchess::{java, prelude::*, Jvm};
+
+#[test]
+fn nested_jvm_with() {
+    let outer: String = Jvm::with(|jvm| {
+        let outer = "outer"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        // Nested `Jvm::with` calls (including ones made through `execute`) share the thread's attachment
+        let inner: String = "inner"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        let nested: String = Jvm::with(|jvm| {
+            let nested = Jvm::with(|jvm| {
+                "nested"
+                    .to_java::<java::lang::String>()
+                    .assert_not_null()
+                    .global()
+                    .execute_with(jvm)
+            })
+            .unwrap();
+            (&*nested).to_rust().execute_with(jvm)
+        })
+        .unwrap();
+        assert_eq!((inner.as_str(), nested.as_str()), ("inner", "nested"));
+
+        // Local refs from the outer frame are still usable
+        (&*outer).to_rust().execute_with(jvm)
+    })
+    .unwrap();
+    assert_eq!(outer, "outer");
+}
+
+#[test]
+fn nested_jvm_with_error() {
+    // An error in a nested call is reported to its caller without disturbing the outer call
+    Jvm::with(|jvm| {
+        let nested: duchess::GlobalResult<()> =
+            Jvm::with(|_jvm| Err(duchess::Error::JvmInternal("nested".into())));
+        assert!(matches!(nested, Err(duchess::Error::JvmInternal(_))));
+        java::lang::Object::new().execute_with(jvm)?;
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,200 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use duchess::{java, prelude::*, Global, Jvm, RustObject};
+
+struct Words {
+    words: Vec<String>,
+    dropped: Arc<AtomicBool>,
+}
+
+impl Drop for Words {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::SeqCst);
+    }
+}
+
+#[duchess::java_peer(java.util.Iterator)]
+impl java::util::Iterator<java::lang::String> for Words {
+    fn has_next(&self) -> bool {
+        !self.words.is_empty()
+    }
+
+    fn next(&mut self) -> String {
+        self.words.remove(0)
+    }
+}
+
+struct Empty;
+
+#[duchess::java_peer(java.util.Iterator)]
+impl java::util::Iterator<java::lang::String> for Empty {
+    fn has_next(&self) -> bool {
+        false
+    }
+
+    fn next(&self) -> duchess::GlobalResult<String> {
+        Err(duchess::Error::NullDeref)
+    }
+}
+
+fn words(words: &[&str]) -> (RustObject<Words>, Arc<AtomicBool>) {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let words = Words {
+        words: words.iter().map(|w| w.to_string()).collect(),
+        dropped: dropped.clone(),
+    };
+    (RustObject::new(words), dropped)
+}
+
+#[test]
+fn iterate_from_rust() {
+    let (words, _) = words(&["a", "b"]);
+    let iterator = words
+        .to_java::<java::util::Iterator<java::lang::String>>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap();
+
+    let mut collected = vec![];
+    while iterator.has_next().execute().unwrap() {
+        let word: String = iterator
+            .next()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        collected.push(word);
+    }
+    assert_eq!(collected, ["a", "b"]);
+}
+
+#[test]
+fn close_drops_value() {
+    let (words, dropped) = words(&["a"]);
+    Jvm::with(|jvm| {
+        let iterator = words
+            .to_java::<java::util::Iterator<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        drop(words);
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        // The peer is an `AutoCloseable`
+        let closeable = (&iterator)
+            .upcast::<java::lang::Object>()
+            .try_downcast::<java::lang::AutoCloseable>()
+            .execute_with(jvm)?
+            .unwrap_or_else(|_| panic!("peer should be `AutoCloseable`"));
+        closeable.close().execute_with(jvm)?;
+        assert!(dropped.load(Ordering::SeqCst));
+
+        // Closing again does nothing, but calling other methods throws
+        closeable.close().execute_with(jvm)?;
+        iterator
+            .has_next()
+            .catch::<java::lang::IllegalStateException>()
+            .execute_with(jvm)?
+            .expect_err("closed object should throw");
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn garbage_collection_drops_value() {
+    let (words, dropped) = words(&["a"]);
+    let iterator = words
+        .to_java::<java::util::Iterator<java::lang::String>>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap();
+    drop((words, iterator));
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !dropped.load(Ordering::SeqCst) {
+        assert!(Instant::now() < deadline, "value was not dropped");
+        java::lang::System::gc().execute().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn with_requires_a_peer() {
+    Jvm::with(|jvm| {
+        let (words, _) = words(&["a", "b"]);
+        let iterator = words
+            .to_java::<java::util::Iterator<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let remaining = RustObject::<Words>::with(jvm, &*iterator, |_, words| words.words.len())?;
+        assert_eq!(remaining, 2);
+
+        // Mutable access is exclusive
+        RustObject::<Words>::with(jvm, &*iterator, |jvm, _| {
+            iterator
+                .next()
+                .catch::<java::lang::IllegalStateException>()
+                .execute_with(jvm)
+        })??
+        .expect_err("iterator is borrowed");
+
+        // Shared access can be nested
+        let nested = RustObject::<Words>::with(jvm, &*iterator, |jvm, _| {
+            iterator.has_next().execute_with(jvm)
+        })??;
+        assert!(nested);
+
+        // Other iterators are not peers of `Words`
+        let other = RustObject::new(Empty)
+            .to_java::<java::util::Iterator<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let err = RustObject::<Words>::with(jvm, &*other, |_, _| ()).unwrap_err();
+        assert!(matches!(err, duchess::Error::Thrown(_)));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn other_threads_wait_for_mutable_access() {
+    let (words, _) = words(&["a"]);
+    let iterator: Global<java::util::Iterator<java::lang::String>> = words
+        .to_java::<java::util::Iterator<java::lang::String>>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap();
+    let (started, wait_for_start) = std::sync::mpsc::channel();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            Jvm::with(|jvm| {
+                RustObject::<Words>::with_mut(jvm, &*iterator, |_, words| {
+                    started.send(()).unwrap();
+                    std::thread::sleep(Duration::from_millis(100));
+                    words.words.push("b".to_string());
+                })
+            })
+            .unwrap();
+        });
+
+        // The call waits for the other thread instead of throwing, so it sees both words
+        wait_for_start.recv().unwrap();
+        let remaining = Jvm::with(|jvm| {
+            RustObject::<Words>::with(jvm, &*iterator, |_, words| words.words.len())
+        })
+        .unwrap();
+        assert_eq!(remaining, 2);
+    });
+}
@@ -0,0 +1,104 @@
+use duchess::{java, prelude::*, IntoRust, Jvm};
+
+#[test]
+fn boxed_scalars_round_trip() {
+    Jvm::with(|jvm| {
+        let int: i32 = 42
+            .to_java::<java::lang::Integer>()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(int, 42);
+
+        let double: f64 = 1.5
+            .to_java::<java::lang::Double>()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(double, 1.5);
+
+        let boolean: bool = true
+            .to_java::<java::lang::Boolean>()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert!(boolean);
+
+        // `null` boxes map to `None`
+        let long: Option<i64> = None::<i64>
+            .to_java::<java::lang::Long>()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(long, None);
+        let long: Option<i64> = Some(7i64)
+            .to_java::<java::lang::Long>()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(long, Some(7));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn boxed_list_elements() {
+    Jvm::with(|jvm| {
+        let list = vec![1i32, 2, 3]
+            .to_java::<java::util::List<java::lang::Integer>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let second: i32 = list.get(1).assert_not_null().to_rust().execute_with(jvm)?;
+        assert_eq!(second, 2);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn chars() {
+    Jvm::with(|jvm| {
+        let c: char = 'é'
+            .to_java::<java::lang::Character>()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(c, 'é');
+
+        // Characters outside of the BMP don't fit in a Java `char`...
+        '🦀'
+            .to_java::<java::lang::Character>()
+            .catch::<java::lang::IllegalArgumentException>()
+            .execute_with(jvm)?
+            .expect_err("a crab is two Java chars");
+
+        // ...and neither half of their surrogate pair is a Rust `char`
+        let err = IntoRust::<char>::into_rust(0xd83eu16, jvm).unwrap_err();
+        assert!(matches!(err, duchess::Error::Thrown(_)));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn floats_and_bytes() {
+    Jvm::with(|jvm| {
+        let floats: Vec<f32> = vec![0.5f32, -2.0]
+            .to_java::<java::Array<f32>>()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(floats, [0.5, -2.0]);
+
+        let bytes: Vec<u8> = vec![0u8, 127, 128, 255]
+            .to_java::<java::Array<i8>>()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(bytes, [0, 127, 128, 255]);
+
+        Ok(())
+    })
+    .unwrap();
+}
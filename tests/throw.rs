@@ -0,0 +1,60 @@
+use duchess::{java, prelude::*, Error, Jvm};
+
+#[test]
+fn throw_new_with_message() {
+    Jvm::with(|jvm| {
+        let error = jvm.throw_new::<java::lang::IllegalArgumentException>("bad input");
+        let Error::Thrown(thrown) = error else {
+            panic!("expected an exception, got {error:?}");
+        };
+        let exception = (&*thrown)
+            .try_downcast::<java::lang::IllegalArgumentException>()
+            .execute_with(jvm)?
+            .expect("exception is an IllegalArgumentException");
+        let message: String = exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "bad input");
+        assert_eq!(thrown.snapshot().unwrap().message(), Some("bad input"));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn rethrow_global_exception() {
+    let exception = java::lang::UnsupportedOperationException::new("stale")
+        .upcast::<java::lang::Throwable>()
+        .global()
+        .execute()
+        .unwrap();
+
+    let error = Jvm::with(|jvm| jvm.throw::<()>(&exception)).unwrap_err();
+    let Error::Thrown(thrown) = &error else {
+        panic!("expected an exception, got {error}");
+    };
+    assert_eq!(
+        error.to_string(),
+        "Java invocation threw: java.lang.UnsupportedOperationException: stale"
+    );
+    assert!(thrown.equals(&exception).execute().unwrap());
+}
+
+#[test]
+fn throw_op() {
+    let result: duchess::GlobalResult<String> =
+        java::lang::IllegalStateException::new().throw().execute();
+    let Err(Error::Thrown(thrown)) = result else {
+        panic!("expected an exception, got {result:?}");
+    };
+    assert_eq!(
+        thrown.snapshot().unwrap().class_name(),
+        "java.lang.IllegalStateException"
+    );
+
+    // Throwing `null` is an error of its own
+    let result = Jvm::with(|jvm| jvm.throw::<()>(&None::<duchess::Local<java::lang::Throwable>>));
+    assert!(matches!(result, Err(Error::NullDeref)));
+}
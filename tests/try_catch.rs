@@ -0,0 +1,73 @@
+use duchess::{java, prelude::*, Caught2, Error, Jvm};
+
+#[test]
+fn catch_any() {
+    Jvm::with(|jvm| {
+        let list = java::util::ArrayList::<java::lang::Object>::new().execute_with(jvm)?;
+
+        let caught = list
+            .get(0)
+            .catch_any::<(
+                java::lang::UnsupportedOperationException,
+                java::lang::IndexOutOfBoundsException,
+            )>()
+            .execute_with(jvm)?;
+        assert!(matches!(caught, Err(Caught2::Second(_))));
+
+        // The classes are tried in order, like the clauses of a Java `try`
+        let caught = list
+            .get(0)
+            .catch_any::<(
+                java::lang::RuntimeException,
+                java::lang::IndexOutOfBoundsException,
+            )>()
+            .execute_with(jvm)?;
+        assert!(matches!(caught, Err(Caught2::First(_))));
+
+        // Other exceptions are not caught
+        let result = list
+            .get(0)
+            .catch_any::<(
+                java::lang::NullPointerException,
+                java::lang::IllegalStateException,
+            )>()
+            .execute_with(jvm);
+        assert!(matches!(result, Err(Error::Thrown(_))));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn finally() {
+    Jvm::with(|jvm| {
+        let list = java::util::ArrayList::<java::lang::Object>::new().execute_with(jvm)?;
+        let log = java::util::ArrayList::<java::lang::Object>::new().execute_with(jvm)?;
+
+        // The cleanup runs after success...
+        let size = list.size().finally(log.add("cleanup")).execute_with(jvm)?;
+        assert_eq!(size, 0);
+        assert_eq!(log.size().execute_with(jvm)?, 1);
+
+        // ...and after an exception, which is still returned
+        let caught = list
+            .get(0)
+            .finally(log.add("cleanup"))
+            .catch::<java::lang::IndexOutOfBoundsException>()
+            .execute_with(jvm)?;
+        assert!(caught.is_err());
+        assert_eq!(log.size().execute_with(jvm)?, 2);
+
+        // An exception thrown by the cleanup replaces the outcome
+        let caught = list
+            .size()
+            .finally(list.get(0))
+            .catch::<java::lang::IndexOutOfBoundsException>()
+            .execute_with(jvm)?;
+        assert!(caught.is_err());
+
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use duchess::{java, prelude::*, Error, IteratorView, Jvm, ListView, MapView};
+
+#[test]
+fn iterator_view_is_lazy() {
+    Jvm::with(|jvm| {
+        let produced = Arc::new(AtomicUsize::new(0));
+        let counter = produced.clone();
+        let view = IteratorView::new((0..).map(move |n: i64| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            n * 2
+        }));
+
+        let iterator = view
+            .to_java::<java::util::Iterator<java::lang::Long>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let first: Vec<i64> = iterator
+            .iter()
+            .execute_with(jvm)?
+            .to_rust()
+            .with_jvm(jvm)
+            .take(3)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(first, [0, 2, 4]);
+        assert_eq!(produced.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn iterator_view_as_stream() {
+    Jvm::with(|jvm| {
+        let words = IteratorView::new(["a", "b", "a", "c"].map(String::from));
+        let distinct = words
+            .to_java::<java::util::stream::Stream<java::lang::String>>()
+            .distinct()
+            .count()
+            .execute_with(jvm)?;
+        assert_eq!(distinct, 3);
+
+        // An infinite iterator is fine, as long as Java only asks for a bounded number of items
+        let count = IteratorView::new(0i32..)
+            .to_java::<java::util::stream::Stream<java::lang::Integer>>()
+            .skip(10i64)
+            .limit(5i64)
+            .count()
+            .execute_with(jvm)?;
+        assert_eq!(count, 5);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn list_view() {
+    Jvm::with(|jvm| {
+        let view = ListView::new(vec!["x".to_string(), "y".to_string(), "z".to_string()]);
+        let list = view
+            .to_java::<java::util::List<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(list.size().execute_with(jvm)?, 3);
+
+        let second: String = list.get(1).assert_not_null().to_rust().execute_with(jvm)?;
+        assert_eq!(second, "y");
+        assert!(list.contains("z").execute_with(jvm)?);
+
+        let back: Vec<String> = (&*list).to_rust().execute_with(jvm)?;
+        assert_eq!(back, ["x", "y", "z"]);
+
+        // The view is read-only
+        assert!(matches!(
+            list.add("w").execute_with(jvm),
+            Err(Error::Thrown(_))
+        ));
+        assert!(matches!(
+            list.get(3).execute_with(jvm),
+            Err(Error::Thrown(_))
+        ));
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn map_view() {
+    Jvm::with(|jvm| {
+        let ages = HashMap::from([("ada".to_string(), 36i32), ("grace".to_string(), 85)]);
+        let map = MapView::new(ages.clone())
+            .to_java::<java::util::Map<java::lang::String, java::lang::Integer>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(map.size().execute_with(jvm)?, 2);
+
+        let age: Option<i32> = map.get("grace").to_rust().execute_with(jvm)?;
+        assert_eq!(age, Some(85));
+        let age: Option<i32> = map.get("linus").to_rust().execute_with(jvm)?;
+        assert_eq!(age, None);
+        assert!(map.contains_key("ada").execute_with(jvm)?);
+
+        // Looking up a key of another class finds nothing
+        let number = java::lang::Integer::value_of(36).execute_with(jvm)?;
+        assert!(!map.contains_key(&number).execute_with(jvm)?);
+
+        // Each conversion iterates over the keys of the map afresh
+        for _ in 0..2 {
+            let back: HashMap<String, i32> = (&*map).to_rust().execute_with(jvm)?;
+            assert_eq!(back, ages);
+        }
+
+        assert!(matches!(
+            map.put("alan", &number).execute_with(jvm),
+            Err(Error::Thrown(_))
+        ));
+
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,79 @@
+use duchess::{java, prelude::*, Error, Jvm, Local};
+
+#[test]
+fn closes_global_resource() {
+    let reader = java::io::StringReader::new("ab")
+        .global()
+        .execute()
+        .unwrap();
+
+    let first = (&reader)
+        .with_resource(|reader, jvm| reader.read().execute_with(jvm))
+        .execute()
+        .unwrap();
+    assert_eq!(first, 'a' as i32);
+
+    // The reader was closed
+    Jvm::with(|jvm| {
+        let io_exception = reader
+            .read()
+            .catch_declared()
+            .execute_with(jvm)?
+            .expect_err("reading a closed reader should throw");
+        let message: String = io_exception
+            .get_message()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(message, "Stream closed");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn closes_local_resource_on_error() {
+    Jvm::with(|jvm| {
+        let result = java::io::StringReader::new("ab")
+            .with_resource(|reader, jvm| {
+                reader.close().execute_with(jvm)?;
+                reader.read().execute_with(jvm)
+            })
+            .execute_with(jvm);
+        let Err(Error::Thrown(thrown)) = result else {
+            panic!("expected an exception, got {result:?}");
+        };
+        let snapshot = thrown.snapshot().unwrap();
+        assert_eq!(snapshot.class_name(), "java.io.IOException");
+        assert!(snapshot.suppressed().is_empty());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn null_resource_is_not_closed() {
+    let nothing: Option<duchess::Global<java::io::StringReader>> = None;
+    let result = (&nothing)
+        .with_resource(|reader, _jvm| Ok(reader.is_none()))
+        .execute()
+        .unwrap();
+    assert!(result);
+}
+
+#[test]
+fn body_returns_local_and_captures_owned_values() {
+    let mut chars_read = vec![];
+    Jvm::with(|jvm| {
+        let text: Option<Local<'_, java::lang::String>> =
+            jvm.with_resource(java::io::StringReader::new("ab"), |reader, jvm| {
+                chars_read.push(reader.read().execute_with(jvm)?);
+                String::from("read").to_java().execute_with(jvm)
+            })?;
+        let text: String = (&*text.unwrap()).to_rust().execute_with(jvm)?;
+        assert_eq!(text, "read");
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(chars_read, ['a' as i32]);
+}